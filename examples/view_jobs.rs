@@ -2,7 +2,7 @@
 // View and search for job listings on Nostr (kind 39993)
 
 use nostr_sdk::prelude::*;
-use nosjob::JobsFilter;
+use nosjob::{JobsFilter, RelayConfig};
 use anyhow::Result;
 
 #[tokio::main]
@@ -12,16 +12,14 @@ async fn main() -> Result<()> {
     // Create a client (no keys needed for reading)
     let client = Client::default();
 
-    // Connect to relays
-    let relays = vec![
-        "wss://relay.damus.io",
-        "wss://relay.nostr.band",
-        "wss://nos.lol",
-    ];
+    // Connect to the configured read relays (defaults if there's no config file)
+    let config = RelayConfig::load(
+        std::env::var("NOSJOB_RELAYS").unwrap_or_else(|_| "nosjob-relays.json".to_string()),
+    )?;
 
     println!("📡 Connecting to relays...");
-    for relay in &relays {
-        client.add_relay(*relay).await?;
+    for relay in &config.read {
+        client.add_relay(relay).await?;
         println!("   • {}", relay);
     }
     client.connect().await;
@@ -66,7 +64,7 @@ async fn main() -> Result<()> {
             println!("   🏢 Company: {}", company);
         }
         
-        if let Some(location) = find_tag_value(&tags, "location") {
+        if let Some(location) = find_tag_value(&tags, "location-type") {
             println!("   📍 Location: {}", location);
         }
         
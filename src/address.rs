@@ -0,0 +1,174 @@
+// src/address.rs
+// Canonical addressing for parameterised-replaceable job events
+// A job is identified by (kind, pubkey, d); the d tag is the listing identifier
+
+use crate::events::KIND_JOB_LISTING;
+use crate::types::JobListing;
+use anyhow::{anyhow, Result};
+use nostr_sdk::prelude::*;
+use std::fmt;
+
+/// Address of a kind 39993 (or other addressable) event: `<kind>:<pubkey>:<d>`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct JobAddress {
+    pub kind: Kind,
+    pub pubkey: PublicKey,
+    pub d: String,
+}
+
+impl JobAddress {
+    /// Address of a job listing published by `pubkey`
+    pub fn new(pubkey: PublicKey, d: &str) -> Self {
+        Self {
+            kind: Kind::from(KIND_JOB_LISTING),
+            pubkey,
+            d: d.to_string(),
+        }
+    }
+
+    /// Address of an addressable event, taken from its kind, author and d tag
+    pub fn from_event(event: &Event) -> Result<Self> {
+        if !event.kind.is_addressable() {
+            return Err(anyhow!("Event kind {} is not addressable", event.kind));
+        }
+        let d = event
+            .tags
+            .identifier()
+            .ok_or_else(|| anyhow!("Missing identifier tag"))?;
+        Ok(Self {
+            kind: event.kind,
+            pubkey: event.pubkey,
+            d: d.to_string(),
+        })
+    }
+
+    /// Parse `<kind>:<pubkey>:<d>`, `naddr1...` or `nostr:naddr1...`
+    pub fn parse(s: &str) -> Result<Self> {
+        Ok(Coordinate::parse(s)?.into())
+    }
+
+    /// Encode as a NIP-19 `naddr`, optionally with relay hints
+    pub fn to_naddr(&self, relays: &[RelayUrl]) -> Result<String> {
+        let coordinate = Nip19Coordinate::new(self.to_coordinate(), relays.iter().cloned());
+        Ok(coordinate.to_bech32()?)
+    }
+
+    /// Decode a NIP-19 `naddr`, returning the address and its relay hints
+    pub fn from_naddr(naddr: &str) -> Result<(Self, Vec<RelayUrl>)> {
+        let decoded = Nip19Coordinate::from_bech32(naddr)?;
+        Ok((decoded.coordinate.into(), decoded.relays))
+    }
+
+    pub fn to_coordinate(&self) -> Coordinate {
+        Coordinate::new(self.kind, self.pubkey).identifier(&self.d)
+    }
+
+    /// `a` tag referencing this address
+    pub fn to_tag(&self) -> Tag {
+        Tag::coordinate(self.to_coordinate(), None)
+    }
+
+    /// Filter for the current version of the addressed event
+    pub fn filter(&self) -> Filter {
+        Filter::new()
+            .kind(self.kind)
+            .author(self.pubkey)
+            .identifier(&self.d)
+    }
+}
+
+impl From<Coordinate> for JobAddress {
+    fn from(coordinate: Coordinate) -> Self {
+        Self {
+            kind: coordinate.kind,
+            pubkey: coordinate.public_key,
+            d: coordinate.identifier,
+        }
+    }
+}
+
+impl fmt::Display for JobAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.kind, self.pubkey, self.d)
+    }
+}
+
+impl JobListing {
+    /// Canonical `d` tag: the listing identifier, unchanged.
+    /// Re-encoding the same listing always addresses the same event.
+    pub fn d_tag(&self) -> &str {
+        &self.identifier
+    }
+
+    /// Address this listing has when published by `pubkey`
+    pub fn address(&self, pubkey: PublicKey) -> JobAddress {
+        JobAddress::new(pubkey, self.d_tag())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures;
+
+    fn sample_job() -> JobListing {
+        test_fixtures::sample_job("acme-senior-rust-dev-a3f7c8d1")
+    }
+
+    #[test]
+    fn test_reencoding_keeps_d_tag() {
+        let keys = Keys::generate();
+        let job = sample_job();
+        let first = job.to_nostr_event(&keys).unwrap();
+
+        let mut edited = JobListing::from_nostr_event(&first).unwrap();
+        edited.title = "Staff Rust Developer".to_string();
+        edited.hiring_organization.name = "Acme Corporation".to_string();
+        let second = edited.to_nostr_event(&keys).unwrap();
+
+        assert_eq!(first.tags.identifier(), Some(job.d_tag()));
+        assert_eq!(JobAddress::from_event(&first).unwrap(), JobAddress::from_event(&second).unwrap());
+        assert_eq!(JobAddress::from_event(&second).unwrap(), job.address(keys.public_key()));
+    }
+
+    #[test]
+    fn test_legacy_d_tag_is_authoritative() {
+        let keys = Keys::generate();
+        let job = sample_job();
+        let legacy = EventBuilder::new(Kind::from(KIND_JOB_LISTING), serde_json::to_string(&job).unwrap())
+            .tag(Tag::identifier("acme-corp-acme-senior-rust-dev-a3f7c8d1-8a7c2f1d"))
+            .sign_with_keys(&keys)
+            .unwrap();
+
+        let decoded = JobListing::from_nostr_event(&legacy).unwrap();
+        let republished = decoded.to_nostr_event(&keys).unwrap();
+        assert_eq!(JobAddress::from_event(&legacy).unwrap(), JobAddress::from_event(&republished).unwrap());
+    }
+
+    #[test]
+    fn test_naddr_round_trip() {
+        let keys = Keys::generate();
+        let address = sample_job().address(keys.public_key());
+        let relay = RelayUrl::parse("wss://relay.damus.io").unwrap();
+
+        let naddr = address.to_naddr(std::slice::from_ref(&relay)).unwrap();
+        assert!(naddr.starts_with("naddr1"));
+
+        let (decoded, relays) = JobAddress::from_naddr(&naddr).unwrap();
+        assert_eq!(decoded, address);
+        assert_eq!(relays, vec![relay]);
+        assert_eq!(JobAddress::parse(&naddr).unwrap(), address);
+        assert_eq!(JobAddress::parse(&address.to_string()).unwrap(), address);
+    }
+
+    #[test]
+    fn test_address_filter_matches_event() {
+        let keys = Keys::generate();
+        let job = sample_job();
+        let event = job.to_nostr_event(&keys).unwrap();
+
+        let filter = job.address(keys.public_key()).filter();
+        assert!(filter.match_event(&event, MatchEventOptions::new()));
+        assert!(!JobAddress::new(keys.public_key(), "other").filter().match_event(&event, MatchEventOptions::new()));
+    }
+}
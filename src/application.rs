@@ -0,0 +1,374 @@
+// src/application.rs
+// Kind 39995: Job applications referencing a kind 39993 listing
+// Content may be plaintext JSON or NIP-44 encrypted to the employer
+
+use crate::address::JobAddress;
+use crate::events::{KIND_JOB_APPLICATION, KIND_JOB_LISTING};
+use crate::payment::PaymentProof;
+use crate::signer::SignEvent;
+use crate::types::ValidationError;
+use anyhow::{anyhow, Result};
+use nostr_sdk::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Job application (kind 39995)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct JobApplication {
+    #[serde(rename = "type")]
+    pub application_type: String, // "application"
+    pub version: u32,
+
+    pub message: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resume_ref: Option<String>, // Resume event id
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cover_letter_url: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub portfolio_url: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payment_proof: Option<PaymentProof>, // Optional anti-spam payment
+
+    // === Extensions ===
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+
+    // === From tags / event (not part of content) ===
+    #[serde(skip)]
+    pub job_event_id: Option<EventId>, // "e" tag
+
+    #[serde(skip)]
+    pub job_address: Option<JobAddress>, // "a" tag
+
+    #[serde(skip)]
+    pub employer_pubkey: Option<PublicKey>, // "p" tag
+
+    #[serde(skip)]
+    pub applicant_pubkey: Option<PublicKey>, // Event author, set on decode
+}
+
+impl JobApplication {
+    pub fn new(message: String) -> Self {
+        Self {
+            application_type: "application".to_string(),
+            version: 1,
+            message,
+            resume_ref: None,
+            cover_letter_url: None,
+            portfolio_url: None,
+            payment_proof: None,
+            extra: HashMap::new(),
+            job_event_id: None,
+            job_address: None,
+            employer_pubkey: None,
+            applicant_pubkey: None,
+        }
+    }
+
+    pub fn builder(message: String) -> JobApplicationBuilder {
+        JobApplicationBuilder::new(message)
+    }
+
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        if self.application_type != "application" {
+            return Err(ValidationError::InvalidConfiguration(
+                "application type must be \"application\"",
+            ));
+        }
+        if self.message.is_empty() {
+            return Err(ValidationError::MissingField("message"));
+        }
+        if self.job_event_id.is_none() {
+            return Err(ValidationError::MissingField("job_event_id"));
+        }
+        if self.employer_pubkey.is_none() {
+            return Err(ValidationError::MissingField("employer_pubkey"));
+        }
+        if !is_http_url(self.cover_letter_url.as_deref()) {
+            return Err(ValidationError::InvalidUrl("cover_letter_url"));
+        }
+        if !is_http_url(self.portfolio_url.as_deref()) {
+            return Err(ValidationError::InvalidUrl("portfolio_url"));
+        }
+        Ok(())
+    }
+
+    /// Convert to a plaintext kind 39995 event
+    pub fn to_nostr_event(&self, keys: &Keys) -> Result<Event> {
+        Ok(self.unsigned_event(keys.public_key())?.sign_with_keys(keys)?)
+    }
+
+    /// Convert to a kind 39995 event with content NIP-44 encrypted to the employer
+    pub fn to_encrypted_nostr_event(&self, keys: &Keys) -> Result<Event> {
+        self.validate()?;
+        let employer = self
+            .employer_pubkey
+            .ok_or_else(|| anyhow!("Missing employer pubkey"))?;
+        let content = nip44::encrypt(
+            keys.secret_key(),
+            &employer,
+            serde_json::to_string(self)?,
+            nip44::Version::V2,
+        )?;
+        Ok(self.build(content, true, keys.public_key())?.sign_with_keys(keys)?)
+    }
+
+    /// `to_encrypted_nostr_event` for any `NostrSigner`; the signer does
+    /// the NIP-44 encryption, so a remote signer never reveals the key
+    pub async fn encrypted_sign_with<S>(&self, signer: &S) -> Result<Event>
+    where
+        S: NostrSigner + ?Sized,
+    {
+        self.validate()?;
+        let employer = self
+            .employer_pubkey
+            .ok_or_else(|| anyhow!("Missing employer pubkey"))?;
+        let content = signer.nip44_encrypt(&employer, &serde_json::to_string(self)?).await?;
+        let author = signer.get_public_key().await?;
+        Ok(signer.sign_event(self.build(content, true, author)?).await?)
+    }
+
+    /// `d` tag: kind 39995 is addressable, so each applicant keeps one
+    /// application per job and a resubmission replaces the previous one
+    pub fn d_tag(&self) -> Option<String> {
+        self.job_address
+            .as_ref()
+            .map(|address| address.to_string())
+            .or_else(|| self.job_event_id.map(|id| id.to_hex()))
+    }
+
+    fn build(&self, content: String, encrypted: bool, author: PublicKey) -> Result<UnsignedEvent> {
+        let d = self.d_tag().ok_or_else(|| anyhow!("Missing job reference"))?;
+        let mut tags = vec![Tag::identifier(d)];
+        if let Some(id) = self.job_event_id {
+            tags.push(Tag::event(id));
+        }
+        if let Some(employer) = self.employer_pubkey {
+            tags.push(Tag::public_key(employer));
+        }
+        if let Some(address) = &self.job_address {
+            tags.push(address.to_tag());
+        }
+        if encrypted {
+            tags.push(Tag::custom(TagKind::Custom("encrypted".into()), vec!["nip44"]));
+        }
+
+        Ok(EventBuilder::new(Kind::from(KIND_JOB_APPLICATION), content)
+            .tags(tags)
+            .build(author))
+    }
+
+    /// Parse a plaintext kind 39995 event
+    pub fn from_nostr_event(event: &Event) -> Result<Self> {
+        Self::check_kind(event)?;
+        if Self::is_encrypted(event) {
+            return Err(anyhow!("Application is encrypted; use decrypt_nostr_event"));
+        }
+        Self::from_content(event, &event.content)
+    }
+
+    /// Parse a kind 39995 event, decrypting with the employer's keys if needed
+    pub fn decrypt_nostr_event(event: &Event, employer_keys: &Keys) -> Result<Self> {
+        Self::check_kind(event)?;
+        if !Self::is_encrypted(event) {
+            return Self::from_content(event, &event.content);
+        }
+        let content = nip44::decrypt(employer_keys.secret_key(), &event.pubkey, &event.content)?;
+        Self::from_content(event, &content)
+    }
+
+    /// `decrypt_nostr_event` for any `NostrSigner`
+    pub async fn decrypt_with<S>(event: &Event, signer: &S) -> Result<Self>
+    where
+        S: NostrSigner + ?Sized,
+    {
+        Self::check_kind(event)?;
+        if !Self::is_encrypted(event) {
+            return Self::from_content(event, &event.content);
+        }
+        let content = signer.nip44_decrypt(&event.pubkey, &event.content).await?;
+        Self::from_content(event, &content)
+    }
+
+    /// Whether the content is NIP-44 encrypted
+    pub fn is_encrypted(event: &Event) -> bool {
+        event
+            .tags
+            .iter()
+            .any(|t| matches!(t.as_slice(), [name, ..] if name == "encrypted"))
+    }
+
+    fn check_kind(event: &Event) -> Result<()> {
+        if event.kind != Kind::from(KIND_JOB_APPLICATION) {
+            return Err(anyhow!(
+                "Invalid event kind: expected {}, got {}",
+                KIND_JOB_APPLICATION,
+                event.kind
+            ));
+        }
+        Ok(())
+    }
+
+    fn from_content(event: &Event, content: &str) -> Result<Self> {
+        let mut application: JobApplication = serde_json::from_str(content)?;
+        application.job_event_id = event.tags.event_ids().next().copied();
+        application.employer_pubkey = event.tags.public_keys().next().copied();
+        application.job_address = event
+            .tags
+            .coordinates()
+            .find(|c| c.kind == Kind::from(KIND_JOB_LISTING))
+            .cloned()
+            .map(JobAddress::from);
+        application.applicant_pubkey = Some(event.pubkey);
+        application.validate()?;
+        Ok(application)
+    }
+}
+
+impl SignEvent for JobApplication {
+    /// Plaintext application; see `encrypted_sign_with` for the NIP-44 form
+    fn unsigned_event(&self, author: PublicKey) -> Result<UnsignedEvent> {
+        self.validate()?;
+        self.build(serde_json::to_string(self)?, false, author)
+    }
+}
+
+fn is_http_url(url: Option<&str>) -> bool {
+    url.is_none_or(|u| u.starts_with("https://") || u.starts_with("http://"))
+}
+
+/// Builder pattern
+pub struct JobApplicationBuilder {
+    application: JobApplication,
+}
+
+impl JobApplicationBuilder {
+    pub fn new(message: String) -> Self {
+        Self {
+            application: JobApplication::new(message),
+        }
+    }
+
+    /// Reference a job listing event. The employer is the listing's
+    /// `employer-pubkey` tag (npub or hex), falling back to its author.
+    pub fn job(mut self, job_event: &Event) -> Self {
+        let employer = job_event
+            .tags
+            .iter()
+            .find_map(|t| match t.as_slice() {
+                [name, value, ..] if name == "employer-pubkey" => PublicKey::parse(value).ok(),
+                _ => None,
+            })
+            .unwrap_or(job_event.pubkey);
+
+        self.application.job_event_id = Some(job_event.id);
+        self.application.employer_pubkey = Some(employer);
+        self.application.job_address = JobAddress::from_event(job_event).ok();
+        self
+    }
+
+    pub fn resume_ref(mut self, event_id: String) -> Self {
+        self.application.resume_ref = Some(event_id);
+        self
+    }
+
+    pub fn cover_letter_url(mut self, url: String) -> Self {
+        self.application.cover_letter_url = Some(url);
+        self
+    }
+
+    pub fn portfolio_url(mut self, url: String) -> Self {
+        self.application.portfolio_url = Some(url);
+        self
+    }
+
+    pub fn payment_proof(mut self, proof: PaymentProof) -> Self {
+        self.application.payment_proof = Some(proof);
+        self
+    }
+
+    pub fn build(self) -> Result<JobApplication, ValidationError> {
+        self.application.validate()?;
+        Ok(self.application)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::sample_job;
+
+    fn job_event(employer: &Keys, declared: Option<&PublicKey>) -> Event {
+        let mut job = sample_job("job-001");
+        job.nostr_employer_pubkey = declared.map(|pk| pk.to_bech32().unwrap());
+        job.to_nostr_event(employer).unwrap()
+    }
+
+    fn application(job: &Event) -> JobApplication {
+        JobApplication::builder("I have 6 years of Rust experience.".to_string())
+            .job(job)
+            .portfolio_url("https://github.com/applicant".to_string())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_plaintext_round_trip() {
+        let employer = Keys::generate();
+        let applicant = Keys::generate();
+        let job = job_event(&employer, None);
+        let application = application(&job);
+
+        let event = application.to_nostr_event(&applicant).unwrap();
+        assert_eq!(event.kind, Kind::from(KIND_JOB_APPLICATION));
+        assert!(event.tags.event_ids().any(|id| *id == job.id));
+        assert!(event.tags.public_keys().any(|pk| *pk == employer.public_key()));
+
+        let address = JobAddress::from_event(&job).unwrap();
+        assert_eq!(event.tags.identifier(), Some(address.to_string().as_str()));
+
+        let decoded = JobApplication::from_nostr_event(&event).unwrap();
+        assert_eq!(decoded.applicant_pubkey, Some(applicant.public_key()));
+        assert_eq!(decoded.job_address, JobAddress::from_event(&job).ok());
+        assert_eq!(decoded.message, application.message);
+        assert_eq!(decoded.portfolio_url, application.portfolio_url);
+    }
+
+    #[test]
+    fn test_encrypted_to_declared_employer_pubkey() {
+        let poster = Keys::generate();
+        let employer = Keys::generate();
+        let applicant = Keys::generate();
+        let job = job_event(&poster, Some(&employer.public_key()));
+        let application = application(&job);
+        assert_eq!(application.employer_pubkey, Some(employer.public_key()));
+
+        let event = application.to_encrypted_nostr_event(&applicant).unwrap();
+        assert!(!event.content.contains("Rust experience"));
+        assert!(JobApplication::from_nostr_event(&event).is_err());
+        assert!(JobApplication::decrypt_nostr_event(&event, &poster).is_err());
+
+        let decoded = JobApplication::decrypt_nostr_event(&event, &employer).unwrap();
+        assert_eq!(decoded.message, application.message);
+        assert_eq!(decoded.applicant_pubkey, Some(applicant.public_key()));
+    }
+
+    #[test]
+    fn test_validation() {
+        assert!(JobApplication::builder("Hello".to_string()).build().is_err());
+
+        let job = job_event(&Keys::generate(), None);
+        assert!(JobApplication::builder(String::new()).job(&job).build().is_err());
+        assert!(
+            JobApplication::builder("Hello".to_string())
+                .job(&job)
+                .portfolio_url("github.com/applicant".to_string())
+                .build()
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,389 @@
+// src/bunker.rs
+// NIP-46 remote signing: requests go to a bunker as NIP-44 encrypted
+// kind 24133 events; the secret key never leaves the bunker.
+// Transport is pluggable: relays in production, in-process in tests
+
+use anyhow::{anyhow, Result};
+use nostr::nips::nip46::{
+    NostrConnectMessage, NostrConnectRequest, NostrConnectURI, ResponseResult,
+};
+use nostr_sdk::prelude::*;
+use nostr_sdk::util::BoxedFuture;
+use std::future::Future;
+use std::time::Duration;
+use tokio::sync::{broadcast, Mutex};
+
+/// Carries kind 24133 events between us and the bunker
+pub trait Nip46Transport: std::fmt::Debug + Send + Sync + 'static {
+    fn send(&self, event: Event) -> impl Future<Output = Result<()>> + Send;
+
+    /// Next kind 24133 event addressed to us (callers filter and decrypt)
+    fn recv(&self) -> impl Future<Output = Result<Event>> + Send;
+}
+
+/// Relay transport: publishes requests and listens for responses `p`-tagged
+/// to the requesting key
+#[derive(Debug)]
+pub struct RelayTransport {
+    client: Client,
+    notifications: Mutex<broadcast::Receiver<RelayPoolNotification>>,
+    subscribed: Mutex<Option<PublicKey>>,
+}
+
+impl RelayTransport {
+    pub async fn connect(relays: &[RelayUrl]) -> Result<Self> {
+        if relays.is_empty() {
+            return Err(anyhow!("Bunker URI lists no relays"));
+        }
+        let client = Client::default();
+        for relay in relays {
+            client.add_relay(relay).await?;
+        }
+        client.connect().await;
+        let notifications = Mutex::new(client.notifications());
+        Ok(Self {
+            client,
+            notifications,
+            subscribed: Mutex::new(None),
+        })
+    }
+}
+
+impl Nip46Transport for RelayTransport {
+    async fn send(&self, event: Event) -> Result<()> {
+        // Subscribe before the first request so no response is missed
+        let mut subscribed = self.subscribed.lock().await;
+        if *subscribed != Some(event.pubkey) {
+            let filter = Filter::new()
+                .kind(Kind::NostrConnect)
+                .pubkey(event.pubkey)
+                .since(Timestamp::now());
+            self.client.subscribe(filter, None).await?;
+            *subscribed = Some(event.pubkey);
+        }
+        self.client.send_event(&event).await?;
+        Ok(())
+    }
+
+    async fn recv(&self) -> Result<Event> {
+        let mut notifications = self.notifications.lock().await;
+        loop {
+            match notifications.recv().await {
+                Ok(RelayPoolNotification::Event { event, .. }) if event.kind == Kind::NostrConnect => {
+                    return Ok(*event);
+                }
+                Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(e) => return Err(anyhow!("Relay connection closed: {}", e)),
+            }
+        }
+    }
+}
+
+/// A `NostrSigner` backed by a NIP-46 bunker (`bunker://` URI)
+#[derive(Debug)]
+pub struct BunkerSigner<T: Nip46Transport> {
+    client_keys: Keys, // Throwaway key for the encrypted channel
+    remote_signer: PublicKey,
+    user: PublicKey,
+    transport: T,
+    timeout: Duration,
+    in_flight: Mutex<()>, // One request at a time on the shared transport
+}
+
+impl BunkerSigner<RelayTransport> {
+    /// Connect over the relays named in the URI
+    pub async fn connect_relays(uri: &str, timeout: Duration) -> Result<Self> {
+        let parsed = NostrConnectURI::parse(uri)?;
+        let transport = RelayTransport::connect(parsed.relays()).await?;
+        Self::connect(uri, transport, timeout).await
+    }
+}
+
+impl<T: Nip46Transport> BunkerSigner<T> {
+    /// Send `connect` (with the URI's secret, if any) and fetch the user's pubkey
+    pub async fn connect(uri: &str, transport: T, timeout: Duration) -> Result<Self> {
+        let uri = NostrConnectURI::parse(uri)?;
+        let remote_signer = match uri.remote_signer_public_key() {
+            Some(pubkey) if uri.is_bunker() => *pubkey,
+            _ => return Err(anyhow!("Expected a bunker:// URI")),
+        };
+
+        let mut signer = Self {
+            client_keys: Keys::generate(),
+            remote_signer,
+            user: remote_signer,
+            transport,
+            timeout,
+            in_flight: Mutex::new(()),
+        };
+        signer
+            .request(NostrConnectRequest::Connect {
+                remote_signer_public_key: remote_signer,
+                secret: uri.secret().map(str::to_string),
+            })
+            .await?;
+        signer.user = signer
+            .request(NostrConnectRequest::GetPublicKey)
+            .await?
+            .to_get_public_key()?;
+        Ok(signer)
+    }
+
+    async fn request(&self, request: NostrConnectRequest) -> Result<ResponseResult> {
+        let _guard = self.in_flight.lock().await;
+        let method = request.method();
+        let message = NostrConnectMessage::request(&request);
+        let id = message.id().to_string();
+        let event = EventBuilder::nostr_connect(&self.client_keys, self.remote_signer, message)?
+            .sign_with_keys(&self.client_keys)?;
+        self.transport.send(event).await?;
+
+        let response = tokio::time::timeout(self.timeout, async {
+            loop {
+                let event = self.transport.recv().await?;
+                if event.pubkey != self.remote_signer || event.verify().is_err() {
+                    continue;
+                }
+                let Ok(json) = nip44::decrypt(self.client_keys.secret_key(), &event.pubkey, &event.content) else {
+                    continue;
+                };
+                match NostrConnectMessage::from_json(json) {
+                    Ok(message) if message.is_response() && message.id() == id => {
+                        return Ok::<_, anyhow::Error>(message.to_response(method)?);
+                    }
+                    _ => continue,
+                }
+            }
+        })
+        .await
+        .map_err(|_| anyhow!("Bunker did not answer {} within {:?}", method, self.timeout))??;
+
+        if let Some(error) = response.error {
+            return Err(anyhow!("Bunker refused {}: {}", method, error));
+        }
+        match response.result {
+            Some(result) if result.is_auth_url() => Err(anyhow!("Bunker requires authorisation for {}", method)),
+            Some(result) => Ok(result),
+            None => Err(anyhow!("Empty {} response from bunker", method)),
+        }
+    }
+
+    /// Key the bunker signs as
+    pub fn user_public_key(&self) -> PublicKey {
+        self.user
+    }
+}
+
+fn signer_error(e: anyhow::Error) -> SignerError {
+    SignerError::from(e.to_string())
+}
+
+impl<T: Nip46Transport> NostrSigner for BunkerSigner<T> {
+    fn backend(&self) -> SignerBackend<'_> {
+        SignerBackend::NostrConnect
+    }
+
+    fn get_public_key(&self) -> BoxedFuture<'_, Result<PublicKey, SignerError>> {
+        Box::pin(async move { Ok(self.user) })
+    }
+
+    fn sign_event(&self, unsigned: UnsignedEvent) -> BoxedFuture<'_, Result<Event, SignerError>> {
+        Box::pin(async move {
+            if unsigned.pubkey != self.user {
+                return Err(SignerError::from("Event author is not the bunker's key"));
+            }
+            let expected = unsigned.clone();
+            let event = self
+                .request(NostrConnectRequest::SignEvent(unsigned))
+                .await
+                .and_then(|r| Ok(r.to_sign_event()?))
+                .map_err(signer_error)?;
+            // The bunker must sign exactly what we asked for
+            if event.pubkey != self.user
+                || event.kind != expected.kind
+                || event.content != expected.content
+                || event.tags != expected.tags
+                || event.created_at != expected.created_at
+            {
+                return Err(SignerError::from("Bunker signed a different event"));
+            }
+            event.verify().map_err(SignerError::backend)?;
+            Ok(event)
+        })
+    }
+
+    fn nip04_encrypt<'a>(&'a self, public_key: &'a PublicKey, content: &'a str) -> BoxedFuture<'a, Result<String, SignerError>> {
+        Box::pin(async move {
+            let request = NostrConnectRequest::Nip04Encrypt { public_key: *public_key, text: content.to_string() };
+            self.request(request).await.and_then(|r| Ok(r.to_nip04_encrypt()?)).map_err(signer_error)
+        })
+    }
+
+    fn nip04_decrypt<'a>(&'a self, public_key: &'a PublicKey, encrypted_content: &'a str) -> BoxedFuture<'a, Result<String, SignerError>> {
+        Box::pin(async move {
+            let request = NostrConnectRequest::Nip04Decrypt { public_key: *public_key, ciphertext: encrypted_content.to_string() };
+            self.request(request).await.and_then(|r| Ok(r.to_nip04_decrypt()?)).map_err(signer_error)
+        })
+    }
+
+    fn nip44_encrypt<'a>(&'a self, public_key: &'a PublicKey, content: &'a str) -> BoxedFuture<'a, Result<String, SignerError>> {
+        Box::pin(async move {
+            let request = NostrConnectRequest::Nip44Encrypt { public_key: *public_key, text: content.to_string() };
+            self.request(request).await.and_then(|r| Ok(r.to_nip44_encrypt()?)).map_err(signer_error)
+        })
+    }
+
+    fn nip44_decrypt<'a>(&'a self, public_key: &'a PublicKey, payload: &'a str) -> BoxedFuture<'a, Result<String, SignerError>> {
+        Box::pin(async move {
+            let request = NostrConnectRequest::Nip44Decrypt { public_key: *public_key, ciphertext: payload.to_string() };
+            self.request(request).await.and_then(|r| Ok(r.to_nip44_decrypt()?)).map_err(signer_error)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::JobApplication;
+    use crate::signer::SignEvent;
+    use crate::test_fixtures::sample_job;
+    use crate::types::JobListing;
+    use nostr::nips::nip46::NostrConnectResponse;
+    use std::collections::VecDeque;
+
+    /// In-process bunker: answers each request as it is sent
+    #[derive(Debug)]
+    struct LocalBunker {
+        keys: Keys,
+        secret: String,
+        stale_first: bool, // Queue an unrelated response ahead of each real one
+        outbox: std::sync::Mutex<VecDeque<Event>>,
+    }
+
+    impl LocalBunker {
+        fn new(keys: Keys, secret: &str) -> Self {
+            Self {
+                keys,
+                secret: secret.to_string(),
+                stale_first: false,
+                outbox: std::sync::Mutex::new(VecDeque::new()),
+            }
+        }
+
+        fn uri(&self, secret: &str) -> String {
+            format!("bunker://{}?relay=wss://relay.example.com&secret={}", self.keys.public_key().to_hex(), secret)
+        }
+
+        fn answer(&self, request: NostrConnectRequest) -> NostrConnectResponse {
+            let result = match request {
+                NostrConnectRequest::Connect { secret, .. } if secret.as_deref() == Some(&self.secret) => {
+                    ResponseResult::Ack
+                }
+                NostrConnectRequest::Connect { .. } => return NostrConnectResponse::with_error("invalid secret"),
+                NostrConnectRequest::GetPublicKey => ResponseResult::GetPublicKey(self.keys.public_key()),
+                NostrConnectRequest::SignEvent(unsigned) => {
+                    ResponseResult::SignEvent(Box::new(unsigned.sign_with_keys(&self.keys).unwrap()))
+                }
+                NostrConnectRequest::Nip44Encrypt { public_key, text } => ResponseResult::Nip44Encrypt {
+                    ciphertext: nip44::encrypt(self.keys.secret_key(), &public_key, text, nip44::Version::V2).unwrap(),
+                },
+                NostrConnectRequest::Ping => ResponseResult::Pong,
+                _ => return NostrConnectResponse::with_error("unsupported"),
+            };
+            NostrConnectResponse::with_result(result)
+        }
+
+        fn reply(&self, to: PublicKey, message: NostrConnectMessage) -> Event {
+            EventBuilder::nostr_connect(&self.keys, to, message)
+                .unwrap()
+                .sign_with_keys(&self.keys)
+                .unwrap()
+        }
+    }
+
+    impl Nip46Transport for LocalBunker {
+        async fn send(&self, event: Event) -> Result<()> {
+            let json = nip44::decrypt(self.keys.secret_key(), &event.pubkey, &event.content)?;
+            let message = NostrConnectMessage::from_json(json)?;
+            let id = message.id().to_string();
+            let response = self.answer(message.to_request()?);
+
+            let mut outbox = self.outbox.lock().unwrap();
+            if self.stale_first {
+                let stale = NostrConnectMessage::response("stale", NostrConnectResponse::with_error("old"));
+                outbox.push_back(self.reply(event.pubkey, stale));
+            }
+            outbox.push_back(self.reply(event.pubkey, NostrConnectMessage::response(id, response)));
+            Ok(())
+        }
+
+        async fn recv(&self) -> Result<Event> {
+            loop {
+                if let Some(event) = self.outbox.lock().unwrap().pop_front() {
+                    return Ok(event);
+                }
+                tokio::task::yield_now().await;
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sign_and_encrypt_through_bunker() {
+        let user = Keys::generate();
+        let mut bunker = LocalBunker::new(user.clone(), "s3cret");
+        bunker.stale_first = true;
+        let uri = bunker.uri("s3cret");
+        let signer = BunkerSigner::connect(&uri, bunker, Duration::from_secs(5)).await.unwrap();
+        assert_eq!(signer.user_public_key(), user.public_key());
+
+        let event = sample_job("job-001").sign_with(&signer).await.unwrap();
+        event.verify().unwrap();
+        assert_eq!(event.pubkey, user.public_key());
+        assert_eq!(JobListing::from_nostr_event(&event).unwrap(), sample_job("job-001"));
+
+        // NIP-44 encryption happens in the bunker too
+        let employer = Keys::generate();
+        let application = JobApplication::builder("Hello".to_string())
+            .job(&sample_job("job-001").to_nostr_event(&employer).unwrap())
+            .build()
+            .unwrap();
+        let encrypted = application.encrypted_sign_with(&signer).await.unwrap();
+        let decrypted = JobApplication::decrypt_nostr_event(&encrypted, &employer).unwrap();
+        assert_eq!(decrypted.message, "Hello");
+        assert_eq!(decrypted.applicant_pubkey, Some(user.public_key()));
+    }
+
+    #[tokio::test]
+    async fn test_rejected_connections() {
+        let bunker = LocalBunker::new(Keys::generate(), "s3cret");
+        let uri = bunker.uri("wrong");
+        let err = BunkerSigner::connect(&uri, bunker, Duration::from_secs(5)).await.unwrap_err();
+        assert!(err.to_string().contains("invalid secret"));
+
+        let bunker = LocalBunker::new(Keys::generate(), "s3cret");
+        let client_uri = NostrConnectURI::client(
+            Keys::generate().public_key(),
+            [RelayUrl::parse("wss://relay.example.com").unwrap()],
+            "nosjob",
+        );
+        assert!(BunkerSigner::connect(&client_uri.to_string(), bunker, Duration::from_secs(5)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_unanswered_request_times_out() {
+        #[derive(Debug)]
+        struct Silent;
+        impl Nip46Transport for Silent {
+            async fn send(&self, _event: Event) -> Result<()> {
+                Ok(())
+            }
+            async fn recv(&self) -> Result<Event> {
+                std::future::pending().await
+            }
+        }
+
+        let uri = format!("bunker://{}?relay=wss://relay.example.com", Keys::generate().public_key().to_hex());
+        let err = BunkerSigner::connect(&uri, Silent, Duration::from_millis(50)).await.unwrap_err();
+        assert!(err.to_string().contains("did not answer"));
+    }
+}
@@ -2,49 +2,88 @@
 // Converts JobListing to/from Nostr events
 // Kind 39993: Job Listings with UUID support
 
+use crate::signer::SignEvent;
 use crate::types::*;
+use crate::versioning::MigrationRegistry;
 use nostr_sdk::prelude::*;
 use anyhow::{anyhow, Result};
 
 // ==================== Job Listing Kind Constants ====================
 pub const KIND_JOB_LISTING: u16 = 39993;  // Job posting
-pub const KIND_JOB_APPLICATION: u16 = 39994;  // Job application (future use)
+pub const KIND_PROFESSIONAL_PROOF: u16 = 39994;  // Employment proof
+pub const KIND_JOB_APPLICATION: u16 = 39995;  // Job application
+pub const KIND_EMPLOYER_VERIFICATION: u16 = 39996;  // Employer verification
+pub const KIND_SKILL_EDGE: u16 = 39997;  // Skill graph edge
+
+// ==================== Relay-Indexable Tag Mirrors ====================
+// Relays only index single-letter tags, so every field JobsFilter can query
+// is mirrored into one next to the NIP's multi-letter tag. Mirror values are
+// normalised with `index_value` on both the encode and the filter side.
+pub const INDEX_COMPANY: Alphabet = Alphabet::C;          // mirrors "company"
+pub const INDEX_EMPLOYMENT_TYPE: Alphabet = Alphabet::J;  // mirrors "employment-type"
+pub const INDEX_SKILL: Alphabet = Alphabet::S;            // mirrors "skill"
+
+/// Normalise a value for a single-letter index tag (trimmed, lowercase)
+pub fn index_value(value: &str) -> String {
+    value.trim().to_lowercase()
+}
+
+pub(crate) fn index_tag(letter: Alphabet, value: &str) -> Tag {
+    Tag::custom(TagKind::single_letter(letter, false), vec![index_value(value)])
+}
 
 // ==================== JobListing ↔ Nostr Event ====================
 impl JobListing {
     /// Convert JobListing to Nostr event (kind 39993)
     pub fn to_nostr_event(&self, keys: &Keys) -> Result<Event> {
+        Ok(self.unsigned_event(keys.public_key())?.sign_with_keys(keys)?)
+    }
+
+    /// Unsigned kind 39993 event: JSON content plus searchable tags
+    pub(crate) fn event_builder(&self) -> Result<EventBuilder> {
         // 1. Serialize as JSON content
         let content = serde_json::to_string_pretty(self)?;
 
         // 2. Build searchable tags following 39993 spec
-        let d_tag_value = format!("{}-{}-{}", 
-            self.hiring_organization.name.to_lowercase().replace(' ', "-"),
-            &self.identifier,
-            &keys.public_key().to_hex()[..8]
-        );
-
+        // The d tag is the identifier itself so re-publishing replaces in place
         let mut tags = vec![
-            Tag::identifier(&d_tag_value),
+            Tag::identifier(self.d_tag()),
             Tag::custom(TagKind::Custom("t".into()), vec!["Jobs"]),
             Tag::custom(TagKind::Custom("company".into()), vec![&self.hiring_organization.name]),
+            index_tag(INDEX_COMPANY, &self.hiring_organization.name),
             Tag::custom(TagKind::Custom("job-id".into()), vec![&self.identifier]),
             Tag::custom(TagKind::Custom("title".into()), vec![&self.title]),
+            Tag::custom(TagKind::Custom("date-posted".into()), vec![&self.date_posted]),
         ];
 
-        // Location
-        tags.push(Tag::custom(TagKind::Custom("location".into()), 
-            vec![self.job_location_type.as_ref()
-                .and_then(|types| types.first())
-                .map(|t| format!("{:?}", t))
-                .unwrap_or_else(|| "Remote".to_string())]
-        ));
+        if let Some(apply_url) = self.apply_url.as_ref().filter(|url| !url.is_empty()) {
+            tags.push(Tag::custom(TagKind::Custom("apply-url".into()), vec![apply_url.clone()]));
+        }
+
+        // Location type
+        if let Some(location_types) = &self.job_location_type {
+            for location_type in location_types {
+                tags.push(Tag::custom(
+                    TagKind::Custom("location-type".into()),
+                    vec![location_type.as_str()]
+                ));
+            }
+        }
 
         // Employment type
         for emp_type in &self.employment_type {
             tags.push(Tag::custom(
                 TagKind::Custom("employment-type".into()), 
-                vec![format!("{:?}", emp_type)]
+                vec![emp_type.as_str()]
+            ));
+            tags.push(index_tag(INDEX_EMPLOYMENT_TYPE, emp_type.as_str()));
+        }
+
+        // Worker type
+        for worker_type in &self.eligible_worker_type {
+            tags.push(Tag::custom(
+                TagKind::Custom("worker-type".into()),
+                vec![worker_type.as_str()]
             ));
         }
 
@@ -52,24 +91,24 @@ impl JobListing {
         if let Some(skills) = &self.skills {
             for skill in skills {
                 tags.push(Tag::custom(TagKind::Custom("skill".into()), vec![skill.clone()]));
+                tags.push(index_tag(INDEX_SKILL, skill));
             }
         }
 
         // Salary range
-        if let Some(salary) = &self.base_salary {
-            if let MonetaryValue::Range(range) = &salary.value {
-                if let (Some(min), Some(max)) = (range.min_value, range.max_value) {
-                    tags.push(Tag::custom(
-                        TagKind::Custom("salary".into()),
-                        vec![
-                            min.to_string(),
-                            max.to_string(),
-                            salary.currency.clone(),
-                            range.unit_text.clone(),
-                        ],
-                    ));
-                }
-            }
+        if let Some(salary) = &self.base_salary
+            && let MonetaryValue::Range(range) = &salary.value
+            && let (Some(min), Some(max)) = (range.min_value, range.max_value)
+        {
+            tags.push(Tag::custom(
+                TagKind::Custom("salary".into()),
+                vec![
+                    min.to_string(),
+                    max.to_string(),
+                    salary.currency.clone(),
+                    range.unit_text.clone(),
+                ],
+            ));
         }
 
         // Location tags (country/region)
@@ -99,15 +138,65 @@ impl JobListing {
         if let Some(url) = &self.hiring_organization.url {
             tags.push(Tag::custom(TagKind::Custom("company-url".into()), vec![url.clone()]));
         }
+        if let Some(org) = &self.hiring_organization.nostr_pubkey {
+            tags.push(Tag::custom(TagKind::Custom("org-pubkey".into()), vec![org.clone()]));
+        }
+        if let Some(nip05) = &self.nip05_verified {
+            tags.push(Tag::custom(TagKind::Custom("nip05".into()), vec![nip05.clone()]));
+        }
+        if let Some(status) = &self.status {
+            tags.push(Tag::custom(TagKind::Custom("status".into()), vec![status.as_str()]));
+        }
 
-        // 3. Build and sign event
-        Ok(EventBuilder::new(Kind::from(KIND_JOB_LISTING), content)
-            .tags(tags)
-            .sign_with_keys(keys)?)
+        // AI agent requirements
+        if let Some(capabilities) = &self.required_capabilities {
+            for capability in capabilities {
+                tags.push(Tag::custom(
+                    TagKind::Custom("capability".into()),
+                    vec![capability.name.as_str(), capability.level.as_str()]
+                ));
+            }
+        }
+        if let Some(response_time) = &self.response_time_max {
+            tags.push(Tag::custom(
+                TagKind::Custom("response-time-max".into()),
+                vec![response_time.value.to_string(), response_time.unit.clone()]
+            ));
+        }
+        if let Some(accuracy) = &self.accuracy_min {
+            tags.push(Tag::custom(
+                TagKind::Custom("accuracy-min".into()),
+                vec![accuracy.value.to_string()]
+            ));
+        }
+        if let Some(throughput) = &self.throughput_min {
+            tags.push(Tag::custom(
+                TagKind::Custom("throughput-min".into()),
+                vec![throughput.value.to_string(), throughput.unit.clone()]
+            ));
+        }
+        if let Some(interface) = &self.interface_type {
+            tags.push(Tag::custom(TagKind::Custom("interface".into()), vec![interface.as_str()]));
+        }
+        if let Some(protocol) = &self.protocol {
+            tags.push(Tag::custom(TagKind::Custom("protocol".into()), vec![protocol.clone()]));
+        }
+        if let Some(oversight) = &self.human_oversight {
+            tags.push(Tag::custom(TagKind::Custom("oversight".into()), vec![oversight.as_str()]));
+        }
+
+        // 3. Build event
+        Ok(EventBuilder::new(Kind::from(KIND_JOB_LISTING), content).tags(tags))
     }
 
-    /// Parse JobListing from kind 39993 Nostr event
+    /// Parse JobListing from kind 39993 Nostr event.
+    /// Content of another `type` or a newer `version` fails with a `SchemaError`.
     pub fn from_nostr_event(event: &Event) -> Result<Self> {
+        Self::from_nostr_event_with(event, &MigrationRegistry::default())
+    }
+
+    /// Parse JobListing, migrating newer content versions with `registry`
+    pub fn from_nostr_event_with(event: &Event, registry: &MigrationRegistry) -> Result<Self> {
         if event.kind != Kind::from(KIND_JOB_LISTING) {
             return Err(anyhow!(
                 "Invalid event kind: expected {}, got {}",
@@ -117,7 +206,15 @@ impl JobListing {
         }
 
         // Try JSON content first
-        if let Ok(job) = serde_json::from_str::<JobListing>(&event.content) {
+        if let Ok(content) = serde_json::from_str::<serde_json::Value>(&event.content)
+            && content.is_object()
+            && let Ok(mut job) = serde_json::from_value::<JobListing>(registry.upgrade("job", content)?)
+        {
+            // The d tag is the event's address; keep it so re-publishing
+            // replaces this event (including legacy prefixed d tags)
+            if let Some(d) = event.tags.identifier() {
+                job.identifier = d.to_string();
+            }
             job.validate()?;
             return Ok(job);
         }
@@ -126,18 +223,13 @@ impl JobListing {
         Self::from_tags(event)
     }
 
-    /// Legacy / fallback from tags
-    fn from_tags(event: &Event) -> Result<Self> {
+    /// Decode a listing from tags alone (content is treated as the Markdown
+    /// description). Rebuilds every field `to_nostr_event` writes as a tag.
+    pub fn from_tags(event: &Event) -> Result<Self> {
         let tags_vec: Vec<Tag> = event.tags.iter().cloned().collect();
 
-        let identifier = tags_vec
-            .iter()
-            .find_map(|t| {
-                let slice = t.as_slice();
-                if !slice.is_empty() && slice[0] == "d" && slice.len() > 1 {
-                    Some(slice[1].clone())
-                } else { None }
-            })
+        let identifier = Self::find_tag_value(&tags_vec, "d")
+            .or_else(|| Self::find_tag_value(&tags_vec, "job-id"))
             .ok_or_else(|| anyhow!("Missing identifier tag"))?;
 
         let title = Self::find_tag_value(&tags_vec, "title")
@@ -147,6 +239,15 @@ impl JobListing {
         let date_posted = Self::find_tag_value(&tags_vec, "date-posted")
             .unwrap_or_else(|| event.created_at.to_human_datetime());
         let description = event.content.clone();
+        let apply_url = Self::find_tag_value(&tags_vec, "apply-url").unwrap_or_default();
+
+        // Worker type (legacy events without the tag are for humans)
+        let mut worker_types: Vec<EligibleWorkerType> = Self::tag_values(&tags_vec, "worker-type")
+            .filter_map(|t| EligibleWorkerType::parse(&t[0]))
+            .collect();
+        if worker_types.is_empty() {
+            worker_types.push(EligibleWorkerType::Human);
+        }
 
         let mut job = JobListing::new(
             identifier,
@@ -154,73 +255,148 @@ impl JobListing {
             description,
             company,
             date_posted,
-            Some("".to_string()),
-            vec![EligibleWorkerType::Human],
+            Some(apply_url),
+            worker_types,
         );
 
-        // Employment types
-        job.employment_type = tags_vec
-            .iter()
-            .filter_map(|t| Self::parse_custom_tag(t, "employment-type"))
-            .filter_map(|s| match s.to_uppercase().as_str() {
-                "FULLTIME" => Some(EmploymentType::FullTime),
-                "PARTTIME" => Some(EmploymentType::PartTime),
-                "CONTRACTOR" => Some(EmploymentType::Contractor),
-                "INTERN" => Some(EmploymentType::Intern),
-                _ => None,
-            })
+        job.employment_type = Self::tag_values(&tags_vec, "employment-type")
+            .filter_map(|t| EmploymentType::parse(&t[0]))
             .collect();
-
-        // Location types
-        job.job_location_type = Some(
-            tags_vec
-                .iter()
-                .filter_map(|t| Self::parse_custom_tag(t, "location-type"))
-                .filter_map(|s| match s.to_uppercase().as_str() {
-                    "TELECOMMUTE" | "REMOTE" => Some(JobLocationType::Telecommute),
-                    "ONSITE" => Some(JobLocationType::OnSite),
-                    "HYBRID" => Some(JobLocationType::Hybrid),
-                    _ => None,
-                })
+        job.job_location_type = Self::non_empty(
+            Self::tag_values(&tags_vec, "location-type")
+                .filter_map(|t| JobLocationType::parse(&t[0]))
                 .collect(),
         );
-
-        // Skills
-        job.skills = Some(
-            tags_vec.iter()
-                .filter_map(|t| Self::parse_custom_tag(t, "skill"))
-                .collect()
+        job.skills = Self::non_empty(
+            Self::tag_values(&tags_vec, "skill").map(|t| t[0].clone()).collect(),
         );
 
+        // Salary range
+        if let Some([min, max, currency, unit, ..]) = Self::tag_values(&tags_vec, "salary").next() {
+            job.base_salary = Some(BaseSalary {
+                schema_type: "MonetaryAmount".to_string(),
+                currency: currency.clone(),
+                value: MonetaryValue::Range(QuantitativeValue {
+                    schema_type: "QuantitativeValue".to_string(),
+                    min_value: min.parse().ok(),
+                    max_value: max.parse().ok(),
+                    unit_text: unit.clone(),
+                }),
+            });
+        }
+
+        // Locations: the encoder writes country then region per location
+        for tag in &tags_vec {
+            let (name, value) = match tag.as_slice() {
+                [name, value, ..] if name == "country" || name == "region" => (name, value),
+                _ => continue,
+            };
+            let starts_new = match job.job_location.last().and_then(|l| l.address.as_ref()) {
+                Some(addr) if name == "region" => addr.address_region.is_some(),
+                _ => true,
+            };
+            if starts_new {
+                job.job_location.push(JobLocation {
+                    schema_type: "Place".to_string(),
+                    address: Some(PostalAddress {
+                        schema_type: "PostalAddress".to_string(),
+                        street_address: None,
+                        address_locality: None,
+                        address_region: None,
+                        postal_code: None,
+                        address_country: None,
+                    }),
+                });
+            }
+            if let Some(addr) = job.job_location.last_mut().and_then(|l| l.address.as_mut()) {
+                if name == "country" {
+                    addr.address_country = Some(value.clone());
+                } else {
+                    addr.address_region = Some(value.clone());
+                }
+            }
+        }
+
         // Expiry
         job.valid_through = Self::find_tag_value(&tags_vec, "expires");
 
         // Nostr-specific
         job.nostr_employer_pubkey = Self::find_tag_value(&tags_vec, "employer-pubkey");
         job.lightning_address = Self::find_tag_value(&tags_vec, "lightning");
+        job.nip05_verified = Self::find_tag_value(&tags_vec, "nip05");
+        job.status = Self::find_tag_value(&tags_vec, "status")
+            .and_then(|s| JobStatus::parse(&s));
         if let Some(url) = Self::find_tag_value(&tags_vec, "company-url") {
             job.hiring_organization.url = Some(url);
         }
-
+        job.hiring_organization.nostr_pubkey = Self::find_tag_value(&tags_vec, "org-pubkey");
+
+        // AI agent requirements
+        job.required_capabilities = Self::non_empty(
+            Self::tag_values(&tags_vec, "capability")
+                .filter_map(|t| match t {
+                    [name, level, ..] => Some(CapabilityRequirement {
+                        name: name.clone(),
+                        level: CapabilityLevel::parse(level)?,
+                    }),
+                    _ => None,
+                })
+                .collect(),
+        );
+        job.response_time_max = Self::performance_tag(&tags_vec, "response-time-max", None);
+        job.accuracy_min = Self::performance_tag(&tags_vec, "accuracy-min", Some("PERCENT"));
+        job.throughput_min = Self::performance_tag(&tags_vec, "throughput-min", None);
+        job.interface_type = Self::find_tag_value(&tags_vec, "interface")
+            .and_then(|s| InterfaceType::parse(&s));
+        job.protocol = Self::find_tag_value(&tags_vec, "protocol");
+        job.human_oversight = Self::find_tag_value(&tags_vec, "oversight")
+            .and_then(|s| OversightRequirement::parse(&s));
+
+        job.validate()?;
         Ok(job)
     }
 
+    /// Values (everything after the name) of every tag called `name`
+    fn tag_values<'a>(tags: &'a [Tag], name: &'a str) -> impl Iterator<Item = &'a [String]> {
+        tags.iter().filter_map(move |t| match t.as_slice() {
+            [tag_name, values @ ..] if tag_name == name && !values.is_empty() => Some(values),
+            _ => None,
+        })
+    }
+
+    /// `["name", "<value>", "<unit>"]`, with `default_unit` for value-only tags
+    fn performance_tag(tags: &[Tag], name: &str, default_unit: Option<&str>) -> Option<PerformanceRequirement> {
+        let values = Self::tag_values(tags, name).next()?;
+        let unit = values.get(1).map(String::as_str).or(default_unit)?;
+        Some(PerformanceRequirement {
+            value: values[0].parse().ok()?,
+            unit: unit.to_string(),
+        })
+    }
+
+    fn non_empty<T>(items: Vec<T>) -> Option<Vec<T>> {
+        if items.is_empty() { None } else { Some(items) }
+    }
+
     fn find_tag_value(tags: &[Tag], name: &str) -> Option<String> {
         tags.iter().find_map(|t| match t.as_slice() {
             [tag_name, value, ..] if tag_name == name => Some(value.to_string()),
             _ => None,
         })
     }
+}
 
-    fn parse_custom_tag(tag: &Tag, name: &str) -> Option<String> {
-        match tag.as_slice() {
-            [tag_name, value, ..] if tag_name == name => Some(value.to_string()),
-            _ => None,
-        }
+impl SignEvent for JobListing {
+    fn unsigned_event(&self, author: PublicKey) -> Result<UnsignedEvent> {
+        Ok(self.event_builder()?.build(author))
     }
 }
 
 // ==================== Jobs Filter ====================
+/// Relay filter for kind 39993 listings.
+/// Queries the single-letter index mirrors written by `to_nostr_event`;
+/// repeated calls for the same field match any of the given values.
+#[derive(Debug, Clone)]
 pub struct JobsFilter {
     filter: Filter,
 }
@@ -236,25 +412,24 @@ impl JobsFilter {
 
     pub fn company(mut self, name: &str) -> Self {
         self.filter = self.filter.custom_tag(
-            SingleLetterTag::lowercase(Alphabet::C), 
-            name.to_string()
+            SingleLetterTag::lowercase(INDEX_COMPANY),
+            index_value(name)
         );
         self
     }
 
     pub fn employment_type(mut self, emp_type: EmploymentType) -> Self {
-        let s = format!("{:?}", emp_type);
         self.filter = self.filter.custom_tag(
-            SingleLetterTag::lowercase(Alphabet::E), 
-            s
+            SingleLetterTag::lowercase(INDEX_EMPLOYMENT_TYPE),
+            index_value(emp_type.as_str())
         );
         self
     }
 
     pub fn skill(mut self, skill: &str) -> Self {
         self.filter = self.filter.custom_tag(
-            SingleLetterTag::lowercase(Alphabet::S), 
-            skill.to_string()
+            SingleLetterTag::lowercase(INDEX_SKILL),
+            index_value(skill)
         );
         self
     }
@@ -274,3 +449,248 @@ impl Default for JobsFilter {
         Self::new()
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{job_builder, task_builder};
+    use crate::versioning::SchemaError;
+
+    fn sample_job() -> JobListing {
+        job_builder("job-001", "Senior Rust Developer")
+            .employment_type(vec![EmploymentType::FullTime, EmploymentType::Contractor])
+            .remote()
+            .skills(vec!["Rust".to_string(), "Nostr".to_string()])
+            .build()
+            .unwrap()
+    }
+
+    fn matches(filter: &Filter, event: &Event) -> bool {
+        filter.match_event(event, MatchEventOptions::new())
+    }
+
+    #[test]
+    fn test_filter_matches_emitted_event() {
+        let keys = Keys::generate();
+        let event = sample_job().to_nostr_event(&keys).unwrap();
+
+        assert!(matches(&JobsFilter::new().build(), &event));
+        assert!(matches(&JobsFilter::new().company("Acme Corp").build(), &event));
+        assert!(matches(&JobsFilter::new().employment_type(EmploymentType::FullTime).build(), &event));
+        assert!(matches(&JobsFilter::new().skill("Rust").build(), &event));
+
+        let combined = JobsFilter::new()
+            .company("Acme Corp")
+            .employment_type(EmploymentType::Contractor)
+            .skill("Nostr")
+            .limit(10)
+            .build();
+        assert!(matches(&combined, &event));
+    }
+
+    #[test]
+    fn test_filter_is_case_insensitive() {
+        let keys = Keys::generate();
+        let event = sample_job().to_nostr_event(&keys).unwrap();
+
+        assert!(matches(&JobsFilter::new().company("acme corp").build(), &event));
+        assert!(matches(&JobsFilter::new().skill("RUST").build(), &event));
+    }
+
+    #[test]
+    fn test_filter_rejects_non_matching_event() {
+        let keys = Keys::generate();
+        let event = sample_job().to_nostr_event(&keys).unwrap();
+
+        assert!(!matches(&JobsFilter::new().company("Other Corp").build(), &event));
+        assert!(!matches(&JobsFilter::new().employment_type(EmploymentType::Intern).build(), &event));
+        assert!(!matches(&JobsFilter::new().skill("Go").build(), &event));
+    }
+
+    #[test]
+    fn test_multi_letter_tags_are_kept() {
+        let keys = Keys::generate();
+        let event = sample_job().to_nostr_event(&keys).unwrap();
+        let names: Vec<&str> = event.tags.iter().map(|t| t.as_slice()[0].as_str()).collect();
+
+        assert!(names.contains(&"company"));
+        assert!(names.contains(&"employment-type"));
+        assert!(names.contains(&"skill"));
+    }
+
+    fn tag_values(event: &Event, name: &str) -> Vec<Vec<String>> {
+        event.tags.iter()
+            .map(|t| t.as_slice())
+            .filter(|s| s[0] == name)
+            .map(|s| s[1..].to_vec())
+            .collect()
+    }
+
+    #[test]
+    fn test_controlled_vocabulary_tags() {
+        let keys = Keys::generate();
+        let mut job = sample_job();
+        job.job_location_type = Some(vec![JobLocationType::OnSite, JobLocationType::Hybrid]);
+        job.nip05_verified = Some("jobs@acme.com".to_string());
+        let event = job.to_nostr_event(&keys).unwrap();
+
+        assert_eq!(tag_values(&event, "employment-type"), vec![vec!["FULL_TIME"], vec!["CONTRACTOR"]]);
+        assert_eq!(tag_values(&event, "location-type"), vec![vec!["ON_SITE"], vec!["HYBRID"]]);
+        assert_eq!(tag_values(&event, "worker-type"), vec![vec!["Human"]]);
+        assert_eq!(tag_values(&event, "nip05"), vec![vec!["jobs@acme.com"]]);
+        assert!(tag_values(&event, "location").is_empty());
+    }
+
+    #[test]
+    fn test_ai_agent_tags() {
+        let keys = Keys::generate();
+        let job = task_builder()
+            .for_hybrid()
+            .response_time(5.0, "SECOND".to_string())
+            .accuracy(95.5)
+            .throughput(1000.0, "TASKS_PER_HOUR".to_string())
+            .interface_type(InterfaceType::WebPortal)
+            .protocol("REST".to_string())
+            .human_oversight(OversightRequirement::Required)
+            .capabilities(vec![CapabilityRequirement {
+                name: "Image Classification".to_string(),
+                level: CapabilityLevel::Advanced,
+            }])
+            .build()
+            .unwrap();
+        let event = job.to_nostr_event(&keys).unwrap();
+
+        assert_eq!(tag_values(&event, "worker-type"), vec![vec!["Human"], vec!["AIAgent"]]);
+        assert_eq!(tag_values(&event, "capability"), vec![vec!["Image Classification", "Advanced"]]);
+        assert_eq!(tag_values(&event, "response-time-max"), vec![vec!["5", "SECOND"]]);
+        assert_eq!(tag_values(&event, "accuracy-min"), vec![vec!["95.5"]]);
+        assert_eq!(tag_values(&event, "throughput-min"), vec![vec!["1000", "TASKS_PER_HOUR"]]);
+        assert_eq!(tag_values(&event, "interface"), vec![vec!["WEB_PORTAL"]]);
+        assert_eq!(tag_values(&event, "protocol"), vec![vec!["REST"]]);
+        assert_eq!(tag_values(&event, "oversight"), vec![vec!["Required"]]);
+    }
+
+    fn full_ai_job() -> JobListing {
+        let mut job = task_builder()
+            .for_hybrid()
+            .employment_type(vec![EmploymentType::TaskBased, EmploymentType::PerDiem])
+            .location_type(vec![JobLocationType::Telecommute, JobLocationType::OnSite])
+            .salary(0.05, 0.1, "USD".to_string(), "TASK".to_string())
+            .skills(vec!["Computer Vision".to_string()])
+            .valid_through("2025-12-31".to_string())
+            .response_time(2.5, "SECOND".to_string())
+            .accuracy(95.0)
+            .throughput(1000.0, "TASKS_PER_HOUR".to_string())
+            .interface_type(InterfaceType::Api)
+            .protocol("gRPC".to_string())
+            .human_oversight(OversightRequirement::Optional)
+            .capabilities(vec![CapabilityRequirement {
+                name: "Image Classification".to_string(),
+                level: CapabilityLevel::Expert,
+            }])
+            .nostr_pubkey("npub1employer".to_string())
+            .lightning_address("jobs@taskplatform.com".to_string())
+            .build()
+            .unwrap();
+        job.description = "## About\n\nClassify *product* images.".to_string();
+        job.nip05_verified = Some("jobs@taskplatform.com".to_string());
+        job.hiring_organization.url = Some("https://taskplatform.com".to_string());
+        for (country, region) in [("US", Some("CA")), ("GB", None), ("DE", Some("BE"))] {
+            job.job_location.push(JobLocation {
+                schema_type: "Place".to_string(),
+                address: Some(PostalAddress {
+                    schema_type: "PostalAddress".to_string(),
+                    street_address: None,
+                    address_locality: None,
+                    address_region: region.map(str::to_string),
+                    postal_code: None,
+                    address_country: Some(country.to_string()),
+                }),
+            });
+        }
+        job
+    }
+
+    #[test]
+    fn test_markdown_and_json_content_decode_equal() {
+        let keys = Keys::generate();
+        let job = full_ai_job();
+        let json_event = job.to_nostr_event(&keys).unwrap();
+        let markdown_event = EventBuilder::new(Kind::from(KIND_JOB_LISTING), &job.description)
+            .tags(json_event.tags.iter().cloned())
+            .sign_with_keys(&keys)
+            .unwrap();
+
+        let from_json = JobListing::from_nostr_event(&json_event).unwrap();
+        let from_markdown = JobListing::from_nostr_event(&markdown_event).unwrap();
+        assert_eq!(from_json, job);
+        assert_eq!(from_markdown, job);
+    }
+
+    #[test]
+    fn test_from_tags_reads_legacy_values() {
+        let keys = Keys::generate();
+        let event = EventBuilder::new(Kind::from(KIND_JOB_LISTING), "Legacy description")
+            .tags([
+                Tag::identifier("acme-job-001-abcdef01"),
+                Tag::custom(TagKind::Custom("title".into()), vec!["Rust Dev"]),
+                Tag::custom(TagKind::Custom("company".into()), vec!["Acme"]),
+                Tag::custom(TagKind::Custom("employment-type".into()), vec!["FullTime"]),
+                Tag::custom(TagKind::Custom("location-type".into()), vec!["Telecommute"]),
+            ])
+            .sign_with_keys(&keys)
+            .unwrap();
+
+        let job = JobListing::from_tags(&event).unwrap();
+        assert_eq!(job.identifier, "acme-job-001-abcdef01");
+        assert_eq!(job.employment_type, vec![EmploymentType::FullTime]);
+        assert_eq!(job.job_location_type, Some(vec![JobLocationType::Telecommute]));
+        assert_eq!(job.eligible_worker_type, vec![EligibleWorkerType::Human]);
+        assert_eq!(job.skills, None);
+    }
+
+    #[test]
+    fn test_content_header_and_versions() {
+        let keys = Keys::generate();
+        let job = sample_job();
+        let event = job.to_nostr_event(&keys).unwrap();
+        let content: serde_json::Value = serde_json::from_str(&event.content).unwrap();
+        assert_eq!(content["type"], "job");
+        assert_eq!(content["version"], 1);
+        assert!(JobListing::from_nostr_event(&event).unwrap().extra.is_empty());
+
+        // A v2 payload is rejected with a typed error...
+        let mut v2 = content.clone();
+        v2["version"] = 2.into();
+        v2["role"] = v2["title"].take();
+        let v2_event = EventBuilder::new(Kind::from(KIND_JOB_LISTING), v2.to_string())
+            .tags(event.tags.iter().cloned())
+            .sign_with_keys(&keys)
+            .unwrap();
+        let err = JobListing::from_nostr_event(&v2_event).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<SchemaError>(),
+            Some(SchemaError::UnsupportedVersion { version: 2, supported: 1, .. })
+        ));
+
+        // ...unless a migration brings it back to v1
+        let mut registry = MigrationRegistry::new();
+        registry.register("job", 2, |mut v| {
+            v["title"] = v["role"].take();
+            v.as_object_mut().unwrap().remove("role");
+            Ok(v)
+        });
+        assert_eq!(JobListing::from_nostr_event_with(&v2_event, &registry).unwrap(), job);
+
+        // Legacy content without a header still reads as a v1 job
+        let mut legacy = content;
+        legacy.as_object_mut().unwrap().remove("type");
+        legacy.as_object_mut().unwrap().remove("version");
+        let legacy_event = EventBuilder::new(Kind::from(KIND_JOB_LISTING), legacy.to_string())
+            .tags(event.tags.iter().cloned())
+            .sign_with_keys(&keys)
+            .unwrap();
+        assert_eq!(JobListing::from_nostr_event(&legacy_event).unwrap(), job);
+    }
+}
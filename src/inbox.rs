@@ -0,0 +1,385 @@
+// src/inbox.rs
+// Employer-side application inbox
+// Collects kind 39995 applications per job address, decrypts and deduplicates
+// them, and publishes status changes back to applicants as encrypted replies
+
+use crate::address::JobAddress;
+use crate::application::JobApplication;
+use crate::events::KIND_JOB_APPLICATION;
+use anyhow::{anyhow, Result};
+use nostr_sdk::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// Where an application is in the hiring process
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ApplicationStatus {
+    Received,
+    Shortlisted,
+    Rejected,
+    Hired,
+}
+
+impl ApplicationStatus {
+    /// Allowed moves: received → shortlisted/rejected/hired,
+    /// shortlisted → rejected/hired. Rejected and hired are final.
+    pub fn can_transition_to(&self, next: ApplicationStatus) -> bool {
+        use ApplicationStatus::*;
+        matches!(
+            (self, next),
+            (Received, Shortlisted | Rejected | Hired) | (Shortlisted, Rejected | Hired)
+        )
+    }
+}
+
+/// Status update sent to an applicant (kind 39995, type "application-status")
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ApplicationReply {
+    #[serde(rename = "type")]
+    pub reply_type: String, // "application-status"
+    pub version: u32,
+    pub status: ApplicationStatus,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl ApplicationReply {
+    pub fn new(status: ApplicationStatus, message: Option<String>) -> Self {
+        Self {
+            reply_type: "application-status".to_string(),
+            version: 1,
+            status,
+            message,
+        }
+    }
+
+    /// Decrypt a reply addressed to the applicant
+    pub fn decrypt_nostr_event(event: &Event, applicant_keys: &Keys) -> Result<Self> {
+        Self::check_kind(event)?;
+        let content = nip44::decrypt(applicant_keys.secret_key(), &event.pubkey, &event.content)?;
+        Self::from_content(&content)
+    }
+
+    /// `decrypt_nostr_event` for any `NostrSigner`
+    pub async fn decrypt_with<S>(event: &Event, signer: &S) -> Result<Self>
+    where
+        S: NostrSigner + ?Sized,
+    {
+        Self::check_kind(event)?;
+        Self::from_content(&signer.nip44_decrypt(&event.pubkey, &event.content).await?)
+    }
+
+    fn check_kind(event: &Event) -> Result<()> {
+        if event.kind != Kind::from(KIND_JOB_APPLICATION) {
+            return Err(anyhow!(
+                "Invalid event kind: expected {}, got {}",
+                KIND_JOB_APPLICATION,
+                event.kind
+            ));
+        }
+        Ok(())
+    }
+
+    fn from_content(content: &str) -> Result<Self> {
+        let reply: ApplicationReply = serde_json::from_str(content)?;
+        if reply.reply_type != "application-status" {
+            return Err(anyhow!("Not an application status reply: {}", reply.reply_type));
+        }
+        Ok(reply)
+    }
+}
+
+/// One applicant's (latest) application to a job
+#[derive(Debug, Clone)]
+pub struct InboxEntry {
+    pub application: JobApplication,
+    pub event: Event,
+    pub status: ApplicationStatus,
+    pub submissions: usize, // 1 + number of resubmissions
+}
+
+/// Employer inbox keyed by job address, then applicant pubkey.
+/// Decryption and replies go through the employer's signer, so the inbox
+/// works the same with local keys or a remote signer.
+pub struct ApplicationInbox {
+    employer: PublicKey,
+    signer: Arc<dyn NostrSigner>,
+    job_ids: HashMap<EventId, JobAddress>,
+    entries: HashMap<JobAddress, HashMap<PublicKey, InboxEntry>>,
+    seen: HashSet<EventId>,
+}
+
+impl ApplicationInbox {
+    pub fn new(employer_keys: Keys) -> Self {
+        Self::from_parts(employer_keys.public_key(), Arc::new(employer_keys))
+    }
+
+    /// Inbox for the key behind any `NostrSigner`
+    pub async fn with_signer(signer: Arc<dyn NostrSigner>) -> Result<Self> {
+        Ok(Self::from_parts(signer.get_public_key().await?, signer))
+    }
+
+    fn from_parts(employer: PublicKey, signer: Arc<dyn NostrSigner>) -> Self {
+        Self {
+            employer,
+            signer,
+            job_ids: HashMap::new(),
+            entries: HashMap::new(),
+            seen: HashSet::new(),
+        }
+    }
+
+    /// Start collecting applications for a job. Call once per published
+    /// version so applications referencing older event ids still match.
+    pub fn track_job(&mut self, job_event: &Event) -> Result<JobAddress> {
+        let address = JobAddress::from_event(job_event)?;
+        self.job_ids.insert(job_event.id, address.clone());
+        self.entries.entry(address.clone()).or_default();
+        Ok(address)
+    }
+
+    /// Relay filter for applications addressed to this employer
+    pub fn filter(&self) -> Filter {
+        Filter::new()
+            .kind(Kind::from(KIND_JOB_APPLICATION))
+            .pubkey(self.employer)
+    }
+
+    /// Decrypt and file one application event.
+    /// Returns the job it was filed under, or `None` if it isn't for a tracked job.
+    pub async fn ingest(&mut self, event: &Event) -> Result<Option<JobAddress>> {
+        let application = JobApplication::decrypt_with(event, self.signer.as_ref()).await?;
+        let Some(address) = self.resolve(&application) else {
+            return Ok(None);
+        };
+
+        // Same event delivered again (e.g. by another relay)
+        if !self.seen.insert(event.id) {
+            return Ok(Some(address));
+        }
+
+        let applicants = self.entries.entry(address.clone()).or_default();
+        match applicants.get_mut(&event.pubkey) {
+            // Resubmission: keep the newest content and the current status
+            Some(entry) => {
+                entry.submissions += 1;
+                if event.created_at > entry.event.created_at {
+                    entry.application = application;
+                    entry.event = event.clone();
+                }
+            }
+            None => {
+                applicants.insert(event.pubkey, InboxEntry {
+                    application,
+                    event: event.clone(),
+                    status: ApplicationStatus::Received,
+                    submissions: 1,
+                });
+            }
+        }
+        Ok(Some(address))
+    }
+
+    /// Applications for a job, oldest first
+    pub fn applications(&self, address: &JobAddress) -> Vec<&InboxEntry> {
+        let mut entries: Vec<&InboxEntry> = self
+            .entries
+            .get(address)
+            .map(|applicants| applicants.values().collect())
+            .unwrap_or_default();
+        entries.sort_by_key(|e| e.event.created_at);
+        entries
+    }
+
+    pub fn get(&self, address: &JobAddress, applicant: &PublicKey) -> Option<&InboxEntry> {
+        self.entries.get(address)?.get(applicant)
+    }
+
+    /// Move an application to `status` and build the encrypted reply to publish
+    pub async fn set_status(
+        &mut self,
+        address: &JobAddress,
+        applicant: &PublicKey,
+        status: ApplicationStatus,
+        message: Option<String>,
+    ) -> Result<Event> {
+        let entry = self
+            .entries
+            .get_mut(address)
+            .and_then(|applicants| applicants.get_mut(applicant))
+            .ok_or_else(|| anyhow!("No application from {} for {}", applicant, address))?;
+
+        if !entry.status.can_transition_to(status) {
+            return Err(anyhow!(
+                "Invalid status transition: {:?} -> {:?}",
+                entry.status,
+                status
+            ));
+        }
+
+        let reply = ApplicationReply::new(status, message);
+        let content = self.signer.nip44_encrypt(applicant, &serde_json::to_string(&reply)?).await?;
+        // One addressable reply per applicant per job; later statuses replace it
+        let d = format!("{}:{}", address, applicant.to_hex());
+        let unsigned = EventBuilder::new(Kind::from(KIND_JOB_APPLICATION), content)
+            .tags([
+                Tag::identifier(d),
+                Tag::event(entry.event.id),
+                Tag::public_key(*applicant),
+                address.to_tag(),
+                Tag::custom(TagKind::Custom("encrypted".into()), vec!["nip44"]),
+            ])
+            .build(self.employer);
+        let event = self.signer.sign_event(unsigned).await?;
+
+        entry.status = status;
+        Ok(event)
+    }
+
+    fn resolve(&self, application: &JobApplication) -> Option<JobAddress> {
+        if let Some(address) = &application.job_address
+            && self.entries.contains_key(address)
+        {
+            return Some(address.clone());
+        }
+        application
+            .job_event_id
+            .and_then(|id| self.job_ids.get(&id).cloned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::sample_job;
+
+    fn job_event(employer: &Keys, id: &str) -> Event {
+        sample_job(id).to_nostr_event(employer).unwrap()
+    }
+
+    fn apply(job: &Event, applicant: &Keys, message: &str, created_at: Timestamp) -> Event {
+        let event = JobApplication::builder(message.to_string())
+            .job(job)
+            .build()
+            .unwrap()
+            .to_encrypted_nostr_event(applicant)
+            .unwrap();
+        EventBuilder::new(event.kind, event.content)
+            .tags(event.tags)
+            .custom_created_at(created_at)
+            .sign_with_keys(applicant)
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_collects_per_job_and_deduplicates() {
+        let employer = Keys::generate();
+        let alice = Keys::generate();
+        let bob = Keys::generate();
+        let job_a = job_event(&employer, "job-a");
+        let job_b = job_event(&employer, "job-b");
+        let untracked = job_event(&employer, "job-c");
+
+        let mut inbox = ApplicationInbox::new(employer.clone());
+        let address_a = inbox.track_job(&job_a).unwrap();
+        let address_b = inbox.track_job(&job_b).unwrap();
+
+        let now = Timestamp::now();
+        let first = apply(&job_a, &alice, "First try", now - 60);
+        let second = apply(&job_a, &alice, "Updated application", now);
+        assert_eq!(inbox.ingest(&first).await.unwrap(), Some(address_a.clone()));
+        assert_eq!(inbox.ingest(&second).await.unwrap(), Some(address_a.clone()));
+        inbox.ingest(&first).await.unwrap(); // Duplicate delivery
+        inbox.ingest(&apply(&job_a, &bob, "Hello", now)).await.unwrap();
+        inbox.ingest(&apply(&job_b, &bob, "Hello again", now)).await.unwrap();
+        assert_eq!(inbox.ingest(&apply(&untracked, &bob, "Hi", now)).await.unwrap(), None);
+
+        let entries = inbox.applications(&address_a);
+        assert_eq!(entries.len(), 2);
+        let alice_entry = inbox.get(&address_a, &alice.public_key()).unwrap();
+        assert_eq!(alice_entry.application.message, "Updated application");
+        assert_eq!(alice_entry.submissions, 2);
+        assert_eq!(alice_entry.status, ApplicationStatus::Received);
+        assert_eq!(inbox.applications(&address_b).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_status_transitions_and_encrypted_reply() {
+        let employer = Keys::generate();
+        let alice = Keys::generate();
+        let job = job_event(&employer, "job-a");
+        let mut inbox = ApplicationInbox::new(employer.clone());
+        let address = inbox.track_job(&job).unwrap();
+        inbox.ingest(&apply(&job, &alice, "Hello", Timestamp::now())).await.unwrap();
+
+        let reply = inbox
+            .set_status(&address, &alice.public_key(), ApplicationStatus::Shortlisted, Some("Let's talk".to_string()))
+            .await
+            .unwrap();
+        assert!(reply.tags.public_keys().any(|pk| *pk == alice.public_key()));
+        assert!(!reply.content.contains("shortlisted"));
+
+        let decoded = ApplicationReply::decrypt_nostr_event(&reply, &alice).unwrap();
+        assert_eq!(decoded.status, ApplicationStatus::Shortlisted);
+        assert_eq!(decoded.message.as_deref(), Some("Let's talk"));
+
+        // Replies are not applications
+        assert!(JobApplication::decrypt_nostr_event(&reply, &alice).is_err());
+
+        inbox.set_status(&address, &alice.public_key(), ApplicationStatus::Hired, None).await.unwrap();
+        assert!(inbox.set_status(&address, &alice.public_key(), ApplicationStatus::Rejected, None).await.is_err());
+        assert_eq!(inbox.get(&address, &alice.public_key()).unwrap().status, ApplicationStatus::Hired);
+    }
+
+    #[tokio::test]
+    async fn test_any_signer_decrypts_and_replies() {
+        let employer = Keys::generate();
+        let alice = Keys::generate();
+        let job = job_event(&employer, "job-a");
+        let mut inbox = ApplicationInbox::with_signer(Arc::new(employer.clone())).await.unwrap();
+        let address = inbox.track_job(&job).unwrap();
+        inbox.ingest(&apply(&job, &alice, "Hello", Timestamp::now())).await.unwrap();
+
+        let reply = inbox
+            .set_status(&address, &alice.public_key(), ApplicationStatus::Rejected, None)
+            .await
+            .unwrap();
+        assert_eq!(reply.pubkey, employer.public_key());
+        let applicant: Arc<dyn NostrSigner> = Arc::new(alice);
+        let decoded = ApplicationReply::decrypt_with(&reply, &applicant).await.unwrap();
+        assert_eq!(decoded.status, ApplicationStatus::Rejected);
+    }
+
+    #[tokio::test]
+    async fn test_matches_older_job_version_by_event_id() {
+        let employer = Keys::generate();
+        let alice = Keys::generate();
+        let job = job_event(&employer, "job-a");
+
+        // Application carrying only the "e" and "p" tags
+        let application = JobApplication::builder("Hello".to_string()).job(&job).build().unwrap();
+        let content = nip44::encrypt(
+            alice.secret_key(),
+            &employer.public_key(),
+            serde_json::to_string(&application).unwrap(),
+            nip44::Version::V2,
+        )
+        .unwrap();
+        let event = EventBuilder::new(Kind::from(KIND_JOB_APPLICATION), content)
+            .tags([
+                Tag::event(job.id),
+                Tag::public_key(employer.public_key()),
+                Tag::custom(TagKind::Custom("encrypted".into()), vec!["nip44"]),
+            ])
+            .sign_with_keys(&alice)
+            .unwrap();
+
+        let mut inbox = ApplicationInbox::new(employer.clone());
+        let address = inbox.track_job(&job).unwrap();
+        assert_eq!(inbox.ingest(&event).await.unwrap(), Some(address));
+        assert!(inbox.filter().match_event(&event, MatchEventOptions::new()));
+    }
+}
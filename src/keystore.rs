@@ -0,0 +1,138 @@
+// src/keystore.rs
+// NIP-49 password-encrypted key file (a single `ncryptsec1...` line)
+// The CLI's default key storage; the secret key is never written in plaintext
+
+use anyhow::{anyhow, Context, Result};
+use nostr_sdk::nips::nip49::{EncryptedSecretKey, KeySecurity};
+use nostr_sdk::prelude::*;
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+/// An ncryptsec key file
+#[derive(Debug, Clone)]
+pub struct Keystore {
+    path: PathBuf,
+    log_n: u8,
+}
+
+impl Keystore {
+    /// scrypt cost (2^16 rounds), the NIP-49 recommended minimum
+    pub const DEFAULT_LOG_N: u8 = 16;
+
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            log_n: Self::DEFAULT_LOG_N,
+        }
+    }
+
+    /// scrypt cost used when writing (reading uses the file's own)
+    pub fn log_n(mut self, log_n: u8) -> Self {
+        self.log_n = log_n;
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn exists(&self) -> bool {
+        self.path.exists()
+    }
+
+    /// Encrypt freshly generated keys into a new key file
+    pub fn create(&self, keys: &Keys, password: &str) -> Result<()> {
+        self.write(keys, password, KeySecurity::Medium)
+    }
+
+    /// Encrypt keys that were previously stored in plaintext (e.g. an nsec
+    /// in `.env`); NIP-49 records them as weakly handled
+    pub fn import(&self, keys: &Keys, password: &str) -> Result<()> {
+        self.write(keys, password, KeySecurity::Weak)
+    }
+
+    /// Decrypt the key file
+    pub fn unlock(&self, password: &str) -> Result<Keys> {
+        let ncryptsec = fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read keystore: {}", self.path.display()))?;
+        let encrypted = EncryptedSecretKey::from_bech32(ncryptsec.trim())
+            .map_err(|e| anyhow!("Invalid keystore {}: {}", self.path.display(), e))?;
+        let secret_key = encrypted
+            .decrypt(password)
+            .map_err(|_| anyhow!("Wrong password for keystore {}", self.path.display()))?;
+        Ok(Keys::new(secret_key))
+    }
+
+    fn write(&self, keys: &Keys, password: &str, security: KeySecurity) -> Result<()> {
+        if password.is_empty() {
+            return Err(anyhow!("Keystore password must not be empty"));
+        }
+        // Fail fast before the slow scrypt; create_new below is the real check
+        if self.exists() {
+            return Err(anyhow!("Keystore already exists: {}", self.path.display()));
+        }
+        let encrypted = EncryptedSecretKey::new(keys.secret_key(), password, self.log_n, security)?;
+
+        // Created owner-only (like an ssh key) and never over an existing
+        // file, in one step so there is no window for another process
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(&self.path).map_err(|e| match e.kind() {
+            ErrorKind::AlreadyExists => anyhow!("Keystore already exists: {}", self.path.display()),
+            _ => anyhow!("Failed to create keystore {}: {}", self.path.display(), e),
+        })?;
+        writeln!(file, "{}", encrypted.to_bech32()?)
+            .with_context(|| format!("Failed to write keystore: {}", self.path.display()))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("nosjob-{}-{}.ncryptsec", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_create_and_unlock() {
+        let path = temp_path("create");
+        let _ = fs::remove_file(&path);
+        let keystore = Keystore::new(&path).log_n(4);
+        let keys = Keys::generate();
+
+        keystore.create(&keys, "correct horse").unwrap();
+        let stored = fs::read_to_string(&path).unwrap();
+        assert!(stored.starts_with("ncryptsec1"));
+        assert!(!stored.contains(&keys.secret_key().to_bech32().unwrap()));
+
+        assert_eq!(keystore.unlock("correct horse").unwrap().public_key(), keys.public_key());
+        assert!(keystore.unlock("wrong").is_err());
+
+        // Never overwrites an existing key
+        assert!(keystore.create(&Keys::generate(), "other").is_err());
+        assert_eq!(keystore.unlock("correct horse").unwrap().public_key(), keys.public_key());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_rejects_empty_password() {
+        let path = temp_path("empty");
+        let _ = fs::remove_file(&path);
+        assert!(Keystore::new(&path).log_n(4).import(&Keys::generate(), "").is_err());
+        assert!(!path.exists());
+    }
+}
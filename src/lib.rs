@@ -3,6 +3,36 @@
 
 pub mod types;
 pub mod events;
+pub mod query;
+pub mod address;
+pub mod lifecycle;
+pub mod application;
+pub mod inbox;
+pub mod proof;
+pub mod skills;
+pub mod profile;
+pub mod versioning;
+pub mod payment;
+pub mod ranking;
+pub mod pow;
+pub mod nip05;
+pub mod trust;
+pub mod org;
+pub mod signer;
+pub mod keystore;
+pub mod bunker;
+pub mod offline;
+pub mod relays;
+pub mod publish;
+pub mod store;
+pub mod sync;
+pub mod reconcile;
+
+#[cfg(test)]
+pub(crate) mod test_relay;
+
+#[cfg(test)]
+pub(crate) mod test_fixtures;
 
 // Re-export commonly used types
 pub use types::{
@@ -12,7 +42,26 @@ pub use types::{
     HiringOrganization,
     JobLocation,
     BaseSalary,
+    JobStatus,
     ValidationError,
 };
 
-pub use events::JobsFilter;
\ No newline at end of file
+pub use events::JobsFilter;
+pub use query::JobQuery;
+pub use address::JobAddress;
+pub use application::JobApplication;
+pub use proof::{EmployerVerification, ProfessionalProof};
+pub use skills::{SkillEdge, SkillGraph};
+pub use profile::{ProfessionalEvent, Profile, SkillAssertion};
+pub use versioning::{MigrationRegistry, SchemaError};
+pub use payment::{Bolt11Invoice, PaymentPolicy, PaymentProof};
+pub use ranking::{JobRanker, JobScorer, RankedJob};
+pub use trust::{Delegations, TrustVerdict};
+pub use org::OrgMembership;
+pub use signer::SignEvent;
+pub use offline::{EventTemplate, IntegrityError};
+pub use relays::{RelayConfig, RelayHealth};
+pub use publish::{PublishReport, Publisher};
+pub use store::{JobSearch, JobStore};
+pub use sync::Syncer;
+pub use reconcile::Reconciler;
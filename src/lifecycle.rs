@@ -0,0 +1,190 @@
+// src/lifecycle.rs
+// Job listing lifecycle: update, status changes and NIP-09 withdrawal
+// Kind 39993 is parameterised-replaceable, so updates re-issue the same address
+
+use crate::address::JobAddress;
+use crate::events::KIND_JOB_LISTING;
+use crate::signer::SignEvent;
+use crate::types::*;
+use anyhow::{anyhow, Result};
+use nostr_sdk::prelude::*;
+
+impl JobListing {
+    /// Current status (listings without one are open)
+    pub fn status(&self) -> JobStatus {
+        self.status.clone().unwrap_or(JobStatus::Open)
+    }
+
+    /// Whether the listing should be shown to job seekers
+    pub fn is_open(&self) -> bool {
+        self.status() == JobStatus::Open
+    }
+
+    /// Re-issue the listing (same `d` tag), replacing the published version
+    pub fn update_event(&self, keys: &Keys) -> Result<Event> {
+        self.to_nostr_event(keys)
+    }
+
+    /// `update_event` for any `NostrSigner`
+    pub async fn update_sign_with<S>(&self, signer: &S) -> Result<Event>
+    where
+        S: NostrSigner + ?Sized,
+    {
+        self.sign_with(signer).await
+    }
+
+    /// Re-issue the listing with a new status field and `status` tag
+    pub fn status_event(&self, status: JobStatus, keys: &Keys) -> Result<Event> {
+        self.with_status(status).to_nostr_event(keys)
+    }
+
+    /// `status_event` for any `NostrSigner`
+    pub async fn status_sign_with<S>(&self, status: JobStatus, signer: &S) -> Result<Event>
+    where
+        S: NostrSigner + ?Sized,
+    {
+        self.with_status(status).sign_with(signer).await
+    }
+
+    fn with_status(&self, status: JobStatus) -> Self {
+        let mut job = self.clone();
+        job.status = Some(status);
+        job
+    }
+}
+
+/// NIP-09 deletion request withdrawing a published listing.
+/// References both the event id (`e`) and its address (`a`) so relays drop
+/// this version and any older version of the same job.
+pub fn deletion_request(event: &Event, reason: Option<&str>, keys: &Keys) -> Result<Event> {
+    Ok(unsigned_deletion_request(event, reason, keys.public_key())?.sign_with_keys(keys)?)
+}
+
+/// Unsigned `deletion_request`, for signing with any `NostrSigner`
+pub fn unsigned_deletion_request(event: &Event, reason: Option<&str>, author: PublicKey) -> Result<UnsignedEvent> {
+    if event.kind != Kind::from(KIND_JOB_LISTING) {
+        return Err(anyhow!(
+            "Invalid event kind: expected {}, got {}",
+            KIND_JOB_LISTING,
+            event.kind
+        ));
+    }
+    if event.pubkey != author {
+        return Err(anyhow!("Only the author can request deletion of a job listing"));
+    }
+
+    let address = JobAddress::from_event(event)?;
+    let mut request = EventDeletionRequest::new()
+        .id(event.id)
+        .coordinate(address.to_coordinate());
+    if let Some(reason) = reason {
+        request = request.reason(reason);
+    }
+
+    Ok(EventBuilder::delete(request)
+        .tag(Tag::custom(TagKind::k(), vec![KIND_JOB_LISTING.to_string()]))
+        .build(author))
+}
+
+/// Whether any of `deletions` (kind 5) withdraws `event`.
+/// Only deletions by the same author count; address references apply to
+/// versions created at or before the deletion.
+pub fn is_deleted<'a, I>(event: &Event, deletions: I) -> bool
+where
+    I: IntoIterator<Item = &'a Event>,
+{
+    let address = JobAddress::from_event(event).ok();
+    deletions.into_iter().any(|deletion| {
+        if deletion.kind != Kind::EventDeletion || deletion.pubkey != event.pubkey {
+            return false;
+        }
+        let by_id = deletion.tags.event_ids().any(|id| *id == event.id);
+        let by_address = address.as_ref().is_some_and(|address| {
+            deletion.created_at >= event.created_at
+                && deletion
+                    .tags
+                    .coordinates()
+                    .any(|c| JobAddress::from(c.clone()) == *address)
+        });
+        by_id || by_address
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::sample_job;
+
+    #[test]
+    fn test_status_event_round_trip() {
+        let keys = Keys::generate();
+        let job = sample_job("job-001");
+        let open = job.to_nostr_event(&keys).unwrap();
+        let filled = job.status_event(JobStatus::Filled, &keys).unwrap();
+
+        assert_eq!(JobAddress::from_event(&open).unwrap(), JobAddress::from_event(&filled).unwrap());
+        assert!(filled.tags.iter().any(|t| t.as_slice() == ["status", "filled"]));
+
+        let decoded = JobListing::from_nostr_event(&filled).unwrap();
+        assert_eq!(decoded.status(), JobStatus::Filled);
+        assert!(!decoded.is_open());
+        assert!(JobListing::from_nostr_event(&open).unwrap().is_open());
+
+        // Tag-only decode sees the same status
+        assert_eq!(JobListing::from_tags(&filled).unwrap().status, Some(JobStatus::Filled));
+    }
+
+    #[tokio::test]
+    async fn test_status_sign_with_any_signer() {
+        let keys = Keys::generate();
+        let signer: std::sync::Arc<dyn NostrSigner> = std::sync::Arc::new(keys.clone());
+        let job = sample_job("job-001");
+
+        let updated = job.update_sign_with(&signer).await.unwrap();
+        let closed = job.status_sign_with(JobStatus::Closed, &signer).await.unwrap();
+        assert_eq!(updated.pubkey, keys.public_key());
+        assert_eq!(JobAddress::from_event(&updated).unwrap(), JobAddress::from_event(&closed).unwrap());
+        assert_eq!(JobListing::from_nostr_event(&closed).unwrap().status(), JobStatus::Closed);
+        assert_eq!(job.status, None);
+    }
+
+    #[test]
+    fn test_deletion_request_references_id_and_address() {
+        let keys = Keys::generate();
+        let event = sample_job("job-001").to_nostr_event(&keys).unwrap();
+        let deletion = deletion_request(&event, Some("position withdrawn"), &keys).unwrap();
+
+        assert_eq!(deletion.kind, Kind::EventDeletion);
+        assert_eq!(deletion.content, "position withdrawn");
+        assert!(deletion.tags.event_ids().any(|id| *id == event.id));
+        assert_eq!(
+            deletion.tags.coordinates().next().cloned().map(JobAddress::from),
+            Some(JobAddress::from_event(&event).unwrap())
+        );
+        assert!(is_deleted(&event, [&deletion]));
+    }
+
+    #[test]
+    fn test_deletion_rules() {
+        let keys = Keys::generate();
+        let other = Keys::generate();
+        let event = sample_job("job-001").to_nostr_event(&keys).unwrap();
+
+        assert!(deletion_request(&event, None, &other).is_err());
+
+        // A deletion signed by someone else is ignored
+        let forged = EventBuilder::delete(EventDeletionRequest::new().id(event.id))
+            .sign_with_keys(&other)
+            .unwrap();
+        assert!(!is_deleted(&event, [&forged]));
+
+        // Address deletions don't reach versions published afterwards
+        let deletion = EventBuilder::delete(
+            EventDeletionRequest::new().coordinate(JobAddress::from_event(&event).unwrap().to_coordinate()),
+        )
+        .custom_created_at(event.created_at - 60)
+        .sign_with_keys(&keys)
+        .unwrap();
+        assert!(!is_deleted(&event, [&deletion]));
+    }
+}
@@ -1,8 +1,26 @@
 use nostr_sdk::prelude::*;
-use anyhow::{Result, Context};
+use anyhow::{anyhow, Result, Context};
 use dotenvy::dotenv;
 use std::fs;
-use nosjob::{JobListing, EmploymentType, JobLocationType};
+use std::sync::Arc;
+use std::time::Duration;
+use nosjob::{lifecycle, JobListing, EmploymentType, JobLocationType, ProfessionalEvent, SchemaError};
+use nosjob::bunker::BunkerSigner;
+use nosjob::keystore::Keystore;
+use nosjob::pow::PowOptions;
+use nosjob::SignEvent;
+use nosjob::offline::{check_presigned, EventTemplate};
+use nosjob::nip05::{HttpResolver, Nip05Verifier};
+use nosjob::org::OrgMembership;
+use nosjob::trust::{self, Delegations, TrustVerdict};
+use nosjob::ranking::{EmployerHistoryScorer, JobRanker, Nip05Scorer};
+use nosjob::publish::Publisher;
+use nosjob::relays::{self, RelayConfig, RelayHealth};
+use nosjob::store::{Ingested, JobSearch, JobStore};
+use nosjob::reconcile::Reconciler;
+use nosjob::sync::{Applied, Syncer};
+use nosjob::payment::PaymentPolicy;
+use std::collections::HashSet;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -39,20 +57,103 @@ fn load_env() {
     dotenv().ok();
 }
 
-/// Ensure a private key exists: load from .env or generate
-fn ensure_private_key() -> String {
+/// Default NIP-49 key file; override with NOSJOB_KEYSTORE
+const DEFAULT_KEYSTORE: &str = "nosjob.ncryptsec";
+
+/// Default relay config (read/write sets); override with NOSJOB_RELAYS
+const DEFAULT_RELAY_CONFIG: &str = "nosjob-relays.json";
+
+/// Default relay health stats; override with NOSJOB_RELAY_HEALTH
+const DEFAULT_RELAY_HEALTH: &str = "nosjob-relay-health.json";
+
+/// How long to wait for relays to connect
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default local job index; override with NOSJOB_DB
+const DEFAULT_STORE: &str = "nosjob.db";
+
+fn store_path() -> String {
+    std::env::var("NOSJOB_DB").unwrap_or_else(|_| DEFAULT_STORE.to_string())
+}
+
+fn relay_config_path() -> String {
+    std::env::var("NOSJOB_RELAYS").unwrap_or_else(|_| DEFAULT_RELAY_CONFIG.to_string())
+}
+
+fn relay_health_path() -> String {
+    std::env::var("NOSJOB_RELAY_HEALTH").unwrap_or_else(|_| DEFAULT_RELAY_HEALTH.to_string())
+}
+
+/// Relay config with this command's `--relay` overrides applied to both sets
+fn load_relay_config(args: &[String]) -> Result<RelayConfig> {
+    let overrides = flag_values(args, "--relay")
+        .into_iter()
+        .map(RelayUrl::parse)
+        .collect::<Result<Vec<_>, _>>()
+        .context("--relay expects a ws:// or wss:// URL")?;
+    Ok(RelayConfig::load(relay_config_path())?.with_overrides(&overrides, &overrides))
+}
+
+/// Connect to `relays`, healthiest first, and save the updated health stats
+async fn connect_relays(relays: &[RelayUrl], health: &mut RelayHealth) -> Result<Client> {
+    let relays = health.prioritise(relays);
+    for relay in &relays {
+        println!("   Connecting to: {}", relay);
+    }
+    let client = relays::connect(&relays, health, CONNECT_TIMEOUT).await;
+    health.save(relay_health_path())?;
+    client
+}
+
+/// Read a password from NOSJOB_PASSWORD, or prompt without echo
+fn read_password(prompt: &str) -> Result<String> {
+    if let Ok(password) = std::env::var("NOSJOB_PASSWORD") {
+        return Ok(password);
+    }
+    // Never fall back to an echoing prompt
+    rpassword::prompt_password(prompt)
+        .context("Cannot prompt for a password without echo here; set NOSJOB_PASSWORD instead")
+}
+
+/// Signer for posting: a NIP-46 bunker when given, otherwise the
+/// password-encrypted keystore (created on first use)
+async fn load_signer(bunker: Option<&str>) -> Result<Arc<dyn NostrSigner>> {
     load_env();
+    if let Some(uri) = bunker.map(str::to_string).or_else(|| std::env::var("NOSJOB_BUNKER").ok()) {
+        println!("🔐 Connecting to remote signer...");
+        let signer = BunkerSigner::connect_relays(&uri, Duration::from_secs(60)).await?;
+        return Ok(Arc::new(signer));
+    }
 
-    if let Ok(key) = std::env::var("PRIVATE_KEY") {
-        println!("🔑 Loaded existing PRIVATE_KEY from .env");
-        key
-    } else {
-        let keys = Keys::generate();
-        let sk_bech32 = keys.secret_key().to_bech32().expect("Failed to convert secret key to bech32");
-        fs::write(".env", format!("PRIVATE_KEY={}\n", sk_bech32)).expect("Failed to write .env");
-        println!("🔑 Generated new PRIVATE_KEY and saved to .env");
-        sk_bech32
+    let path = std::env::var("NOSJOB_KEYSTORE").unwrap_or_else(|_| DEFAULT_KEYSTORE.to_string());
+    let keystore = Keystore::new(&path);
+    if keystore.exists() {
+        let keys = keystore.unlock(&read_password(&format!("🔑 Password for {}: ", path))?)?;
+        println!("🔑 Unlocked {}", path);
+        return Ok(Arc::new(keys));
     }
+
+    // First run: encrypt a legacy plaintext PRIVATE_KEY, or generate a key
+    let legacy = std::env::var("PRIVATE_KEY").ok();
+    let password = read_password(&format!("🔑 New password for {}: ", path))?;
+    if std::env::var("NOSJOB_PASSWORD").is_err() && read_password("🔑 Repeat password: ")? != password {
+        return Err(anyhow!("Passwords do not match"));
+    }
+    let keys = match legacy {
+        Some(nsec) => {
+            let keys = Keys::parse(&nsec).context("Invalid PRIVATE_KEY")?;
+            keystore.import(&keys, &password)?;
+            println!("🔑 Encrypted PRIVATE_KEY into {}; delete it from .env", path);
+            keys
+        }
+        None => {
+            let keys = Keys::generate();
+            keystore.create(&keys, &password)?;
+            println!("🔑 Generated a new key, saved encrypted to {}", path);
+            keys
+        }
+    };
+    Ok(Arc::new(keys))
 }
 
 /// Parse employment type from string
@@ -90,7 +191,7 @@ fn load_job_from_file(path: &str) -> Result<SimpleJobJson> {
 }
 
 /// Convert SimpleJobJson to JobListing with UUID
-fn convert_to_job_listing(simple: &SimpleJobJson, pubkey: &str) -> Result<JobListing> {
+fn convert_to_job_listing(simple: &SimpleJobJson, pubkey: Option<&str>) -> Result<JobListing> {
     // Generate UUID for uniqueness
     let uuid = Uuid::new_v4();
     let uuid_short = uuid.to_string().split('-').next().unwrap().to_string();
@@ -120,8 +221,11 @@ fn convert_to_job_listing(simple: &SimpleJobJson, pubkey: &str) -> Result<JobLis
         simple.salary.currency.clone(),
         "YEAR".to_string(),
     )
-    .skills(simple.skills.clone())
-    .nostr_pubkey(pubkey.to_string());
+    .skills(simple.skills.clone());
+
+    if let Some(pubkey) = pubkey {
+        builder = builder.nostr_pubkey(pubkey.to_string());
+    }
 
     if let Some(valid_through) = &simple.valid_through {
         builder = builder.valid_through(valid_through.clone());
@@ -138,9 +242,41 @@ fn convert_to_job_listing(simple: &SimpleJobJson, pubkey: &str) -> Result<JobLis
 fn print_help() {
     println!("🚀 Nostr Job Poster\n");
     println!("USAGE:");
-    println!("  cargo run -- <job.json> [--publish]    Post a job listing");
-    println!("  cargo run -- view_jobs                  View recent job listings");
+    println!("  cargo run -- <job.json> [--publish] [--pow <bits>] [--bunker <bunker://...>]");
+    println!("               [--quorum <n>] [--report <file>]");
+    println!("                                          Post a job listing (optionally with NIP-13 PoW)");
+    println!("  cargo run -- view_jobs [--paid-only] [--min-sats <n>] [--payee <node id>]");
+    println!("                                          View recent job listings, best first");
+    println!("  cargo run -- export <job.json> [--pubkey <npub>] [--out <file>]");
+    println!("                                          Write an unsigned event template for approval");
+    println!("  cargo run -- sign <template.json> [--digest <hex>] [--out <file>] [--bunker <uri>]");
+    println!("                                          Sign an approved template (e.g. air-gapped)");
+    println!("  cargo run -- publish <signed.json> [--template <template.json>] [--quorum <n>] [--report <file>]");
+    println!("                                          Check and publish a pre-signed event");
+    println!("  cargo run -- search [\"words\"] [--skill <s>] [--company <c>] [--country <cc>] [--remote] [--min-salary <n>] [--limit <n>]");
+    println!("                                          Search listings saved by view_jobs or sync, offline");
+    println!("  cargo run -- sync [--no-backfill]       Keep the local index in sync with the read relays");
+    println!("  cargo run -- sync --negentropy [--report <file>]");
+    println!("                                          Fetch only what the store lacks (NIP-77), then exit");
+    println!("  cargo run -- relays [import <npub> [--replace]]");
+    println!("                                          Show relay sets and health, or import a NIP-65 list");
     println!("  cargo run -- help                       Show this help\n");
+    println!("RELAYS:");
+    println!("  Read and write relay sets come from {} (NOSJOB_RELAYS),", DEFAULT_RELAY_CONFIG);
+    println!("  or the defaults if it doesn't exist. Any command takes --relay <url>");
+    println!("  (repeatable) to use just those relays. Per-relay health is kept in");
+    println!("  {} (NOSJOB_RELAY_HEALTH).", DEFAULT_RELAY_HEALTH);
+    println!("  Publishing retries relays that are down or busy and exits non-zero");
+    println!("  unless --quorum relays (default 1) accepted; --report writes a JSON report.\n");
+    println!("PAYMENTS:");
+    println!("  A listing only counts as paid when its proof is a mainnet invoice that");
+    println!("  names the listing's identifier and is signed by an accepted Lightning");
+    println!("  node: --payee <node id> (repeatable) or the comma-separated NOSJOB_PAYEES.\n");
+    println!("KEYS:");
+    println!("  Signs with the password-encrypted (NIP-49) key in {} (NOSJOB_KEYSTORE),", DEFAULT_KEYSTORE);
+    println!("  created on first use; an old PRIVATE_KEY in .env is encrypted into it.");
+    println!("  NOSJOB_PASSWORD skips the prompt. --bunker (or NOSJOB_BUNKER) signs");
+    println!("  with a NIP-46 remote signer instead.\n");
     println!("EXAMPLES:");
     println!("  cargo run -- my-job.json               Dry run (preview)");
     println!("  cargo run -- my-job.json --publish     Publish to relays");
@@ -148,24 +284,12 @@ fn print_help() {
 }
 
 /// View recent job listings from relays
-async fn view_jobs() -> Result<()> {
+async fn view_jobs(paid_only: bool, min_sats: Option<u64>, payments: &PaymentPolicy, config: &RelayConfig) -> Result<()> {
     println!("🔍 Fetching recent job listings...\n");
     
-    let keys = Keys::generate(); // Anonymous viewing
-    let client = Client::new(keys);
-    
-    let relays = vec![
-        "wss://relay.damus.io",
-        "wss://relay.nostr.band",
-        "wss://nostr.wine",
-    ];
-    
-    for relay in &relays {
-        println!("   Connecting to: {}", relay);
-        client.add_relay(*relay).await?;
-    }
-    
-    client.connect().await;
+    // Anonymous viewing from the read relays
+    let mut health = RelayHealth::load(relay_health_path())?;
+    let client = connect_relays(&config.read, &mut health).await?;
     
     let filter = nosjob::JobsFilter::new()
         .limit(10)
@@ -174,37 +298,509 @@ async fn view_jobs() -> Result<()> {
     println!("\n📡 Fetching jobs from relays...");
     let timeout = std::time::Duration::from_secs(10);
     let events = client.fetch_events(filter, timeout).await?;
+
+    // Withdrawn listings (NIP-09 deletion requests from their authors)
+    let deletions = if events.is_empty() {
+        Events::default()
+    } else {
+        let filter = Filter::new()
+            .kind(Kind::EventDeletion)
+            .authors(events.iter().map(|e| e.pubkey));
+        client.fetch_events(filter, timeout).await?
+    };
+
+    // Keep them for offline `search`
+    let mut store = JobStore::open(store_path())?;
+    let indexed = store.ingest_all(events.iter())?;
+    for deletion in deletions.iter() {
+        store.apply_deletion(deletion)?;
+    }
     
     println!("\n📋 Found {} job listings:\n", events.len());
     
-    for (i, event) in events.iter().enumerate() {
-        match JobListing::from_nostr_event(event) {
-            Ok(job) => {
-                println!("{}. {}", i + 1, job.title);
-                println!("   Company: {}", job.hiring_organization.name);
-                println!("   Type: {:?}", job.employment_type);
-                println!("   Location: {:?}", job.job_location_type.as_ref()
-                    .and_then(|v| v.first())
-                    .unwrap_or(&JobLocationType::Telecommute));
-                if let Some(skills) = &job.skills {
-                    println!("   Skills: {}", skills.join(", "));
+    let mut hidden = 0;
+    let mut open: Vec<(JobListing, &Event)> = Vec::new();
+    for event in events.iter() {
+        if lifecycle::is_deleted(event, deletions.iter()) {
+            hidden += 1;
+            continue;
+        }
+        match ProfessionalEvent::from_nostr_event(event) {
+            // Resumes and skill assertions share the kind; not listings
+            Ok(ProfessionalEvent::Resume(_) | ProfessionalEvent::Skill(_)) => {
+                hidden += 1;
+            }
+            Ok(ProfessionalEvent::Job(job)) if !job.is_open() => {
+                hidden += 1;
+            }
+            Ok(ProfessionalEvent::Job(job)) => open.push((*job, event)),
+            // Newer schema than this client reads: show what the tags say
+            Err(e) if matches!(e.downcast_ref(), Some(SchemaError::UnsupportedVersion { .. })) => {
+                println!("⚠️  {}", e);
+                if let Ok(job) = JobListing::from_tags(event) {
+                    println!("   Title: {}", job.title);
+                    println!("   Company: {}", job.hiring_organization.name);
                 }
                 println!("   Event ID: {}", event.id.to_bech32()?);
                 println!();
             }
             Err(e) => {
-                println!("{}. [Parse error: {}]", i + 1, e);
+                println!("[Parse error: {}]", e);
                 println!("   Event ID: {}", event.id.to_bech32()?);
                 println!();
             }
         }
     }
+
+    // Employers whose NIP-05 identifier maps to the key that signed the listing
+    let verifier = Nip05Verifier::new(
+        HttpResolver::new().timeout(std::time::Duration::from_secs(5)),
+        std::time::Duration::from_secs(3600),
+    );
+    let mut nip05_verified = HashSet::new();
+    for (job, event) in &open {
+        if job.nip05_verified.is_some() && verifier.verify_event(event).await.is_verified() {
+            nip05_verified.insert(event.pubkey);
+        }
+    }
+
+    // Recruiters authorised by the orgs the listings name (NIP-51 recruiter lists)
+    let orgs: HashSet<PublicKey> = open
+        .iter()
+        .filter_map(|(job, _)| job.hiring_organization.nostr_pubkey.as_deref())
+        .chain(open.iter().filter_map(|(job, _)| job.nostr_employer_pubkey.as_deref()))
+        .filter_map(|pk| trust::normalize_pubkey(pk).ok())
+        .collect();
+    let delegations = if orgs.is_empty() {
+        Delegations::new()
+    } else {
+        let lists = client.fetch_events(OrgMembership::filter(orgs), timeout).await?;
+        Delegations::from_membership_events(lists.iter())
+    };
+
+    // Best first: verified payment, proof-of-work, completeness, NIP-05, employer history
+    let mut ranker = JobRanker::with_payment_policy(payments.clone())
+        .scorer(Nip05Scorer::new(nip05_verified.iter().copied()), 2.0)
+        .scorer(EmployerHistoryScorer::from_events(events.iter(), 180), 1.0)
+        .show_unpaid(!paid_only);
+    if let Some(sats) = min_sats {
+        ranker = ranker.min_payment_sats(sats);
+    }
+    let ranked = ranker.rank(open.iter().map(|(job, event)| (job, *event)));
+    hidden += open.len() - ranked.len();
+
+    for (i, ranked_job) in ranked.iter().enumerate() {
+        let job = ranked_job.job;
+        match ranked_job.paid_sats {
+            Some(sats) => println!("{}. [💰 Paid Job] {} – {} sats", i + 1, job.title, sats),
+            None => println!("{}. {}", i + 1, job.title),
+        }
+        println!("   Company: {}", job.hiring_organization.name);
+        match job.trust_verdict(ranked_job.event, &delegations) {
+            Ok(TrustVerdict::Mismatch { claimed }) => println!(
+                "   🚩 Claims employer {} but signed by {}",
+                claimed.to_bech32()?,
+                ranked_job.event.pubkey.to_bech32()?
+            ),
+            Ok(TrustVerdict::Delegated { employer }) => {
+                println!("   🤝 Posted by a recruiter authorised by {}", employer.to_bech32()?)
+            }
+            Ok(TrustVerdict::Undeclared) => println!("   ❔ No employer key declared; the company name is unverified"),
+            Err(e) => println!("   🚩 {}", e),
+            Ok(TrustVerdict::SignerIsEmployer) => {}
+        }
+        if let Some(nip05) = &job.nip05_verified {
+            let mark = if nip05_verified.contains(&ranked_job.event.pubkey) { "✅" } else { "❔ unverified" };
+            println!("   NIP-05: {} {}", nip05, mark);
+        }
+        println!("   Type: {:?}", job.employment_type);
+        println!("   Location: {:?}", job.job_location_type.as_ref()
+            .and_then(|v| v.first())
+            .unwrap_or(&JobLocationType::Telecommute));
+        if let Some(skills) = &job.skills {
+            println!("   Skills: {}", skills.join(", "));
+        }
+        println!("   Score: {:.2}", ranked_job.score);
+        println!("   Event ID: {}", ranked_job.event.id.to_bech32()?);
+        println!();
+    }
+
+    if hidden > 0 {
+        println!("🙈 Hidden {} closed, withdrawn, unpaid or non-job event(s)", hidden);
+    }
+    let new = indexed.iter().filter(|i| matches!(i, Ingested::Inserted | Ingested::Replaced)).count();
+    println!("💾 {} new or updated listing(s) saved to {} ({} total)", new, store_path(), store.len()?);
     
     Ok(())
 }
 
+/// Value following `flag` on the command line
+fn flag_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|arg| arg == flag)
+        .and_then(|i| args.get(i + 1))
+        .map(String::as_str)
+}
+
+/// Every value following a repeatable `flag`
+fn flag_values<'a>(args: &'a [String], flag: &str) -> Vec<&'a str> {
+    args.windows(2)
+        .filter(|pair| pair[0] == flag)
+        .map(|pair| pair[1].as_str())
+        .collect()
+}
+
+/// Lightning nodes whose invoices count as paid: `--payee <node id>`
+/// (repeatable) or the comma-separated NOSJOB_PAYEES
+fn payment_policy(args: &[String]) -> Result<PaymentPolicy> {
+    let from_env = std::env::var("NOSJOB_PAYEES").unwrap_or_default();
+    let mut policy = PaymentPolicy::default();
+    for node_id in flag_values(args, "--payee").into_iter().chain(from_env.split(',')) {
+        let node_id = node_id.trim();
+        if node_id.is_empty() {
+            continue;
+        }
+        let node_id = node_id
+            .parse()
+            .with_context(|| format!("Invalid Lightning node id: {}", node_id))?;
+        policy = policy.payee(node_id);
+    }
+    Ok(policy)
+}
+
+/// `<stem>.<suffix>` next to `path`, for default output files
+fn sibling_path(path: &str, suffix: &str) -> String {
+    let stem = path.strip_suffix(".json").unwrap_or(path);
+    format!("{}.{}", stem, suffix)
+}
+
+fn print_job_details(job: &JobListing) {
+    println!("📋 Job Details:");
+    println!("   ID: {}", job.identifier);
+    println!("   Title: {}", job.title);
+    println!("   Company: {}", job.hiring_organization.name);
+    println!("   Type: {:?}", job.employment_type);
+    println!("   Location: {:?}", job.job_location_type);
+    println!("   Skills: {}", job.skills.as_ref().map(|s| s.join(", ")).unwrap_or_default());
+    
+    if let Some(salary) = &job.base_salary
+        && let nosjob::types::MonetaryValue::Range(range) = &salary.value
+        && let (Some(min), Some(max)) = (range.min_value, range.max_value)
+    {
+        println!("   Salary: ${} - ${} {} per {}", 
+            min as u64, max as u64, salary.currency, range.unit_text);
+    }
+}
+
+/// Send a signed listing to every write relay, retrying transient failures,
+/// and fail unless `--quorum` relays (default 1) accepted it
+async fn publish_event(event: &Event, job: &JobListing, config: &RelayConfig, args: &[String]) -> Result<()> {
+    let quorum = flag_value(args, "--quorum")
+        .map(str::parse::<usize>)
+        .transpose()
+        .context("--quorum expects a number of relays")?
+        .unwrap_or(1);
+
+    println!("\n📡 Publishing to relays (quorum {})...", quorum);
+    let client = Client::default(); // Event is already signed
+    for relay in RelayHealth::load(relay_health_path())?.prioritise(&config.write) {
+        println!("   Connecting to: {}", relay);
+        client.add_relay(relay).await?;
+    }
+    let report = Publisher::new(quorum).timeout(CONNECT_TIMEOUT).publish(&client, event).await;
+
+    let mut health = RelayHealth::load(relay_health_path())?;
+    health.record_report(&report);
+    health.save(relay_health_path())?;
+    if let Some(path) = flag_value(args, "--report") {
+        fs::write(path, serde_json::to_string_pretty(&report)?)
+            .with_context(|| format!("Failed to write publish report: {}", path))?;
+        println!("   Report written to: {}", path);
+    }
+
+    for outcome in &report.relays {
+        match &outcome.error {
+            None => println!("   ✓ {} (attempt {})", outcome.relay, outcome.attempts),
+            Some(e) => println!("   ✗ {}: {} (after {} attempt(s))", outcome.relay, e, outcome.attempts),
+        }
+    }
+    report.ensure_quorum()?;
+    println!("\n✅ Published successfully!");
+    println!("   Accepted by {} of {} relays", report.accepted, report.relays.len());
+
+    println!("\n🔗 Event Identifiers:");
+    println!("   Event ID: {}", event.id.to_bech32()?);
+    println!("   Pubkey: {}", event.pubkey.to_bech32()?);
+    println!("   Address: {}", job.address(event.pubkey).to_naddr(&[])?);
+    Ok(())
+}
+
+/// Write an unsigned event template for offline approval
+fn export_template(job_file: &str, args: &[String]) -> Result<()> {
+    let approver = flag_value(args, "--pubkey")
+        .map(PublicKey::parse)
+        .transpose()
+        .context("--pubkey expects an npub or hex pubkey")?;
+    let out = flag_value(args, "--out")
+        .map(str::to_string)
+        .unwrap_or_else(|| sibling_path(job_file, "template.json"));
+
+    let simple_job = load_job_from_file(job_file)?;
+    let job = convert_to_job_listing(&simple_job, approver.map(|pk| pk.to_hex()).as_deref())?;
+    print_job_details(&job);
+
+    let template = EventTemplate::from_job(&job, approver)?;
+    fs::write(&out, serde_json::to_string_pretty(&template)?)
+        .with_context(|| format!("Failed to write template: {}", out))?;
+    println!("\n📝 Unsigned template written to: {}", out);
+    println!("   Digest: {}", template.digest());
+    if approver.is_none() {
+        println!("   Any key may sign it (use --pubkey to require the approver's key)");
+    }
+    println!("\n💡 On the signing machine, run:");
+    println!("   cargo run -- sign {} --digest {}", out, template.digest());
+    Ok(())
+}
+
+/// Sign an approved template, checking its digest and the signed result
+async fn sign_template(template_file: &str, args: &[String]) -> Result<()> {
+    let out = flag_value(args, "--out")
+        .map(str::to_string)
+        .unwrap_or_else(|| sibling_path(template_file.trim_end_matches(".template.json"), "signed.json"));
+    let json = fs::read_to_string(template_file)
+        .with_context(|| format!("Failed to read template: {}", template_file))?;
+    let template: EventTemplate = serde_json::from_str(&json)
+        .with_context(|| format!("Invalid event template: {}", template_file))?;
+
+    if let Ok(job) = serde_json::from_str::<JobListing>(&template.content) {
+        print_job_details(&job);
+    }
+    println!("\n🔏 Template digest: {}", template.digest());
+    match flag_value(args, "--digest") {
+        Some(approved) => {
+            template.check_digest(approved)?;
+            println!("   ✅ Matches the approved digest");
+        }
+        None => println!("   ⚠️  No --digest given; compare it with the exported one before signing"),
+    }
+
+    let signer = load_signer(flag_value(args, "--bunker")).await?;
+    let event = template.sign_with(&signer).await?;
+    fs::write(&out, event.as_pretty_json())
+        .with_context(|| format!("Failed to write signed event: {}", out))?;
+    println!("\n✅ Signed event written to: {}", out);
+    println!("   Event ID: {}", event.id);
+    println!("\n💡 To publish it, run:");
+    println!("   cargo run -- publish {} --template {}", out, template_file);
+    Ok(())
+}
+
+/// Publish a pre-signed listing after integrity checks
+async fn publish_presigned(event_file: &str, args: &[String]) -> Result<()> {
+    let json = fs::read_to_string(event_file)
+        .with_context(|| format!("Failed to read signed event: {}", event_file))?;
+    let event = Event::from_json(json).with_context(|| format!("Invalid signed event: {}", event_file))?;
+    let template = flag_value(args, "--template")
+        .map(|path| -> Result<EventTemplate> {
+            let json = fs::read_to_string(path).with_context(|| format!("Failed to read template: {}", path))?;
+            Ok(serde_json::from_str(&json)?)
+        })
+        .transpose()?;
+
+    let job = check_presigned(&event, template.as_ref())?;
+    print_job_details(&job);
+    match &template {
+        Some(_) => println!("\n✅ Signature valid and content matches the approved template"),
+        None => println!("\n✅ Signature valid (no --template given to compare against)"),
+    }
+    publish_event(&event, &job, &load_relay_config(args)?, args).await
+}
+
+/// Search the local job index (filled by `view_jobs`) without going online
+fn search_jobs(args: &[String]) -> Result<()> {
+    let mut search = JobSearch::new().not_expired();
+    if let Some(text) = args.get(2).filter(|arg| !arg.starts_with("--")) {
+        search = search.text(text);
+    }
+    for skill in flag_values(args, "--skill") {
+        search = search.skill(skill);
+    }
+    for company in flag_values(args, "--company") {
+        search = search.company(company);
+    }
+    for country in flag_values(args, "--country") {
+        search = search.country(country);
+    }
+    if args.iter().any(|arg| arg == "--remote") {
+        search = search.location_type(JobLocationType::Telecommute);
+    }
+    if let Some(salary) = flag_value(args, "--min-salary") {
+        let amount = salary.parse::<f64>().context("--min-salary expects a yearly amount")?;
+        search = search.salary_min(amount, "YEAR");
+    }
+    let limit = flag_value(args, "--limit")
+        .map(str::parse::<usize>)
+        .transpose()
+        .context("--limit expects a number")?
+        .unwrap_or(20);
+
+    let store = JobStore::open(store_path())?;
+    let results = store.search(&search.limit(limit))?;
+    println!("🔎 {} match(es) in {} ({} listings stored)\n", results.len(), store_path(), store.len()?);
+    for (i, stored) in results.iter().enumerate() {
+        let job = &stored.job;
+        println!("{}. {}", i + 1, job.title);
+        println!("   Company: {}", job.hiring_organization.name);
+        if let Some(skills) = &job.skills {
+            println!("   Skills: {}", skills.join(", "));
+        }
+        if let Some(valid_through) = &job.valid_through {
+            println!("   Valid through: {}", valid_through);
+        }
+        println!("   Address: {}", job.address(stored.event.pubkey).to_naddr(&[])?);
+        println!();
+    }
+    Ok(())
+}
+
+/// Keep the local job index in sync with the read relays until Ctrl-C
+async fn sync_jobs(args: &[String]) -> Result<()> {
+    if args.iter().any(|arg| arg == "--negentropy") {
+        return reconcile_jobs(args).await;
+    }
+    let config = load_relay_config(args)?;
+    let mut store = JobStore::open(store_path())?;
+    let syncer = Syncer::new().backfill(!args.iter().any(|arg| arg == "--no-backfill"));
+
+    println!("🔄 Syncing job listings into {}...", store_path());
+    let mut health = RelayHealth::load(relay_health_path())?;
+    let client = connect_relays(&config.read, &mut health).await?;
+    println!("   Catching up, then following live updates (Ctrl-C to stop)\n");
+
+    let shutdown = async {
+        let _ = tokio::signal::ctrl_c().await;
+    };
+    let stats = syncer
+        .run(&client, &mut store, shutdown, |relay, event, applied| {
+            let title = || {
+                JobListing::from_nostr_event(event)
+                    .map(|job| job.title)
+                    .unwrap_or_default()
+            };
+            match applied {
+                Applied::Listing(Ingested::Inserted) => println!("   + {} ({})", title(), relay),
+                Applied::Listing(Ingested::Replaced) => println!("   ~ {} ({})", title(), relay),
+                Applied::Deletion(removed) if removed > 0 => {
+                    println!("   - {} withdrawn listing(s) ({})", removed, relay)
+                }
+                _ => {}
+            }
+        })
+        .await?;
+
+    println!(
+        "\n💾 {} new, {} updated, {} withdrawn; {} listings in {}",
+        stats.inserted,
+        stats.replaced,
+        stats.deleted,
+        store.len()?,
+        store_path()
+    );
+    Ok(())
+}
+
+/// One-shot NIP-77 reconciliation of the local store against each read relay
+async fn reconcile_jobs(args: &[String]) -> Result<()> {
+    let config = load_relay_config(args)?;
+    let mut store = JobStore::open(store_path())?;
+
+    println!("🔄 Reconciling {} with the read relays (NIP-77)...", store_path());
+    let mut health = RelayHealth::load(relay_health_path())?;
+    let client = connect_relays(&config.read, &mut health).await?;
+    let reports = Reconciler::new().reconcile_all(&client, &mut store).await;
+
+    for report in &reports {
+        match &report.error {
+            None => println!(
+                "   ✓ {}: {} added, {} replaced, {} missing on relay, {} not received",
+                report.relay,
+                report.added,
+                report.replaced,
+                report.missing_on_relay.len(),
+                report.not_received.len()
+            ),
+            Some(e) => {
+                health.record_failure(&report.relay, e);
+                println!("   ✗ {}: {}", report.relay, e);
+            }
+        }
+    }
+    health.save(relay_health_path())?;
+    if let Some(path) = flag_value(args, "--report") {
+        fs::write(path, serde_json::to_string_pretty(&reports)?)
+            .with_context(|| format!("Failed to write reconciliation report: {}", path))?;
+        println!("   Report written to: {}", path);
+    }
+
+    println!("\n💾 {} listings in {}", store.len()?, store_path());
+    Ok(())
+}
+
+/// Show the relay sets and their health, or import a NIP-65 relay list
+async fn manage_relays(args: &[String]) -> Result<()> {
+    let path = relay_config_path();
+    if args.get(2).map(String::as_str) == Some("import") {
+        let pubkey = args
+            .get(3)
+            .map(|pk| PublicKey::parse(pk))
+            .transpose()
+            .context("import expects an npub or hex pubkey")?
+            .context("Usage: cargo run -- relays import <npub> [--replace]")?;
+
+        println!("📡 Fetching NIP-65 relay list for {}...", pubkey.to_bech32()?);
+        let mut health = RelayHealth::load(relay_health_path())?;
+        let client = connect_relays(&load_relay_config(args)?.read, &mut health).await?;
+        let imported = RelayConfig::fetch_nip65(&client, pubkey, CONNECT_TIMEOUT)
+            .await?
+            .context("No relay list (kind 10002) found for that pubkey")?;
+
+        let config = if args.iter().any(|arg| arg == "--replace") {
+            imported
+        } else {
+            let mut config = RelayConfig::load(&path)?;
+            config.merge(&imported);
+            config
+        };
+        config.save(&path)?;
+        println!("✅ Saved relay config to {}\n", path);
+    }
+
+    let config = load_relay_config(args)?;
+    let health = RelayHealth::load(relay_health_path())?;
+    for (name, relays) in [("Read", &config.read), ("Write", &config.write)] {
+        println!("📡 {} relays:", name);
+        for relay in relays {
+            match health.stats(relay) {
+                Some(stats) => println!(
+                    "   {} {} ({} ok, {} failed{})",
+                    if health.is_healthy(relay) { "•" } else { "✗" },
+                    relay,
+                    stats.successes,
+                    stats.failures,
+                    stats.last_error.as_ref().map(|e| format!(", last error: {}", e)).unwrap_or_default()
+                ),
+                None => println!("   • {} (no history)", relay),
+            }
+        }
+    }
+    println!("\n   Config: {}", path);
+    println!("   Health: {}", relay_health_path());
+    Ok(())
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
+    load_env();
     let args: Vec<String> = std::env::args().collect();
     
     // Check if first arg is a command
@@ -212,7 +808,38 @@ async fn main() -> Result<()> {
     
     match command {
         "view_jobs" => {
-            view_jobs().await?;
+            let paid_only = args.iter().any(|arg| arg == "--paid-only");
+            let min_sats = args
+                .iter()
+                .position(|arg| arg == "--min-sats")
+                .and_then(|i| args.get(i + 1))
+                .map(|sats| sats.parse::<u64>())
+                .transpose()
+                .context("--min-sats expects a number of sats")?;
+            view_jobs(paid_only, min_sats, &payment_policy(&args)?, &load_relay_config(&args)?).await?;
+            return Ok(());
+        }
+        "export" | "sign" | "publish" => {
+            let file = args
+                .get(2)
+                .with_context(|| format!("Usage: cargo run -- {} <file.json>", command))?;
+            match command {
+                "export" => export_template(file, &args)?,
+                "sign" => sign_template(file, &args).await?,
+                _ => publish_presigned(file, &args).await?,
+            }
+            return Ok(());
+        }
+        "sync" => {
+            sync_jobs(&args).await?;
+            return Ok(());
+        }
+        "search" => {
+            search_jobs(&args)?;
+            return Ok(());
+        }
+        "relays" => {
+            manage_relays(&args).await?;
             return Ok(());
         }
         "help" | "--help" | "-h" => {
@@ -226,6 +853,15 @@ async fn main() -> Result<()> {
     
     let job_file = command;
     let publish = args.iter().any(|arg| arg == "--publish" || arg == "-p");
+    let pow = args
+        .iter()
+        .position(|arg| arg == "--pow")
+        .and_then(|i| args.get(i + 1))
+        .map(|bits| bits.parse::<u8>())
+        .transpose()
+        .context("--pow expects a difficulty in bits")?;
+    let bunker = flag_value(&args, "--bunker");
+    let relays = load_relay_config(&args)?;
 
     println!("🚀 Nostr Job Poster (Kind 39993 with UUID)\n");
 
@@ -234,33 +870,24 @@ async fn main() -> Result<()> {
     let simple_job = load_job_from_file(job_file)?;
     println!("✅ Job loaded: {}\n", simple_job.title);
 
-    // 2. Load or generate keys
-    let private_key = ensure_private_key();
-    let keys = Keys::parse(&private_key)?;
-    println!("📝 Using pubkey: {}\n", keys.public_key().to_bech32()?);
+    // 2. Unlock the keystore or connect to the bunker
+    let signer = load_signer(bunker).await?;
+    let pubkey = signer.get_public_key().await?;
+    println!("📝 Using pubkey: {}\n", pubkey.to_bech32()?);
 
     // 3. Convert to full JobListing with UUID
-    let job = convert_to_job_listing(&simple_job, &keys.public_key().to_hex())?;
+    let job = convert_to_job_listing(&simple_job, Some(&pubkey.to_hex()))?;
 
-    println!("📋 Job Details:");
-    println!("   ID: {}", job.identifier);
-    println!("   Title: {}", job.title);
-    println!("   Company: {}", job.hiring_organization.name);
-    println!("   Type: {:?}", job.employment_type);
-    println!("   Location: {:?}", job.job_location_type);
-    println!("   Skills: {}", job.skills.as_ref().map(|s| s.join(", ")).unwrap_or_default());
-    
-    if let Some(salary) = &job.base_salary {
-        if let nosjob::types::MonetaryValue::Range(range) = &salary.value {
-            if let (Some(min), Some(max)) = (range.min_value, range.max_value) {
-                println!("   Salary: ${} - ${} {} per {}", 
-                    min as u64, max as u64, salary.currency, range.unit_text);
-            }
-        }
-    }
+    print_job_details(&job);
 
     // 4. Convert to Nostr event
-    let event = job.to_nostr_event(&keys)?;
+    let event = match pow {
+        Some(difficulty) => {
+            println!("⛏️  Mining proof-of-work (difficulty {})...", difficulty);
+            job.mined_sign_with(&signer, &PowOptions::new(difficulty)).await?
+        }
+        None => job.sign_with(&signer).await?,
+    };
     println!("\n📦 Event Created:");
     println!("   ID: {}", event.id);
     println!("   Kind: {} (39993 job listing)", event.kind);
@@ -275,46 +902,12 @@ async fn main() -> Result<()> {
         println!("   ... and {} more", event.tags.len() - 10);
     }
 
-    // 6. Relay list
-    let relays = vec![
-        "wss://relay.damus.io",
-        "wss://relay.nostr.band",
-        "wss://nostr.wine",
-    ];
-
     if publish {
-        println!("\n📡 Publishing to relays...");
-        let client = Client::new(keys);
-        
-        for relay in &relays {
-            println!("   Connecting to: {}", relay);
-            client.add_relay(*relay).await?;
-        }
-        
-        client.connect().await;
-        let output = client.send_event(&event.clone()).await?;
-        
-        println!("\n✅ Published successfully!");
-        println!("   Success: {} relays", output.success.len());
-        println!("   Failed: {} relays", output.failed.len());
-        
-        for relay in output.success {
-            println!("   ✓ {}", relay);
-        }
-        
-        if !output.failed.is_empty() {
-            for (relay, err) in output.failed {
-                println!("   ✗ {}: {}", relay, err);
-            }
-        }
-
-        println!("\n🔗 Event Identifiers:");
-        println!("   Event ID: {}", event.id.to_bech32()?);
-        println!("   Pubkey: {}", event.pubkey.to_bech32()?);
+        publish_event(&event, &job, &relays, &args).await?;
     } else {
         println!("\n🔍 DRY RUN MODE (use --publish or -p to actually publish)");
         println!("\n📡 Would publish to:");
-        for relay in &relays {
+        for relay in &relays.write {
             println!("   • {}", relay);
         }
 
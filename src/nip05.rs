@@ -0,0 +1,530 @@
+// src/nip05.rs
+// NIP-05 employer verification
+// Confirms a listing's `nip05` identifier maps to the key that signed it,
+// and that the hiring organization's website is on the same domain
+
+use crate::types::JobListing;
+use anyhow::{anyhow, Context, Result};
+use nostr_sdk::nips::nip05::Nip05Address;
+use nostr_sdk::prelude::*;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::future::Future;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::{lookup_host, TcpStream};
+use tokio_rustls::rustls::{self, pki_types::ServerName};
+use tokio_rustls::TlsConnector;
+
+/// Largest response accepted, headers included
+const MAX_RESPONSE: u64 = 64 * 1024;
+
+/// Fetches the `/.well-known/nostr.json` document for an address
+pub trait Nip05Resolver {
+    fn fetch(&self, address: &Nip05Address) -> impl Future<Output = Result<Value>> + Send;
+}
+
+/// HTTPS resolver. Redirects are not followed, as NIP-05 requires.
+/// The domain comes from whoever wrote the listing, so by default only
+/// port 443 on public addresses is contacted; see `allow_local_hosts`.
+#[derive(Debug, Clone)]
+pub struct HttpResolver {
+    timeout: Duration,
+    plain_http: bool,
+    local_hosts: bool,
+}
+
+impl Default for HttpResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HttpResolver {
+    pub fn new() -> Self {
+        Self {
+            timeout: Duration::from_secs(10),
+            plain_http: false,
+            local_hosts: false,
+        }
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Use `http://` on port 80 instead of `https://` (local stand-ins only)
+    pub fn plain_http(mut self) -> Self {
+        self.plain_http = true;
+        self
+    }
+
+    /// Also contact loopback, private and link-local addresses, on any port
+    /// (local stand-ins and tests only)
+    pub fn allow_local_hosts(mut self) -> Self {
+        self.local_hosts = true;
+        self
+    }
+
+    /// Addresses to connect to, after refusing non-default ports and
+    /// non-public addresses. Connecting to exactly these avoids a second
+    /// lookup that could answer differently.
+    async fn resolve(&self, url: &Url) -> Result<Vec<SocketAddr>> {
+        let host = url.host_str().ok_or_else(|| anyhow!("No host in {}", url))?;
+        let default_port = if url.scheme() == "https" { 443 } else { 80 };
+        let port = url.port().unwrap_or(default_port);
+        if !self.local_hosts && port != default_port {
+            return Err(anyhow!("Refusing port {} for {}", port, url));
+        }
+        // Bracketed IPv6 literals resolve without them
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        let addrs: Vec<SocketAddr> = lookup_host((host, port)).await?.collect();
+        if addrs.is_empty() {
+            return Err(anyhow!("{} has no addresses", host));
+        }
+        if !self.local_hosts
+            && let Some(addr) = addrs.iter().find(|addr| !is_public(addr.ip()))
+        {
+            return Err(anyhow!("Refusing {}: {} is not a public address", host, addr.ip()));
+        }
+        Ok(addrs)
+    }
+
+    async fn get(&self, url: &Url) -> Result<Value> {
+        let host = url.host_str().ok_or_else(|| anyhow!("No host in {}", url))?.to_string();
+        let mut target = url.path().to_string();
+        if let Some(query) = url.query() {
+            target.push('?');
+            target.push_str(query);
+        }
+        // HTTP/1.0: the body is never chunked and ends when the server closes
+        let request = format!(
+            "GET {} HTTP/1.0\r\nHost: {}\r\nAccept: application/json\r\nUser-Agent: nosjob\r\n\r\n",
+            target, host
+        );
+
+        let stream = TcpStream::connect(&self.resolve(url).await?[..]).await?;
+        let response = if url.scheme() == "https" {
+            let server_name = ServerName::try_from(host.clone())?;
+            let tls = TlsConnector::from(tls_config()).connect(server_name, stream).await?;
+            exchange(tls, &request).await?
+        } else {
+            exchange(stream, &request).await?
+        };
+
+        let text = String::from_utf8(response).context("Response is not UTF-8")?;
+        let (head, body) = text
+            .split_once("\r\n\r\n")
+            .ok_or_else(|| anyhow!("Malformed HTTP response"))?;
+        let status = head.split_whitespace().nth(1).unwrap_or_default();
+        if status != "200" {
+            return Err(anyhow!("{} returned HTTP {}", url, status));
+        }
+        let content_type = head
+            .lines()
+            .filter_map(|line| line.split_once(':'))
+            .find(|(name, _)| name.trim().eq_ignore_ascii_case("content-type"))
+            .map(|(_, value)| value.split(';').next().unwrap_or_default().trim())
+            .unwrap_or_default();
+        if !content_type.eq_ignore_ascii_case("application/json") {
+            return Err(anyhow!("{} returned {:?}, not application/json", url, content_type));
+        }
+        Ok(serde_json::from_str(body)?)
+    }
+}
+
+/// TLS settings shared by every request: webpki roots, ring, safe defaults
+fn tls_config() -> Arc<rustls::ClientConfig> {
+    static CONFIG: OnceLock<Arc<rustls::ClientConfig>> = OnceLock::new();
+    CONFIG
+        .get_or_init(|| {
+            let mut roots = rustls::RootCertStore::empty();
+            roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+            let config = rustls::ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+                .with_safe_default_protocol_versions()
+                .expect("ring supports the default protocol versions")
+                .with_root_certificates(roots)
+                .with_no_client_auth();
+            Arc::new(config)
+        })
+        .clone()
+}
+
+/// Not loopback, private, link-local, unspecified, multicast or broadcast
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_multicast()
+                || ip.is_broadcast()
+                // 100.64.0.0/10, carrier-grade NAT
+                || (ip.octets()[0] == 100 && ip.octets()[1] & 0xc0 == 64))
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(v4) => is_public(IpAddr::V4(v4)),
+            None => {
+                !(ip.is_loopback()
+                    || ip.is_unspecified()
+                    || ip.is_multicast()
+                    || ip.is_unique_local()
+                    || ip.is_unicast_link_local())
+            }
+        },
+    }
+}
+
+async fn exchange<S: AsyncRead + AsyncWrite + Unpin>(mut stream: S, request: &str) -> Result<Vec<u8>> {
+    stream.write_all(request.as_bytes()).await?;
+    stream.flush().await?;
+    // The host is chosen by whoever wrote the listing: bound what we buffer
+    let mut response = Vec::new();
+    (&mut stream).take(MAX_RESPONSE + 1).read_to_end(&mut response).await?;
+    if response.len() as u64 > MAX_RESPONSE {
+        return Err(anyhow!("Response is larger than {} bytes", MAX_RESPONSE));
+    }
+    Ok(response)
+}
+
+impl Nip05Resolver for HttpResolver {
+    async fn fetch(&self, address: &Nip05Address) -> Result<Value> {
+        let mut url = address.url().clone();
+        if self.plain_http {
+            url.set_scheme("http").map_err(|_| anyhow!("Cannot use http for {}", url))?;
+        }
+        tokio::time::timeout(self.timeout, self.get(&url))
+            .await
+            .map_err(|_| anyhow!("Timed out fetching {}", url))?
+    }
+}
+
+/// Fixed `nostr.json` documents keyed by domain (offline use and tests)
+#[derive(Debug, Clone, Default)]
+pub struct MemoryResolver {
+    documents: HashMap<String, Value>,
+}
+
+impl MemoryResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, domain: &str, document: Value) {
+        self.documents.insert(domain.to_lowercase(), document);
+    }
+}
+
+impl Nip05Resolver for MemoryResolver {
+    async fn fetch(&self, address: &Nip05Address) -> Result<Value> {
+        self.documents
+            .get(&address.domain().to_lowercase())
+            .cloned()
+            .ok_or_else(|| anyhow!("No nostr.json for {}", address.domain()))
+    }
+}
+
+/// Outcome of checking one identifier against a pubkey
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Nip05Status {
+    Verified,
+    Mismatch { registered: PublicKey }, // Name points at another key
+    NotRegistered,                      // Name missing from nostr.json
+    Unavailable(String),                // Fetch failed; try again later
+    InvalidIdentifier,
+    NoIdentifier,
+}
+
+/// Verification of a job listing's employer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Nip05Verification {
+    pub identifier: Option<String>,
+    pub status: Nip05Status,
+    /// Whether `hiring_organization.url` is on the NIP-05 domain (`None`: no URL)
+    pub org_url_matches: Option<bool>,
+}
+
+impl Nip05Verification {
+    pub fn is_verified(&self) -> bool {
+        self.status == Nip05Status::Verified && self.org_url_matches != Some(false)
+    }
+}
+
+/// Verifies identifiers through a resolver, caching lookups for `ttl`.
+/// Failed fetches aren't cached.
+pub struct Nip05Verifier<R> {
+    resolver: R,
+    ttl: Duration,
+    cache: Mutex<HashMap<String, (Instant, Option<PublicKey>)>>,
+}
+
+impl<R: Nip05Resolver> Nip05Verifier<R> {
+    pub fn new(resolver: R, ttl: Duration) -> Self {
+        Self {
+            resolver,
+            ttl,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Check that `identifier` (`name@domain`, or `domain` for `_@domain`) maps to `pubkey`
+    pub async fn verify(&self, identifier: &str, pubkey: &PublicKey) -> Nip05Status {
+        let Ok(address) = Nip05Address::parse(&identifier.trim().to_lowercase()) else {
+            return Nip05Status::InvalidIdentifier;
+        };
+        let key = address.to_string();
+
+        let cached = self.cache.lock().unwrap().get(&key).copied();
+        let registered = match cached {
+            Some((fetched_at, registered)) if fetched_at.elapsed() < self.ttl => registered,
+            _ => match self.resolver.fetch(&address).await {
+                Ok(document) => {
+                    let registered = document
+                        .get("names")
+                        .and_then(|names| names.get(address.name()))
+                        .and_then(Value::as_str)
+                        .and_then(|hex| PublicKey::from_hex(hex).ok());
+                    self.cache.lock().unwrap().insert(key, (Instant::now(), registered));
+                    registered
+                }
+                Err(e) => return Nip05Status::Unavailable(e.to_string()),
+            },
+        };
+
+        match registered {
+            Some(registered) if registered == *pubkey => Nip05Status::Verified,
+            Some(registered) => Nip05Status::Mismatch { registered },
+            None => Nip05Status::NotRegistered,
+        }
+    }
+
+    /// Verify a listing event: its `nip05` identifier (content field or tag)
+    /// must map to the event's signing key, and the organization's website
+    /// must be on the same domain
+    pub async fn verify_event(&self, event: &Event) -> Nip05Verification {
+        let job = JobListing::from_nostr_event(event).ok();
+        let identifier = job
+            .as_ref()
+            .and_then(|job| job.nip05_verified.clone())
+            .or_else(|| {
+                event.tags.iter().find_map(|t| match t.as_slice() {
+                    [name, value, ..] if name == "nip05" => Some(value.clone()),
+                    _ => None,
+                })
+            });
+        let Some(identifier) = identifier else {
+            return Nip05Verification {
+                identifier: None,
+                status: Nip05Status::NoIdentifier,
+                org_url_matches: None,
+            };
+        };
+
+        let status = self.verify(&identifier, &event.pubkey).await;
+        let org_url_matches = job
+            .as_ref()
+            .and_then(|job| job.hiring_organization.url.as_deref())
+            .map(|url| org_url_matches(url, &identifier));
+        Nip05Verification {
+            identifier: Some(identifier),
+            status,
+            org_url_matches,
+        }
+    }
+}
+
+/// The organization's website is the NIP-05 domain or a subdomain of it.
+/// Not the other way round: anyone can get a subdomain of a shared host
+/// (`*.github.io`), so a NIP-05 subdomain says nothing about the parent.
+pub fn org_url_matches(org_url: &str, identifier: &str) -> bool {
+    let Some(host) = Url::parse(org_url).ok().and_then(|u| u.host_str().map(str::to_lowercase)) else {
+        return false;
+    };
+    let Ok(address) = Nip05Address::parse(&identifier.trim().to_lowercase()) else {
+        return false;
+    };
+    let domain = address.domain().split(':').next().unwrap_or_default();
+    let host = host.strip_prefix("www.").unwrap_or(&host);
+    host == domain || host.ends_with(&format!(".{}", domain))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::sample_job;
+    use serde_json::json;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::net::TcpListener;
+
+    struct Counting {
+        inner: MemoryResolver,
+        fetches: AtomicUsize,
+    }
+
+    impl Nip05Resolver for Counting {
+        async fn fetch(&self, address: &Nip05Address) -> Result<Value> {
+            self.fetches.fetch_add(1, Ordering::SeqCst);
+            self.inner.fetch(address).await
+        }
+    }
+
+    fn job_event(keys: &Keys, nip05: &str, org_url: Option<&str>) -> Event {
+        let mut job = sample_job("job-001");
+        job.nip05_verified = Some(nip05.to_string());
+        job.hiring_organization.url = org_url.map(str::to_string);
+        job.to_nostr_event(keys).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_verify_event_and_cache() {
+        let employer = Keys::generate();
+        let impostor = Keys::generate();
+        let mut documents = MemoryResolver::new();
+        documents.insert("acme.com", json!({ "names": { "jobs": employer.public_key().to_hex() } }));
+        let verifier = Nip05Verifier::new(
+            Counting { inner: documents, fetches: AtomicUsize::new(0) },
+            Duration::from_secs(300),
+        );
+
+        let genuine = verifier
+            .verify_event(&job_event(&employer, "Jobs@acme.com", Some("https://www.acme.com/careers")))
+            .await;
+        assert_eq!(genuine.status, Nip05Status::Verified);
+        assert_eq!(genuine.org_url_matches, Some(true));
+        assert!(genuine.is_verified());
+
+        let forged = verifier.verify_event(&job_event(&impostor, "jobs@acme.com", None)).await;
+        assert_eq!(forged.status, Nip05Status::Mismatch { registered: employer.public_key() });
+        assert_eq!(verifier.resolver.fetches.load(Ordering::SeqCst), 1);
+
+        // Right key, but the organization's website is elsewhere
+        let elsewhere = verifier
+            .verify_event(&job_event(&employer, "jobs@acme.com", Some("https://evil.example")))
+            .await;
+        assert_eq!(elsewhere.status, Nip05Status::Verified);
+        assert!(!elsewhere.is_verified());
+
+        assert_eq!(verifier.verify("hr@acme.com", &employer.public_key()).await, Nip05Status::NotRegistered);
+        assert!(matches!(
+            verifier.verify("jobs@unknown.org", &employer.public_key()).await,
+            Nip05Status::Unavailable(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_cache_expires() {
+        let keys = Keys::generate();
+        let mut documents = MemoryResolver::new();
+        documents.insert("acme.com", json!({ "names": { "_": keys.public_key().to_hex() } }));
+        let verifier = Nip05Verifier::new(
+            Counting { inner: documents, fetches: AtomicUsize::new(0) },
+            Duration::from_millis(20),
+        );
+
+        assert_eq!(verifier.verify("acme.com", &keys.public_key()).await, Nip05Status::Verified);
+        tokio::time::sleep(Duration::from_millis(40)).await;
+        assert_eq!(verifier.verify("_@acme.com", &keys.public_key()).await, Nip05Status::Verified);
+        assert_eq!(verifier.resolver.fetches.load(Ordering::SeqCst), 2);
+    }
+
+    /// Answer one HTTP request with `content_type` and `body`; yields the request
+    async fn serve_once(content_type: &str, body: String) -> (u16, tokio::task::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let head = format!("HTTP/1.0 200 OK\r\nContent-Type: {}\r\n\r\n", content_type);
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = vec![0u8; 1024];
+            let n = socket.read(&mut request).await.unwrap();
+            let request = String::from_utf8_lossy(&request[..n]).to_string();
+            // The client may hang up once it has read enough
+            let _ = socket.write_all(format!("{}{}", head, body).as_bytes()).await;
+            request
+        });
+        (port, server)
+    }
+
+    #[tokio::test]
+    async fn test_http_resolver_against_local_server() {
+        let keys = Keys::generate();
+        let body = json!({ "names": { "jobs": keys.public_key().to_hex() } }).to_string();
+        let (port, server) = serve_once("application/json; charset=utf-8", body).await;
+
+        let verifier = Nip05Verifier::new(HttpResolver::new().plain_http().allow_local_hosts(), Duration::from_secs(60));
+        let identifier = format!("jobs@127.0.0.1:{}", port);
+        assert_eq!(verifier.verify(&identifier, &keys.public_key()).await, Nip05Status::Verified);
+
+        let request = server.await.unwrap();
+        assert!(request.starts_with("GET /.well-known/nostr.json?name=jobs HTTP/1.0\r\n"));
+    }
+
+    #[tokio::test]
+    async fn test_http_resolver_rejects_oversized_and_non_json() {
+        let resolver = HttpResolver::new().plain_http().allow_local_hosts();
+        let fetch = |port: u16| {
+            let resolver = resolver.clone();
+            async move {
+                let address = Nip05Address::parse(&format!("jobs@127.0.0.1:{}", port)).unwrap();
+                resolver.fetch(&address).await
+            }
+        };
+
+        let padding = " ".repeat(MAX_RESPONSE as usize);
+        let (port, _) = serve_once("application/json", format!("{{\"names\":{{}}}}{}", padding)).await;
+        let error = fetch(port).await.unwrap_err().to_string();
+        assert!(error.contains("larger than"), "{}", error);
+
+        let (port, _) = serve_once("text/html", "{\"names\":{}}".to_string()).await;
+        let error = fetch(port).await.unwrap_err().to_string();
+        assert!(error.contains("not application/json"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn test_http_resolver_refuses_local_hosts_and_other_ports() {
+        let resolver = HttpResolver::new();
+        let fetch = |identifier: &str| {
+            let address = Nip05Address::parse(identifier).unwrap();
+            let resolver = resolver.clone();
+            async move { resolver.fetch(&address).await.unwrap_err().to_string() }
+        };
+
+        for identifier in ["jobs@127.0.0.1", "jobs@10.0.0.7", "jobs@169.254.169.254", "jobs@[::1]", "jobs@localhost"] {
+            let error = fetch(identifier).await;
+            assert!(error.contains("not a public address"), "{}: {}", identifier, error);
+        }
+        let error = fetch("jobs@acme.com:8443").await;
+        assert!(error.contains("Refusing port 8443"), "{}", error);
+
+        // Plain http is an opt-in, and then only on port 80
+        let error = HttpResolver::new()
+            .plain_http()
+            .fetch(&Nip05Address::parse("jobs@acme.com:8080").unwrap())
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("Refusing port 8080"), "{}", error);
+    }
+
+    #[test]
+    fn test_is_public() {
+        assert!(is_public("93.184.216.34".parse().unwrap()));
+        assert!(is_public("2606:2800:220:1::".parse().unwrap()));
+        for ip in ["127.0.0.1", "192.168.1.1", "172.16.0.1", "100.64.0.1", "0.0.0.0", "fe80::1", "fd00::1", "::", "::ffff:10.0.0.1"] {
+            assert!(!is_public(ip.parse().unwrap()), "{}", ip);
+        }
+    }
+
+    #[test]
+    fn test_org_url_matches() {
+        assert!(org_url_matches("https://acme.com", "jobs@acme.com"));
+        assert!(org_url_matches("https://careers.acme.com/x", "acme.com"));
+        assert!(!org_url_matches("https://acme.com", "jobs@hr.acme.com"));
+        assert!(!org_url_matches("https://github.io", "jobs@attacker.github.io"));
+        assert!(!org_url_matches("https://notacme.com", "jobs@acme.com"));
+        assert!(!org_url_matches("not a url", "jobs@acme.com"));
+    }
+}
@@ -0,0 +1,206 @@
+// src/offline.rs
+// Offline signing: export an unsigned event template, sign it on another
+// machine, then publish the pre-signed event once it checks out against
+// the approved template
+
+use crate::types::JobListing;
+use anyhow::Result;
+use nostr_sdk::hashes::{sha256, Hash};
+use nostr_sdk::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Unsigned event awaiting approval (NIP-01 fields, no id or sig).
+/// `pubkey` pins the approver's key when it is known at export time.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventTemplate {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pubkey: Option<PublicKey>,
+    pub created_at: Timestamp,
+    pub kind: Kind,
+    pub tags: Vec<Tag>,
+    pub content: String,
+}
+
+/// Signed event does not match its approved template
+#[derive(Debug, Clone, PartialEq)]
+pub enum IntegrityError {
+    InvalidSignature(String),
+    WrongSigner { expected: PublicKey, actual: PublicKey },
+    FieldMismatch(&'static str),
+    DigestMismatch { expected: String, actual: String },
+}
+
+impl fmt::Display for IntegrityError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IntegrityError::InvalidSignature(e) => write!(f, "Invalid event id or signature: {}", e),
+            IntegrityError::WrongSigner { expected, actual } => {
+                write!(f, "Signed by {} but the template requires {}", actual, expected)
+            }
+            IntegrityError::FieldMismatch(field) => write!(f, "Signed event {} does not match the template", field),
+            IntegrityError::DigestMismatch { expected, actual } => {
+                write!(f, "Template digest {} does not match the approved {}", actual, expected)
+            }
+        }
+    }
+}
+
+impl std::error::Error for IntegrityError {}
+
+impl EventTemplate {
+    /// Template from an unsigned event; `pin_author` keeps its pubkey
+    pub fn from_unsigned(unsigned: UnsignedEvent, pin_author: bool) -> Self {
+        Self {
+            pubkey: pin_author.then_some(unsigned.pubkey),
+            created_at: unsigned.created_at,
+            kind: unsigned.kind,
+            tags: unsigned.tags.to_vec(),
+            content: unsigned.content,
+        }
+    }
+
+    /// Kind 39993 template for a listing, optionally pinned to the approver
+    pub fn from_job(job: &JobListing, approver: Option<PublicKey>) -> Result<Self> {
+        job.validate()?;
+        // Any key will do when unpinned: only the unsigned fields are kept
+        let author = approver.unwrap_or_else(|| Keys::generate().public_key());
+        let unsigned = job.event_builder()?.build(author);
+        Ok(Self::from_unsigned(unsigned, approver.is_some()))
+    }
+
+    /// SHA-256 over the template's fields, for approvers to record and
+    /// compare across machines
+    pub fn digest(&self) -> String {
+        let json = serde_json::json!([self.pubkey, self.created_at, self.kind, self.tags, self.content]);
+        sha256::Hash::hash(json.to_string().as_bytes()).to_string()
+    }
+
+    /// Fail unless the template still has the approved digest
+    pub fn check_digest(&self, approved: &str) -> Result<(), IntegrityError> {
+        let actual = self.digest();
+        if !actual.eq_ignore_ascii_case(approved.trim()) {
+            return Err(IntegrityError::DigestMismatch {
+                expected: approved.trim().to_string(),
+                actual,
+            });
+        }
+        Ok(())
+    }
+
+    /// The unsigned event for `author` (must be the pinned key, if any)
+    pub fn to_unsigned(&self, author: PublicKey) -> Result<UnsignedEvent, IntegrityError> {
+        if let Some(expected) = self.pubkey
+            && expected != author
+        {
+            return Err(IntegrityError::WrongSigner { expected, actual: author });
+        }
+        Ok(UnsignedEvent::new(author, self.created_at, self.kind, self.tags.clone(), self.content.clone()))
+    }
+
+    /// Sign with any `NostrSigner`, then check the result against the template
+    pub async fn sign_with<S>(&self, signer: &S) -> Result<Event>
+    where
+        S: NostrSigner + ?Sized,
+    {
+        let unsigned = self.to_unsigned(signer.get_public_key().await?)?;
+        let event = signer.sign_event(unsigned).await?;
+        self.verify_signed(&event)?;
+        Ok(event)
+    }
+
+    /// Event id and signature are valid and every signed field matches
+    pub fn verify_signed(&self, event: &Event) -> Result<(), IntegrityError> {
+        event
+            .verify()
+            .map_err(|e| IntegrityError::InvalidSignature(e.to_string()))?;
+        if let Some(expected) = self.pubkey
+            && expected != event.pubkey
+        {
+            return Err(IntegrityError::WrongSigner { expected, actual: event.pubkey });
+        }
+        if event.kind != self.kind {
+            return Err(IntegrityError::FieldMismatch("kind"));
+        }
+        if event.created_at != self.created_at {
+            return Err(IntegrityError::FieldMismatch("created_at"));
+        }
+        if event.tags.as_slice() != self.tags.as_slice() {
+            return Err(IntegrityError::FieldMismatch("tags"));
+        }
+        if event.content != self.content {
+            return Err(IntegrityError::FieldMismatch("content"));
+        }
+        Ok(())
+    }
+}
+
+/// Checks before publishing a pre-signed listing: valid id and signature,
+/// a listing that parses and validates, and (if given) the approved template
+pub fn check_presigned(event: &Event, template: Option<&EventTemplate>) -> Result<JobListing> {
+    match template {
+        Some(template) => template.verify_signed(event)?,
+        None => event
+            .verify()
+            .map_err(|e| IntegrityError::InvalidSignature(e.to_string()))?,
+    }
+    let job = JobListing::from_nostr_event(event)?;
+    job.validate()?;
+    Ok(job)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::sample_job;
+
+    #[tokio::test]
+    async fn test_export_sign_publish() {
+        let approver = Keys::generate();
+        let template = EventTemplate::from_job(&sample_job("job-001"), None).unwrap();
+        assert!(template.pubkey.is_none());
+
+        // Template survives the trip to the air-gapped machine
+        let json = serde_json::to_string_pretty(&template).unwrap();
+        let copied: EventTemplate = serde_json::from_str(&json).unwrap();
+        assert_eq!(copied, template);
+        copied.check_digest(&template.digest().to_uppercase()).unwrap();
+
+        let event = copied.sign_with(&approver).await.unwrap();
+        assert_eq!(event.pubkey, approver.public_key());
+        let signed_json = event.as_json();
+        let event = Event::from_json(signed_json).unwrap();
+        assert_eq!(check_presigned(&event, Some(&template)).unwrap(), sample_job("job-001"));
+    }
+
+    #[tokio::test]
+    async fn test_integrity_failures() {
+        let approver = Keys::generate();
+        let template = EventTemplate::from_job(&sample_job("job-001"), Some(approver.public_key())).unwrap();
+
+        // Edited after approval
+        let mut edited = template.clone();
+        edited.content = edited.content.replace("Acme Corp", "Evil Corp");
+        assert!(matches!(
+            edited.check_digest(&template.digest()),
+            Err(IntegrityError::DigestMismatch { .. })
+        ));
+        let signed_edit = edited.sign_with(&approver).await.unwrap();
+        assert_eq!(
+            template.verify_signed(&signed_edit),
+            Err(IntegrityError::FieldMismatch("content"))
+        );
+
+        // Pinned to the approver's key
+        let intruder = Keys::generate();
+        assert!(template.sign_with(&intruder).await.is_err());
+        let other = EventTemplate { pubkey: None, ..template.clone() }.sign_with(&intruder).await.unwrap();
+        assert!(matches!(template.verify_signed(&other), Err(IntegrityError::WrongSigner { .. })));
+
+        // Tampered after signing
+        let mut tampered = template.sign_with(&approver).await.unwrap();
+        tampered.created_at = tampered.created_at + 60;
+        assert!(matches!(template.verify_signed(&tampered), Err(IntegrityError::InvalidSignature(_))));
+        assert!(check_presigned(&tampered, None).is_err());
+    }
+}
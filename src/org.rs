@@ -0,0 +1,223 @@
+// src/org.rs
+// Organisation recruiter lists: a NIP-51 follow set (kind 30000) published
+// by an org key, with one `p` tag per recruiter allowed to post for it
+
+use crate::signer::SignEvent;
+use crate::trust::Delegations;
+use anyhow::{anyhow, Result};
+use nostr_sdk::prelude::*;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+/// `d` tag prefix that marks a follow set as a recruiter list
+pub const RECRUITER_LIST_PREFIX: &str = "recruiters:";
+
+/// Recruiter keys an organisation has authorised
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrgMembership {
+    pub org: PublicKey,
+    pub name: String,
+    pub recruiters: Vec<PublicKey>,
+}
+
+impl OrgMembership {
+    pub fn new(org: PublicKey, name: String) -> Self {
+        Self {
+            org,
+            name,
+            recruiters: Vec::new(),
+        }
+    }
+
+    pub fn recruiter(mut self, pubkey: PublicKey) -> Self {
+        if !self.recruiters.contains(&pubkey) {
+            self.recruiters.push(pubkey);
+        }
+        self
+    }
+
+    /// `d` tag: `recruiters:<org-name>`, one list per organisation
+    pub fn d_tag(&self) -> String {
+        format!("{}{}", RECRUITER_LIST_PREFIX, self.name.trim().to_lowercase().replace(' ', "-"))
+    }
+
+    /// Convert to a kind 30000 event; the signer must be the org key
+    pub fn to_nostr_event(&self, keys: &Keys) -> Result<Event> {
+        Ok(self.unsigned_event(keys.public_key())?.sign_with_keys(keys)?)
+    }
+
+    /// Parse a kind 30000 recruiter list; the event signer is the org
+    pub fn from_nostr_event(event: &Event) -> Result<Self> {
+        if event.kind != Kind::FollowSet {
+            return Err(anyhow!("Expected kind 30000, got {}", event.kind));
+        }
+        let slug = event
+            .tags
+            .identifier()
+            .and_then(|d| d.strip_prefix(RECRUITER_LIST_PREFIX))
+            .ok_or_else(|| anyhow!("Not a recruiter list"))?;
+        event.verify().map_err(|e| anyhow!("Invalid event: {}", e))?;
+
+        let name = event
+            .tags
+            .find(TagKind::Title)
+            .and_then(|t| t.content())
+            .unwrap_or(slug)
+            .to_string();
+        Ok(Self {
+            org: event.pubkey,
+            name,
+            recruiters: event.tags.public_keys().copied().collect(),
+        })
+    }
+
+    /// Follow sets published by these org keys (relays can't match a `d`
+    /// prefix, so non-recruiter sets are skipped when parsing)
+    pub fn filter<I>(orgs: I) -> Filter
+    where
+        I: IntoIterator<Item = PublicKey>,
+    {
+        Filter::new().kind(Kind::FollowSet).authors(orgs)
+    }
+}
+
+impl SignEvent for OrgMembership {
+    fn unsigned_event(&self, author: PublicKey) -> Result<UnsignedEvent> {
+        if self.name.trim().is_empty() {
+            return Err(anyhow!("Organisation name is required"));
+        }
+        if author != self.org {
+            return Err(anyhow!("Recruiter list must be signed by the organisation key"));
+        }
+
+        let mut tags = vec![
+            Tag::identifier(self.d_tag()),
+            Tag::custom(TagKind::Title, vec![&self.name]),
+        ];
+        tags.extend(self.recruiters.iter().map(|pk| Tag::public_key(*pk)));
+
+        Ok(EventBuilder::new(Kind::FollowSet, "")
+            .tags(tags)
+            .build(author))
+    }
+}
+
+impl Delegations {
+    /// Authorise every recruiter on the list to post for its org
+    pub fn add_membership(&mut self, list: &OrgMembership) -> &mut Self {
+        for recruiter in &list.recruiters {
+            self.allow(list.org, *recruiter);
+        }
+        self
+    }
+
+    /// Build from fetched kind 30000 events, keeping the newest version of
+    /// each list and skipping anything that isn't a valid recruiter list
+    pub fn from_membership_events<'a, I>(events: I) -> Self
+    where
+        I: IntoIterator<Item = &'a Event>,
+    {
+        // Versions share the event's own address; the title may change between them
+        let mut latest: HashMap<(PublicKey, String), &Event> = HashMap::new();
+        for event in events {
+            if OrgMembership::from_nostr_event(event).is_err() {
+                continue;
+            }
+            let address = (event.pubkey, event.tags.identifier().unwrap_or_default().to_string());
+            latest
+                .entry(address)
+                .and_modify(|e| {
+                    // NIP-01: newest created_at wins, ties go to the lowest id
+                    if (event.created_at, Reverse(event.id)) > (e.created_at, Reverse(e.id)) {
+                        *e = event;
+                    }
+                })
+                .or_insert(event);
+        }
+
+        let mut delegations = Self::new();
+        for event in latest.into_values() {
+            if let Ok(list) = OrgMembership::from_nostr_event(event) {
+                delegations.add_membership(&list);
+            }
+        }
+        delegations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let org = Keys::generate();
+        let recruiter = Keys::generate().public_key();
+        let list = OrgMembership::new(org.public_key(), "Acme Corp".to_string())
+            .recruiter(recruiter)
+            .recruiter(recruiter);
+        assert_eq!(list.d_tag(), "recruiters:acme-corp");
+        assert_eq!(list.recruiters.len(), 1);
+
+        let event = list.to_nostr_event(&org).unwrap();
+        assert_eq!(event.kind, Kind::FollowSet);
+        assert_eq!(OrgMembership::from_nostr_event(&event).unwrap(), list);
+
+        // Only the org may publish its list
+        assert!(list.to_nostr_event(&Keys::generate()).is_err());
+
+        // Ordinary follow sets are not recruiter lists
+        let follows = EventBuilder::new(Kind::FollowSet, "")
+            .tag(Tag::identifier("friends"))
+            .sign_with_keys(&org)
+            .unwrap();
+        assert!(OrgMembership::from_nostr_event(&follows).is_err());
+    }
+
+    #[test]
+    fn test_newest_list_wins() {
+        let org = Keys::generate();
+        let (old_recruiter, new_recruiter) = (Keys::generate().public_key(), Keys::generate().public_key());
+
+        let list = OrgMembership::new(org.public_key(), "Acme Corp".to_string());
+        let old = EventBuilder::new(Kind::FollowSet, "")
+            .tags([Tag::identifier(list.d_tag()), Tag::public_key(old_recruiter)])
+            .custom_created_at(Timestamp::now() - 3600)
+            .sign_with_keys(&org)
+            .unwrap();
+        let new = list.recruiter(new_recruiter).to_nostr_event(&org).unwrap();
+
+        let delegations = Delegations::from_membership_events([&new, &old]);
+        assert!(delegations.is_delegate(&org.public_key(), &new_recruiter));
+        assert!(!delegations.is_delegate(&org.public_key(), &old_recruiter));
+    }
+
+    #[test]
+    fn test_renamed_list_still_replaces() {
+        let org = Keys::generate();
+        let (removed, kept) = (Keys::generate().public_key(), Keys::generate().public_key());
+
+        let old = EventBuilder::new(Kind::FollowSet, "")
+            .tags([
+                Tag::identifier("recruiters:acme"),
+                Tag::custom(TagKind::Title, vec!["Acme"]),
+                Tag::public_key(removed),
+                Tag::public_key(kept),
+            ])
+            .custom_created_at(Timestamp::now() - 3600)
+            .sign_with_keys(&org)
+            .unwrap();
+        let renamed = EventBuilder::new(Kind::FollowSet, "")
+            .tags([
+                Tag::identifier("recruiters:acme"),
+                Tag::custom(TagKind::Title, vec!["Acme, Inc."]),
+                Tag::public_key(kept),
+            ])
+            .sign_with_keys(&org)
+            .unwrap();
+
+        let delegations = Delegations::from_membership_events([&old, &renamed]);
+        assert!(delegations.is_delegate(&org.public_key(), &kept));
+        assert!(!delegations.is_delegate(&org.public_key(), &removed));
+    }
+}
@@ -0,0 +1,603 @@
+// src/payment.rs
+// Optional `payment_proof` on listings and applications (see CLIENT_GUIDE.md)
+// Verified offline: the BOLT-11 invoice must be signed by a payee the caller
+// accepts, on an accepted network, and its description must name the listing
+// it pays for; the preimage must hash to its payment hash and its amount must
+// match the claimed `amount_sats`
+
+use crate::application::JobApplication;
+use crate::types::JobListing;
+use bech32::primitives::decode::CheckedHrpstring;
+use bech32::{Bech32, Fe32};
+use nostr_sdk::hashes::{sha256, Hash};
+use secp256k1::ecdsa::{RecoverableSignature, RecoveryId};
+use secp256k1::{Message, PublicKey as NodeId, SECP256K1};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// `payment_proof` content field
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PaymentProof {
+    pub amount_sats: u64,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invoice: Option<String>, // BOLT-11
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preimage: Option<String>, // hex
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub txid: Option<String>, // On-chain payments (not verified)
+}
+
+/// Why a payment proof doesn't check out
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PaymentError {
+    MissingInvoice,
+    MissingPreimage,
+    InvalidInvoice(String),
+    /// The invoice signature doesn't match its contents (or its `n` field)
+    InvalidSignature,
+    /// Signed by a node the caller doesn't accept payments to
+    UnknownPayee(NodeId),
+    /// A network the caller doesn't accept, e.g. "tb" (testnet)
+    UnsupportedCurrency(String),
+    /// The invoice description doesn't name the listing, so it could have paid for any
+    NotCommitted(String),
+    InvalidPreimage,
+    PreimageMismatch,
+    AmountMismatch { claimed_sats: u64, invoice_msat: Option<u64> },
+}
+
+impl std::fmt::Display for PaymentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            PaymentError::MissingInvoice => write!(f, "Payment proof has no invoice"),
+            PaymentError::MissingPreimage => write!(f, "Payment proof has no preimage"),
+            PaymentError::InvalidInvoice(reason) => write!(f, "Invalid BOLT-11 invoice: {}", reason),
+            PaymentError::InvalidSignature => write!(f, "Invoice signature does not verify"),
+            PaymentError::UnknownPayee(payee) => write!(f, "Invoice is payable to an unaccepted node: {}", payee),
+            PaymentError::UnsupportedCurrency(currency) => write!(f, "Invoice is for an unaccepted network: ln{}", currency),
+            PaymentError::NotCommitted(listing) => write!(f, "Invoice description does not commit to listing {}", listing),
+            PaymentError::InvalidPreimage => write!(f, "Preimage must be 32 bytes of hex"),
+            PaymentError::PreimageMismatch => write!(f, "Preimage does not match the invoice payment hash"),
+            PaymentError::AmountMismatch { claimed_sats, invoice_msat } => match invoice_msat {
+                Some(msat) => write!(f, "Claimed {} sats but invoice is for {} msat", claimed_sats, msat),
+                None => write!(f, "Claimed {} sats but invoice has no amount", claimed_sats),
+            },
+        }
+    }
+}
+
+impl std::error::Error for PaymentError {}
+
+/// Which invoices count as paid: signed by one of the accepted payees
+/// (Lightning node ids), on mainnet unless other networks are allowed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaymentPolicy {
+    payees: HashSet<NodeId>,
+    currencies: HashSet<String>,
+}
+
+impl Default for PaymentPolicy {
+    /// No accepted payees, so no proof verifies
+    fn default() -> Self {
+        Self {
+            payees: HashSet::new(),
+            currencies: HashSet::from(["bc".to_string()]),
+        }
+    }
+}
+
+impl PaymentPolicy {
+    pub fn new<I>(payees: I) -> Self
+    where
+        I: IntoIterator<Item = NodeId>,
+    {
+        Self {
+            payees: payees.into_iter().collect(),
+            ..Self::default()
+        }
+    }
+
+    pub fn payee(mut self, node_id: NodeId) -> Self {
+        self.payees.insert(node_id);
+        self
+    }
+
+    /// Also accept invoices for `currency`, e.g. "tb" (testnet) or "bcrt" (regtest)
+    pub fn allow_currency(mut self, currency: &str) -> Self {
+        self.currencies.insert(currency.to_lowercase());
+        self
+    }
+
+    fn check(&self, invoice: &Bolt11Invoice) -> Result<(), PaymentError> {
+        if !self.currencies.contains(&invoice.currency) {
+            return Err(PaymentError::UnsupportedCurrency(invoice.currency.clone()));
+        }
+        if !self.payees.contains(&invoice.payee) {
+            return Err(PaymentError::UnknownPayee(invoice.payee));
+        }
+        Ok(())
+    }
+}
+
+impl PaymentProof {
+    pub fn lightning(amount_sats: u64, invoice: String, preimage: String) -> Self {
+        Self {
+            amount_sats,
+            invoice: Some(invoice),
+            preimage: Some(preimage),
+            txid: None,
+        }
+    }
+
+    /// Check the proof offline against `policy` and return the verified
+    /// amount in sats. The invoice must commit to `listing` (the `d` tag of
+    /// the listing it paid for), so one payment can't be copied onto other
+    /// listings. Only Lightning proofs can be verified; `txid` alone is not
+    /// enough.
+    pub fn verify(&self, policy: &PaymentPolicy, listing: &str) -> Result<u64, PaymentError> {
+        let invoice = Bolt11Invoice::decode(self.invoice.as_deref().ok_or(PaymentError::MissingInvoice)?)?;
+        policy.check(&invoice)?;
+        if !invoice.commits_to(listing) {
+            return Err(PaymentError::NotCommitted(listing.to_string()));
+        }
+        let preimage = decode_hex(self.preimage.as_deref().ok_or(PaymentError::MissingPreimage)?)
+            .filter(|bytes| bytes.len() == 32)
+            .ok_or(PaymentError::InvalidPreimage)?;
+
+        if sha256::Hash::hash(&preimage).to_byte_array() != invoice.payment_hash {
+            return Err(PaymentError::PreimageMismatch);
+        }
+        if invoice.amount_msat != self.amount_sats.checked_mul(1000) {
+            return Err(PaymentError::AmountMismatch {
+                claimed_sats: self.amount_sats,
+                invoice_msat: invoice.amount_msat,
+            });
+        }
+        Ok(self.amount_sats)
+    }
+
+    pub fn is_verified(&self, policy: &PaymentPolicy, listing: &str) -> bool {
+        self.verify(policy, listing).is_ok()
+    }
+}
+
+impl JobListing {
+    /// Sats paid for this listing, if its payment proof verifies under `policy`
+    /// and its invoice commits to this listing's identifier
+    pub fn verified_payment_sats(&self, policy: &PaymentPolicy) -> Option<u64> {
+        self.payment_proof.as_ref()?.verify(policy, &self.identifier).ok()
+    }
+}
+
+impl JobApplication {
+    /// Sats paid with this application, if its payment proof verifies under
+    /// `policy` and its invoice commits to the listing applied to
+    pub fn verified_payment_sats(&self, policy: &PaymentPolicy) -> Option<u64> {
+        let listing = &self.job_address.as_ref()?.d;
+        self.payment_proof.as_ref()?.verify(policy, listing).ok()
+    }
+}
+
+/// The parts of a BOLT-11 invoice needed to check a payment proof.
+/// Decoding fails unless the signature verifies; `payee` is the signing node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bolt11Invoice {
+    pub currency: String, // "bc", "tb", "bcrt", ...
+    pub payee: NodeId,
+    pub amount_msat: Option<u64>,
+    pub timestamp: u64,
+    pub payment_hash: [u8; 32],
+    pub description: Option<String>,
+    pub description_hash: Option<[u8; 32]>,
+    pub expiry: Option<u64>, // Seconds
+}
+
+// Tagged field types (bech32 character value)
+const FIELD_PAYMENT_HASH: u8 = 1; // p
+const FIELD_EXPIRY: u8 = 6; // x
+const FIELD_DESCRIPTION: u8 = 13; // d
+const FIELD_PAYEE: u8 = 19; // n
+const FIELD_DESCRIPTION_HASH: u8 = 23; // h
+
+const TIMESTAMP_LEN: usize = 7;
+const SIGNATURE_LEN: usize = 104;
+
+impl Bolt11Invoice {
+    pub fn decode(invoice: &str) -> Result<Self, PaymentError> {
+        let invalid = |reason: &str| PaymentError::InvalidInvoice(reason.to_string());
+
+        let invoice = invoice.trim();
+        let invoice = invoice.strip_prefix("lightning:").unwrap_or(invoice).to_lowercase();
+        let parsed = CheckedHrpstring::new::<Bech32>(&invoice).map_err(|e| invalid(&e.to_string()))?;
+        let hrp = parsed.hrp().to_lowercase();
+        let (currency, amount_msat) = parse_hrp(&hrp).ok_or_else(|| invalid("bad prefix or amount"))?;
+
+        let data: Vec<u8> = parsed
+            .data_part_ascii_no_checksum()
+            .iter()
+            .map(|c| Fe32::from_char(char::from(*c)).map(Fe32::to_u8))
+            .collect::<Result<_, _>>()
+            .map_err(|e| invalid(&e.to_string()))?;
+        if data.len() < TIMESTAMP_LEN + SIGNATURE_LEN {
+            return Err(invalid("too short"));
+        }
+
+        let timestamp = read_u64(&data[..TIMESTAMP_LEN]);
+        let mut fields = &data[TIMESTAMP_LEN..data.len() - SIGNATURE_LEN];
+        let mut payment_hash = None;
+        let mut description = None;
+        let mut description_hash = None;
+        let mut expiry = None;
+        let mut payee = None;
+
+        while !fields.is_empty() {
+            if fields.len() < 3 {
+                return Err(invalid("truncated field"));
+            }
+            let field_type = fields[0];
+            let len = (fields[1] as usize) << 5 | fields[2] as usize;
+            let value = fields.get(3..3 + len).ok_or_else(|| invalid("truncated field"))?;
+            fields = &fields[3 + len..];
+
+            match field_type {
+                // Readers skip a `p` field of the wrong length
+                FIELD_PAYMENT_HASH if len == 52 && payment_hash.is_none() => {
+                    payment_hash = <[u8; 32]>::try_from(to_bytes(value)).ok();
+                }
+                FIELD_DESCRIPTION => {
+                    description = String::from_utf8(to_bytes(value)).ok();
+                }
+                FIELD_DESCRIPTION_HASH if len == 52 => {
+                    description_hash = <[u8; 32]>::try_from(to_bytes(value)).ok();
+                }
+                FIELD_EXPIRY => expiry = Some(read_u64(value)),
+                FIELD_PAYEE if len == 53 => {
+                    payee = Some(NodeId::from_slice(&to_bytes(value)).map_err(|_| invalid("bad payee"))?);
+                }
+                _ => {}
+            }
+        }
+
+        // Signed: sha256 of the prefix plus the data before the signature, zero-padded to bytes
+        let (signed, signature) = data.split_at(data.len() - SIGNATURE_LEN);
+        let mut message = hrp.into_bytes();
+        message.extend(to_bytes_padded(signed));
+        let signature = to_bytes(signature);
+        let message = Message::from_digest(sha256::Hash::hash(&message).to_byte_array());
+        let recoverable = RecoveryId::from_i32(i32::from(signature[64]))
+            .and_then(|id| RecoverableSignature::from_compact(&signature[..64], id))
+            .map_err(|_| PaymentError::InvalidSignature)?;
+        let payee = match payee {
+            Some(payee) => payee,
+            None => SECP256K1.recover_ecdsa(&message, &recoverable).map_err(|_| PaymentError::InvalidSignature)?,
+        };
+        // Also rejects high-S signatures, as BOLT-11 requires
+        SECP256K1
+            .verify_ecdsa(&message, &recoverable.to_standard(), &payee)
+            .map_err(|_| PaymentError::InvalidSignature)?;
+
+        Ok(Self {
+            currency,
+            payee,
+            amount_msat,
+            timestamp,
+            payment_hash: payment_hash.ok_or_else(|| invalid("missing payment hash"))?,
+            description,
+            description_hash,
+            expiry,
+        })
+    }
+
+    /// Whether the description names `listing` as a whole word, or the
+    /// description hash is the hash of exactly `listing`
+    pub fn commits_to(&self, listing: &str) -> bool {
+        let in_description = self
+            .description
+            .as_deref()
+            .is_some_and(|d| d.split_whitespace().any(|word| word == listing));
+        let hashed = self.description_hash == Some(sha256::Hash::hash(listing.as_bytes()).to_byte_array());
+        !listing.is_empty() && (in_description || hashed)
+    }
+}
+
+/// `ln` + currency + optional amount with multiplier, e.g. `lnbc2500u`
+fn parse_hrp(hrp: &str) -> Option<(String, Option<u64>)> {
+    let rest = hrp.strip_prefix("ln")?;
+    let digits_at = rest.find(|c: char| c.is_ascii_digit()).unwrap_or(rest.len());
+    let (currency, amount) = rest.split_at(digits_at);
+    if currency.is_empty() {
+        return None;
+    }
+    if amount.is_empty() {
+        return Some((currency.to_string(), None));
+    }
+
+    let (number, multiplier) = match amount.char_indices().last()? {
+        (i, c) if c.is_ascii_alphabetic() => (&amount[..i], Some(c)),
+        _ => (amount, None),
+    };
+    let number: u64 = number.parse().ok()?;
+    // msat per unit of the multiplier
+    let msat = match multiplier {
+        None => number.checked_mul(100_000_000_000)?,
+        Some('m') => number.checked_mul(100_000_000)?,
+        Some('u') => number.checked_mul(100_000)?,
+        Some('n') => number.checked_mul(100)?,
+        Some('p') if number.is_multiple_of(10) => number / 10,
+        _ => return None,
+    };
+    Some((currency.to_string(), Some(msat)))
+}
+
+/// Big-endian integer from 5-bit groups
+fn read_u64(groups: &[u8]) -> u64 {
+    groups.iter().fold(0, |acc, g| acc << 5 | u64::from(*g))
+}
+
+/// 5-bit groups to bytes, dropping trailing padding bits
+fn to_bytes(groups: &[u8]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(groups.len() * 5 / 8);
+    let (mut acc, mut bits) = (0u32, 0);
+    for g in groups {
+        acc = acc << 5 | u32::from(*g);
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((acc >> bits) as u8);
+            acc &= (1 << bits) - 1;
+        }
+    }
+    bytes
+}
+
+/// 5-bit groups to bytes, zero-padding the last byte
+fn to_bytes_padded(groups: &[u8]) -> Vec<u8> {
+    let mut bytes = to_bytes(groups);
+    let bits = groups.len() * 5 % 8;
+    if bits > 0 {
+        let tail = read_u64(&groups[groups.len() - bits.div_ceil(5)..]);
+        bytes.push(((tail & ((1 << bits) - 1)) << (8 - bits)) as u8);
+    }
+    bytes
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::test_fixtures::sample_job;
+    use bech32::{Fe32IterExt, Hrp};
+
+    fn groups(value: u64, len: usize) -> Vec<u8> {
+        (0..len).rev().map(|i| ((value >> (5 * i)) & 31) as u8).collect()
+    }
+
+    fn bytes_to_groups(bytes: &[u8]) -> Vec<u8> {
+        let (mut out, mut acc, mut bits) = (Vec::new(), 0u32, 0);
+        for b in bytes {
+            acc = acc << 8 | u32::from(*b);
+            bits += 8;
+            while bits >= 5 {
+                bits -= 5;
+                out.push(((acc >> bits) & 31) as u8);
+            }
+        }
+        if bits > 0 {
+            out.push(((acc << (5 - bits)) & 31) as u8);
+        }
+        out
+    }
+
+    fn field(field_type: u8, value: Vec<u8>) -> Vec<u8> {
+        let mut out = vec![field_type];
+        out.extend(groups(value.len() as u64, 2));
+        out.extend(value);
+        out
+    }
+
+    fn node(seed: u8) -> secp256k1::SecretKey {
+        secp256k1::SecretKey::from_slice(&[seed; 32]).unwrap()
+    }
+
+    fn encode(hrp: &str, data: Vec<u8>) -> String {
+        let hrp = Hrp::parse(hrp).unwrap();
+        data.into_iter()
+            .map(|g| Fe32::try_from(g).unwrap())
+            .with_checksum::<Bech32>(&hrp)
+            .chars()
+            .collect()
+    }
+
+    /// Fields laid out the way a wallet does, without the signature
+    fn unsigned(payment_hash: &[u8; 32], description: &str) -> Vec<u8> {
+        let mut data = groups(1_700_000_000, TIMESTAMP_LEN);
+        data.extend(field(FIELD_PAYMENT_HASH, bytes_to_groups(payment_hash)));
+        data.extend(field(FIELD_DESCRIPTION, bytes_to_groups(description.as_bytes())));
+        data.extend(field(FIELD_EXPIRY, groups(3600, 3)));
+        data
+    }
+
+    /// `data` signed by `node` for `signed_hrp`, encoded under `hrp`
+    fn sign(hrp: &str, signed_hrp: &str, mut data: Vec<u8>, node: &secp256k1::SecretKey) -> String {
+        let mut message = signed_hrp.as_bytes().to_vec();
+        message.extend(to_bytes_padded(&data));
+        let digest = Message::from_digest(sha256::Hash::hash(&message).to_byte_array());
+        let (id, compact) = SECP256K1.sign_ecdsa_recoverable(&digest, node).serialize_compact();
+        let mut signature = compact.to_vec();
+        signature.push(id.to_i32() as u8);
+        data.extend(bytes_to_groups(&signature));
+        encode(hrp, data)
+    }
+
+    /// `hrp` invoice signed by the node `policy()` accepts
+    pub(crate) fn invoice(hrp: &str, payment_hash: &[u8; 32], description: &str) -> String {
+        sign(hrp, hrp, unsigned(payment_hash, description), &node(1))
+    }
+
+    pub(crate) fn policy() -> PaymentPolicy {
+        PaymentPolicy::new([node(1).public_key(SECP256K1)])
+    }
+
+    pub(crate) fn to_hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn test_decode_bolt11() {
+        let hash = [7u8; 32];
+        let decoded = Bolt11Invoice::decode(&invoice("lnbc10u", &hash, "Job posting fee")).unwrap();
+        assert_eq!(decoded.currency, "bc");
+        assert_eq!(decoded.amount_msat, Some(1_000_000));
+        assert_eq!(decoded.timestamp, 1_700_000_000);
+        assert_eq!(decoded.payment_hash, hash);
+        assert_eq!(decoded.description.as_deref(), Some("Job posting fee"));
+        assert_eq!(decoded.expiry, Some(3600));
+        assert_eq!(decoded.payee, node(1).public_key(SECP256K1));
+
+        // BOLT-11 example invoice, payee recovered from the signature
+        let spec = Bolt11Invoice::decode(
+            "lnbc1pvjluezsp5zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zygspp5qqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqypqdpl2pkx2ctnv5sxxmmwwd5kgetjypeh2ursdae8g6twvus8g6rfwvs8qun0dfjkxaq9qrsgq357wnc5r2ueh7ck6q93dj32dlqnls087fxdwk8qakdyafkq3yap9us6v52vjjsrvywa6rt52cm9r9zqt8r2t7mlcwspyetp5h2tztugp9lfyql",
+        )
+        .unwrap();
+        assert_eq!(spec.payee.to_string(), "03e7156ae33b0a208d0744199163177e909e80176e55d97a2f221ede0f934dd9ad");
+
+        // Real wallet invoice (with route hints and features we skip)
+        let real = Bolt11Invoice::decode(
+            "lnbc210n1pj99rx0pp5ehevgz9nf7d97h05fgkdeqxzytm6yuxd7048axru03fpzxxvzt7shp5gv7ef0s26pw5gy5dpwvsh6qgc8se8x2lmz2ev90l9vjqzcns6u6scqzzsxqyz5vqsp5rdjyt9jr2avv2runy330766avkweqp30ndnyt9x6dp5juzn7q0nq9qyyssq2mykpgu04q0hlga228kx9v95meaqzk8a9cnvya305l4c353u3h04azuh9hsmd503x6jlzjrsqzark5dxx30s46vuatwzjhzmkt3j4tgqu35rms",
+        )
+        .unwrap();
+        assert_eq!(real.amount_msat, Some(21_000));
+        assert_eq!(to_hex(&real.payment_hash[..2]), "cdf2");
+
+        assert_eq!(parse_hrp("lntb2500n"), Some(("tb".to_string(), Some(250_000))));
+        assert_eq!(parse_hrp("lnbcrt"), Some(("bcrt".to_string(), None)));
+        assert_eq!(parse_hrp("lnbc25p"), None);
+
+        let mut corrupted = invoice("lnbc10u", &hash, "x");
+        corrupted.pop();
+        corrupted.push('q');
+        assert!(Bolt11Invoice::decode(&corrupted).is_err());
+    }
+
+    #[test]
+    fn test_verify_preimage_and_amount() {
+        let preimage = [42u8; 32];
+        let hash = sha256::Hash::hash(&preimage).to_byte_array();
+        let invoice = invoice("lnbc10u", &hash, "Posting fee for job-001");
+
+        let proof = PaymentProof::lightning(1000, invoice.clone(), to_hex(&preimage));
+        assert_eq!(proof.verify(&policy(), "job-001"), Ok(1000));
+
+        let inflated = PaymentProof::lightning(5000, invoice.clone(), to_hex(&preimage));
+        assert!(matches!(inflated.verify(&policy(), "job-001"), Err(PaymentError::AmountMismatch { claimed_sats: 5000, .. })));
+
+        let wrong = PaymentProof::lightning(1000, invoice, to_hex(&[1u8; 32]));
+        assert_eq!(wrong.verify(&policy(), "job-001"), Err(PaymentError::PreimageMismatch));
+
+        let onchain = PaymentProof { amount_sats: 1000, invoice: None, preimage: None, txid: Some("ab".into()) };
+        assert_eq!(onchain.verify(&policy(), "job-001"), Err(PaymentError::MissingInvoice));
+    }
+
+    #[test]
+    fn test_forged_invoices_rejected() {
+        let preimage = [42u8; 32];
+        let hash = sha256::Hash::hash(&preimage).to_byte_array();
+        let proof = |invoice: String| PaymentProof::lightning(1000, invoice, to_hex(&preimage));
+
+        // Zeroed signature
+        let mut zeroed = unsigned(&hash, "Posting fee for job-001");
+        zeroed.extend(vec![0; SIGNATURE_LEN]);
+        assert_eq!(proof(encode("lnbc10u", zeroed)).verify(&policy(), "job-001"), Err(PaymentError::InvalidSignature));
+
+        // Signed by another node than the one it names
+        let mut named = unsigned(&hash, "Posting fee for job-001");
+        named.extend(field(FIELD_PAYEE, bytes_to_groups(&node(1).public_key(SECP256K1).serialize())));
+        let impostor = sign("lnbc10u", "lnbc10u", named.clone(), &node(2));
+        assert_eq!(proof(impostor).verify(&policy(), "job-001"), Err(PaymentError::InvalidSignature));
+        assert_eq!(proof(sign("lnbc10u", "lnbc10u", named, &node(1))).verify(&policy(), "job-001"), Ok(1000));
+
+        // Amount changed after signing: recovers some other key
+        let altered = sign("lnbc10u", "lnbc50u", unsigned(&hash, "Posting fee for job-001"), &node(1));
+        assert!(matches!(proof(altered).verify(&policy(), "job-001"), Err(PaymentError::UnknownPayee(_))));
+
+        // Validly signed, but by a node the caller doesn't accept
+        let self_issued = sign("lnbc10u", "lnbc10u", unsigned(&hash, "Posting fee for job-001"), &node(2));
+        assert_eq!(
+            proof(self_issued).verify(&policy(), "job-001"),
+            Err(PaymentError::UnknownPayee(node(2).public_key(SECP256K1)))
+        );
+
+        // Test networks only count when allowed
+        let testnet = proof(invoice("lntb10u", &hash, "Posting fee for job-001"));
+        assert_eq!(testnet.verify(&policy(), "job-001"), Err(PaymentError::UnsupportedCurrency("tb".to_string())));
+        assert_eq!(testnet.verify(&policy().allow_currency("tb"), "job-001"), Ok(1000));
+        assert!(!testnet.is_verified(&PaymentPolicy::default().allow_currency("tb"), "job-001"));
+    }
+
+    #[test]
+    fn test_invoice_must_commit_to_the_listing() {
+        let preimage = [42u8; 32];
+        let hash = sha256::Hash::hash(&preimage).to_byte_array();
+        let proof = |description: &str| PaymentProof::lightning(1000, invoice("lnbc10u", &hash, description), to_hex(&preimage));
+
+        assert_eq!(proof("Posting fee for job-001").verify(&policy(), "job-001"), Ok(1000));
+        // The same payment copied onto another listing
+        assert_eq!(
+            proof("Posting fee for job-001").verify(&policy(), "job-002"),
+            Err(PaymentError::NotCommitted("job-002".to_string()))
+        );
+        // Naming the listing only as part of a longer word doesn't count
+        assert!(!proof("Posting fee for job-0010").is_verified(&policy(), "job-001"));
+        assert!(!proof("Posting fee").is_verified(&policy(), ""));
+
+        // Or commit through the description hash
+        let mut hashed = unsigned(&hash, "");
+        hashed.truncate(TIMESTAMP_LEN + 3 + 52);
+        hashed.extend(field(FIELD_DESCRIPTION_HASH, bytes_to_groups(&sha256::Hash::hash(b"job-001").to_byte_array())));
+        let hashed = PaymentProof::lightning(1000, sign("lnbc10u", "lnbc10u", hashed, &node(1)), to_hex(&preimage));
+        assert_eq!(hashed.verify(&policy(), "job-001"), Ok(1000));
+        assert!(!hashed.is_verified(&policy(), "job-002"));
+    }
+
+    #[test]
+    fn test_paid_listing_filter() {
+        let preimage = [9u8; 32];
+        let hash = sha256::Hash::hash(&preimage).to_byte_array();
+        let proof = PaymentProof::lightning(2000, invoice("lnbc20u", &hash, "Posting fee for job-001"), to_hex(&preimage));
+        let listing = |proof: Option<PaymentProof>| {
+            let mut job = sample_job("job-001");
+            job.payment_proof = proof;
+            job
+        };
+
+        let keys = nostr_sdk::Keys::generate();
+        let event = listing(Some(proof.clone())).to_nostr_event(&keys).unwrap();
+        let content: serde_json::Value = serde_json::from_str(&event.content).unwrap();
+        assert_eq!(content["payment_proof"]["amount_sats"], 2000);
+        let paid = JobListing::from_nostr_event(&event).unwrap();
+        assert_eq!(paid.verified_payment_sats(&policy()), Some(2000));
+
+        // Claiming more than the invoice paid doesn't count
+        let inflated = listing(Some(PaymentProof { amount_sats: 50_000, ..proof.clone() }));
+        // Nor does another listing's payment
+        let mut copied = sample_job("job-002");
+        copied.payment_proof = Some(proof);
+        let query = crate::JobQuery::new().paid_min(1000, &policy());
+        assert!(query.matches(&paid));
+        assert!(!query.matches(&inflated));
+        assert!(!query.matches(&copied));
+        assert!(!query.matches(&listing(None)));
+    }
+}
@@ -0,0 +1,256 @@
+// src/pow.rs
+// NIP-13 proof-of-work: a free anti-spam signal for posters without Lightning
+// Mining runs on several threads and gives up after a timeout
+
+use crate::signer::SignEvent;
+use crate::types::JobListing;
+use anyhow::Result;
+use nostr_sdk::hashes::{sha256, Hash, HashEngine};
+use nostr_sdk::nips::nip13;
+use nostr_sdk::prelude::*;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Target difficulty, worker threads and how long to try
+#[derive(Debug, Clone)]
+pub struct PowOptions {
+    pub difficulty: u8,
+    pub threads: usize,
+    pub timeout: Duration,
+}
+
+impl PowOptions {
+    /// All available cores, 60 second timeout
+    pub fn new(difficulty: u8) -> Self {
+        Self {
+            difficulty,
+            threads: thread::available_parallelism().map_or(1, |n| n.get()),
+            timeout: Duration::from_secs(60),
+        }
+    }
+
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+/// Mining gave up
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PowError {
+    Timeout { difficulty: u8, attempts: u64 },
+}
+
+impl std::fmt::Display for PowError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            PowError::Timeout { difficulty, attempts } => write!(
+                f,
+                "No nonce found for difficulty {} after {} attempts",
+                difficulty, attempts
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PowError {}
+
+// Attempts between deadline checks
+const CHECK_INTERVAL: u64 = 4096;
+
+/// Add a `["nonce", "<n>", "<difficulty>"]` tag whose event id has at least
+/// `difficulty` leading zero bits. Any existing nonce tag is replaced.
+pub fn mine(unsigned: UnsignedEvent, options: &PowOptions) -> Result<UnsignedEvent, PowError> {
+    let mut tags: Vec<Tag> = unsigned
+        .tags
+        .into_iter()
+        .filter(|t| t.kind() != TagKind::Nonce)
+        .collect();
+
+    // The nonce tag goes last, so the serialized event is
+    // prefix + nonce + suffix and only the tail changes per attempt
+    let mut prefix = serde_json::json!([0, unsigned.pubkey, unsigned.created_at, unsigned.kind, tags]).to_string();
+    prefix.truncate(prefix.len() - 2); // "]]"
+    prefix.push_str(if tags.is_empty() { "[\"nonce\",\"" } else { ",[\"nonce\",\"" });
+    let suffix = format!(
+        "\",\"{}\"]],{}]",
+        options.difficulty,
+        serde_json::Value::from(unsigned.content.as_str())
+    );
+
+    let mut midstate = sha256::Hash::engine();
+    midstate.input(prefix.as_bytes());
+
+    let deadline = Instant::now() + options.timeout;
+    let found = AtomicBool::new(false);
+    let attempts = AtomicU64::new(0);
+    let threads = options.threads.max(1) as u128;
+
+    let nonce = thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|start| {
+                let (midstate, suffix, found, attempts) = (&midstate, &suffix, &found, &attempts);
+                scope.spawn(move || {
+                    let mut nonce = start;
+                    let mut tried = 0u64;
+                    while !found.load(Ordering::Relaxed) {
+                        let mut engine = midstate.clone();
+                        engine.input(nonce.to_string().as_bytes());
+                        engine.input(suffix.as_bytes());
+                        let hash = sha256::Hash::from_engine(engine);
+                        if nip13::get_leading_zero_bits(hash.as_byte_array()) >= options.difficulty {
+                            found.store(true, Ordering::Relaxed);
+                            attempts.fetch_add(tried, Ordering::Relaxed);
+                            return Some(nonce);
+                        }
+                        nonce += threads;
+                        tried += 1;
+                        if tried.is_multiple_of(CHECK_INTERVAL) && Instant::now() >= deadline {
+                            break;
+                        }
+                    }
+                    attempts.fetch_add(tried, Ordering::Relaxed);
+                    None
+                })
+            })
+            .collect();
+        workers.into_iter().filter_map(|w| w.join().ok().flatten()).min()
+    });
+
+    let Some(nonce) = nonce else {
+        return Err(PowError::Timeout {
+            difficulty: options.difficulty,
+            attempts: attempts.into_inner(),
+        });
+    };
+    tags.push(Tag::pow(nonce, options.difficulty));
+    let mut mined = UnsignedEvent::new(unsigned.pubkey, unsigned.created_at, unsigned.kind, tags, unsigned.content);
+    mined.ensure_id();
+    Ok(mined)
+}
+
+/// Committed (nonce tag) versus actual (event id) difficulty
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PowCheck {
+    pub committed: Option<u8>,
+    pub actual: u8,
+}
+
+impl PowCheck {
+    /// The event meets the difficulty it commits to
+    pub fn is_valid(&self) -> bool {
+        self.committed.is_some_and(|c| self.actual >= c)
+    }
+
+    /// Difficulty to credit: the commitment, when met. Extra zero bits
+    /// beyond it are luck, and uncommitted work doesn't count.
+    pub fn effective_difficulty(&self) -> u8 {
+        match self.committed {
+            Some(committed) if self.actual >= committed => committed,
+            _ => 0,
+        }
+    }
+}
+
+pub fn check_pow(event: &Event) -> PowCheck {
+    let committed = event.tags.iter().find_map(|t| match t.as_slice() {
+        [name, _nonce, difficulty, ..] if name == "nonce" => difficulty.parse().ok(),
+        _ => None,
+    });
+    PowCheck {
+        committed,
+        actual: nip13::get_leading_zero_bits(event.id.as_bytes()),
+    }
+}
+
+impl JobListing {
+    /// Convert to a kind 39993 event with a NIP-13 nonce mined to `options.difficulty`
+    pub fn to_mined_nostr_event(&self, keys: &Keys, options: &PowOptions) -> Result<Event> {
+        let unsigned = mine(self.unsigned_event(keys.public_key())?, options)?;
+        Ok(unsigned.sign_with_keys(keys)?)
+    }
+
+    /// `to_mined_nostr_event` for any `NostrSigner`. Mining runs on the
+    /// blocking pool so it doesn't stall the async runtime.
+    pub async fn mined_sign_with<S>(&self, signer: &S, options: &PowOptions) -> Result<Event>
+    where
+        S: NostrSigner + ?Sized,
+    {
+        let unsigned = self.unsigned_event(signer.get_public_key().await?)?;
+        let options = options.clone();
+        let mined = tokio::task::spawn_blocking(move || mine(unsigned, &options)).await??;
+        Ok(signer.sign_event(mined).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures;
+
+    fn sample_job() -> JobListing {
+        // Escaped characters in the content must survive nonce mining
+        let mut job = test_fixtures::sample_job("job-001");
+        job.description = "Build \"decentralized\" systems\n".to_string();
+        job
+    }
+
+    #[test]
+    fn test_mined_event_meets_commitment() {
+        let keys = Keys::generate();
+        let event = sample_job()
+            .to_mined_nostr_event(&keys, &PowOptions::new(10).threads(4))
+            .unwrap();
+        event.verify().unwrap();
+
+        let check = check_pow(&event);
+        assert_eq!(check.committed, Some(10));
+        assert!(check.actual >= 10);
+        assert!(check.is_valid());
+        assert_eq!(check.effective_difficulty(), 10);
+        assert_eq!(JobListing::from_nostr_event(&event).unwrap(), sample_job());
+    }
+
+    #[tokio::test]
+    async fn test_mined_sign_with_any_signer() {
+        let keys = Keys::generate();
+        let signer: std::sync::Arc<dyn NostrSigner> = std::sync::Arc::new(keys.clone());
+        let event = sample_job()
+            .mined_sign_with(&signer, &PowOptions::new(8).threads(2))
+            .await
+            .unwrap();
+        event.verify().unwrap();
+        assert_eq!(event.pubkey, keys.public_key());
+        assert!(check_pow(&event).is_valid());
+        assert_eq!(check_pow(&event).effective_difficulty(), 8);
+    }
+
+    #[test]
+    fn test_unmet_or_missing_commitment() {
+        let keys = Keys::generate();
+        let plain = sample_job().to_nostr_event(&keys).unwrap();
+        assert_eq!(check_pow(&plain).committed, None);
+        assert_eq!(check_pow(&plain).effective_difficulty(), 0);
+
+        // Claims 200 bits without doing the work
+        let forged = EventBuilder::text_note("spam")
+            .tag(Tag::pow(1, 200))
+            .sign_with_keys(&keys)
+            .unwrap();
+        assert!(!check_pow(&forged).is_valid());
+        assert_eq!(check_pow(&forged).effective_difficulty(), 0);
+    }
+
+    #[test]
+    fn test_timeout() {
+        let unsigned = EventBuilder::text_note("hard").build(Keys::generate().public_key());
+        let options = PowOptions::new(255).threads(2).timeout(Duration::from_millis(50));
+        assert!(matches!(mine(unsigned, &options), Err(PowError::Timeout { difficulty: 255, .. })));
+    }
+}
@@ -0,0 +1,302 @@
+// src/profile.rs
+// Non-job content on kind 39993: resumes and skill assertions
+// The content `type` field decides how an event is read
+
+use crate::signer::SignEvent;
+use crate::events::{INDEX_SKILL, KIND_JOB_LISTING, index_tag};
+use crate::types::{JobListing, ValidationError};
+use crate::versioning::{MigrationRegistry, SchemaError};
+use anyhow::{anyhow, Result};
+use nostr_sdk::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Resume / professional profile (kind 39993, type "resume")
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Profile {
+    #[serde(rename = "type")]
+    pub profile_type: String, // "resume"
+    pub version: u32,
+
+    pub name: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub headline: Option<String>,
+
+    #[serde(default)]
+    pub skills: Vec<String>,
+
+    #[serde(default)]
+    pub work_history: Vec<WorkHistoryEntry>,
+
+    // === Extensions ===
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+/// One position in a resume's work history
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WorkHistoryEntry {
+    pub role: String,
+    pub company: String,
+    pub start: String, // YYYY-MM
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end: Option<String>, // None while current
+}
+
+impl Profile {
+    /// `d` tag: one resume per author; publishing again replaces it
+    pub const D_TAG: &'static str = "resume";
+
+    pub fn new(name: String) -> Self {
+        Self {
+            profile_type: "resume".to_string(),
+            version: 1,
+            name,
+            headline: None,
+            skills: Vec::new(),
+            work_history: Vec::new(),
+            extra: HashMap::new(),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        if self.profile_type != "resume" {
+            return Err(ValidationError::InvalidConfiguration(
+                "profile type must be \"resume\"",
+            ));
+        }
+        if self.name.is_empty() {
+            return Err(ValidationError::MissingField("name"));
+        }
+        if self.work_history.iter().any(|w| w.role.is_empty() || w.company.is_empty()) {
+            return Err(ValidationError::MissingField("work_history role/company"));
+        }
+        Ok(())
+    }
+
+    /// Convert to a kind 39993 event tagged `t=resume`
+    pub fn to_nostr_event(&self, keys: &Keys) -> Result<Event> {
+        Ok(self.unsigned_event(keys.public_key())?.sign_with_keys(keys)?)
+    }
+}
+
+impl SignEvent for Profile {
+    fn unsigned_event(&self, author: PublicKey) -> Result<UnsignedEvent> {
+        self.validate()?;
+        let mut tags = vec![
+            Tag::identifier(Self::D_TAG),
+            Tag::hashtag("resume"),
+        ];
+        for skill in &self.skills {
+            tags.push(Tag::custom(TagKind::Custom("skill".into()), vec![skill.clone()]));
+            tags.push(index_tag(INDEX_SKILL, skill));
+        }
+
+        Ok(EventBuilder::new(Kind::from(KIND_JOB_LISTING), serde_json::to_string(self)?)
+            .tags(tags)
+            .build(author))
+    }
+}
+
+/// Skill assertion (kind 39993, type "skill")
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SkillAssertion {
+    #[serde(rename = "type")]
+    pub assertion_type: String, // "skill"
+    pub version: u32,
+
+    pub name: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub level: Option<String>, // e.g. "beginner", "expert"
+
+    // === Extensions ===
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+impl SkillAssertion {
+    pub fn new(name: String, level: Option<String>) -> Self {
+        Self {
+            assertion_type: "skill".to_string(),
+            version: 1,
+            name,
+            level,
+            extra: HashMap::new(),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        if self.assertion_type != "skill" {
+            return Err(ValidationError::InvalidConfiguration(
+                "skill assertion type must be \"skill\"",
+            ));
+        }
+        if self.name.trim().is_empty() {
+            return Err(ValidationError::MissingField("name"));
+        }
+        Ok(())
+    }
+
+    /// `d` tag: one assertion per author and skill
+    pub fn d_tag(&self) -> String {
+        format!("skill:{}", self.name.trim().to_lowercase())
+    }
+
+    /// Convert to a kind 39993 event tagged `t=skill`
+    pub fn to_nostr_event(&self, keys: &Keys) -> Result<Event> {
+        Ok(self.unsigned_event(keys.public_key())?.sign_with_keys(keys)?)
+    }
+}
+
+impl SignEvent for SkillAssertion {
+    fn unsigned_event(&self, author: PublicKey) -> Result<UnsignedEvent> {
+        self.validate()?;
+        let mut tags = vec![
+            Tag::identifier(self.d_tag()),
+            Tag::hashtag("skill"),
+            Tag::custom(TagKind::Custom("skill".into()), vec![self.name.clone()]),
+            index_tag(INDEX_SKILL, &self.name),
+        ];
+        if let Some(level) = &self.level {
+            tags.push(Tag::custom(TagKind::Custom("level".into()), vec![level.clone()]));
+        }
+
+        Ok(EventBuilder::new(Kind::from(KIND_JOB_LISTING), serde_json::to_string(self)?)
+            .tags(tags)
+            .build(author))
+    }
+}
+
+/// Any kind 39993 event, read according to its content `type`
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProfessionalEvent {
+    Job(Box<JobListing>),
+    Resume(Profile),
+    Skill(SkillAssertion),
+}
+
+/// The `type` field every content object in this family carries
+#[derive(Deserialize)]
+struct ContentHeader {
+    #[serde(rename = "type")]
+    content_type: Option<String>,
+}
+
+impl ProfessionalEvent {
+    /// Dispatch on the content `type`. Content without one (Schema.org
+    /// JobPosting JSON, or a Markdown description with tags) is a job.
+    pub fn from_nostr_event(event: &Event) -> Result<Self> {
+        Self::from_nostr_event_with(event, &MigrationRegistry::default())
+    }
+
+    /// Dispatch on the content `type`, migrating newer versions with `registry`
+    pub fn from_nostr_event_with(event: &Event, registry: &MigrationRegistry) -> Result<Self> {
+        if event.kind != Kind::from(KIND_JOB_LISTING) {
+            return Err(anyhow!(
+                "Invalid event kind: expected {}, got {}",
+                KIND_JOB_LISTING,
+                event.kind
+            ));
+        }
+
+        let content_type = serde_json::from_str::<ContentHeader>(&event.content)
+            .ok()
+            .and_then(|header| header.content_type);
+        match content_type.as_deref() {
+            None | Some("job") => Ok(Self::Job(Box::new(JobListing::from_nostr_event_with(event, registry)?))),
+            Some("resume") => {
+                let content = registry.upgrade("resume", serde_json::from_str(&event.content)?)?;
+                let profile: Profile = serde_json::from_value(content)?;
+                profile.validate()?;
+                Ok(Self::Resume(profile))
+            }
+            Some("skill") => {
+                let content = registry.upgrade("skill", serde_json::from_str(&event.content)?)?;
+                let skill: SkillAssertion = serde_json::from_value(content)?;
+                skill.validate()?;
+                Ok(Self::Skill(skill))
+            }
+            Some(other) => Err(SchemaError::UnsupportedType(other.to_string()).into()),
+        }
+    }
+
+    pub fn as_job(&self) -> Option<&JobListing> {
+        match self {
+            Self::Job(job) => Some(job),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::sample_job;
+
+    fn sample_profile() -> Profile {
+        let mut profile = Profile::new("Alice Example".to_string());
+        profile.headline = Some("Backend Engineer".to_string());
+        profile.skills = vec!["rust".to_string(), "grpc".to_string()];
+        profile.work_history.push(WorkHistoryEntry {
+            role: "Backend Engineer".to_string(),
+            company: "Acme Corp".to_string(),
+            start: "2020-01".to_string(),
+            end: Some("2024-05".to_string()),
+        });
+        profile
+    }
+
+    #[test]
+    fn test_dispatch_by_content_type() {
+        let keys = Keys::generate();
+
+        let resume = sample_profile().to_nostr_event(&keys).unwrap();
+        assert_eq!(resume.tags.identifier(), Some(Profile::D_TAG));
+        assert!(JobListing::from_nostr_event(&resume).is_err());
+        assert_eq!(
+            ProfessionalEvent::from_nostr_event(&resume).unwrap(),
+            ProfessionalEvent::Resume(sample_profile())
+        );
+
+        let skill = SkillAssertion::new("Rust".to_string(), Some("expert".to_string()));
+        let event = skill.to_nostr_event(&keys).unwrap();
+        assert_eq!(event.tags.identifier(), Some("skill:rust"));
+        assert_eq!(ProfessionalEvent::from_nostr_event(&event).unwrap(), ProfessionalEvent::Skill(skill));
+
+        let job = sample_job("job-001");
+        let event = job.to_nostr_event(&keys).unwrap();
+        let decoded = ProfessionalEvent::from_nostr_event(&event).unwrap();
+        assert_eq!(decoded.as_job(), Some(&job));
+    }
+
+    #[test]
+    fn test_resume_not_matched_by_jobs_filter() {
+        let keys = Keys::generate();
+        let resume = sample_profile().to_nostr_event(&keys).unwrap();
+        let filter = crate::JobsFilter::new().skill("rust").build();
+        assert!(!filter.match_event(&resume, MatchEventOptions::new()));
+    }
+
+    #[test]
+    fn test_unknown_type_and_forward_compatible_fields() {
+        let keys = Keys::generate();
+        let unknown = EventBuilder::new(Kind::from(KIND_JOB_LISTING), r#"{"type":"badge","version":1}"#)
+            .sign_with_keys(&keys)
+            .unwrap();
+        assert!(ProfessionalEvent::from_nostr_event(&unknown).is_err());
+
+        let content = r#"{"type":"resume","version":1,"name":"Bob","location":"Berlin"}"#;
+        let event = EventBuilder::new(Kind::from(KIND_JOB_LISTING), content)
+            .sign_with_keys(&keys)
+            .unwrap();
+        let ProfessionalEvent::Resume(profile) = ProfessionalEvent::from_nostr_event(&event).unwrap() else {
+            panic!("expected a resume");
+        };
+        assert_eq!(profile.name, "Bob");
+        assert!(profile.skills.is_empty());
+        assert_eq!(profile.extra.get("location"), Some(&serde_json::json!("Berlin")));
+    }
+}
@@ -0,0 +1,479 @@
+// src/proof.rs
+// Kind 39994: Professional proofs (employment evidence)
+// Kind 39996: Employer verifications countersigning those proofs
+
+use crate::signer::SignEvent;
+use crate::events::{KIND_EMPLOYER_VERIFICATION, KIND_PROFESSIONAL_PROOF};
+use crate::types::ValidationError;
+use anyhow::{anyhow, Result};
+use nostr_sdk::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+// ==================== 39994: Professional Proof ====================
+
+/// Employment proof (kind 39994, type "employment-proof")
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProfessionalProof {
+    #[serde(rename = "type")]
+    pub proof_type: String, // "employment-proof"
+    pub version: u32,
+
+    pub issued_by: PublicKey,
+    pub subject: PublicKey,
+    pub role: String,
+    pub company: String,
+    pub period: EmploymentPeriod,
+
+    #[serde(rename = "verificationMethod")]
+    pub verification_method: VerificationMethod,
+
+    #[serde(rename = "referenceUrl", skip_serializing_if = "Option::is_none")]
+    pub reference_url: Option<String>,
+
+    // === Extensions ===
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+
+    // === From tags (not part of content) ===
+    #[serde(skip)]
+    pub claim_event_id: Option<EventId>, // "e" tag, e.g. the resume event
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EmploymentPeriod {
+    pub start: String, // YYYY-MM
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end: Option<String>, // None = current role
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum VerificationMethod {
+    Email,
+    Document,
+    Blockchain,
+}
+
+impl ProfessionalProof {
+    pub fn new(
+        issued_by: PublicKey,
+        subject: PublicKey,
+        role: String,
+        company: String,
+        period: EmploymentPeriod,
+        verification_method: VerificationMethod,
+    ) -> Self {
+        Self {
+            proof_type: "employment-proof".to_string(),
+            version: 1,
+            issued_by,
+            subject,
+            role,
+            company,
+            period,
+            verification_method,
+            reference_url: None,
+            extra: HashMap::new(),
+            claim_event_id: None,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        if self.proof_type != "employment-proof" {
+            return Err(ValidationError::InvalidConfiguration(
+                "proof type must be \"employment-proof\"",
+            ));
+        }
+        if self.role.is_empty() {
+            return Err(ValidationError::MissingField("role"));
+        }
+        if self.company.is_empty() {
+            return Err(ValidationError::MissingField("company"));
+        }
+        if !is_year_month(&self.period.start) {
+            return Err(ValidationError::InvalidDateFormat("period.start"));
+        }
+        if let Some(end) = &self.period.end {
+            if !is_year_month(end) {
+                return Err(ValidationError::InvalidDateFormat("period.end"));
+            }
+            if *end < self.period.start {
+                return Err(ValidationError::InvalidConfiguration("period ends before it starts"));
+            }
+        }
+        if let Some(url) = &self.reference_url
+            && !url.starts_with("https://")
+            && !url.starts_with("http://")
+        {
+            return Err(ValidationError::InvalidUrl("reference_url"));
+        }
+        Ok(())
+    }
+
+    /// `d` tag: one addressable proof per subject, company and start date
+    pub fn d_tag(&self) -> String {
+        format!(
+            "{}:{}:{}",
+            self.subject.to_hex(),
+            self.company.to_lowercase().replace(' ', "-"),
+            self.period.start
+        )
+    }
+
+    /// Convert to a kind 39994 event; the signer must be `issued_by`
+    pub fn to_nostr_event(&self, keys: &Keys) -> Result<Event> {
+        Ok(self.unsigned_event(keys.public_key())?.sign_with_keys(keys)?)
+    }
+
+    /// Parse a kind 39994 event
+    pub fn from_nostr_event(event: &Event) -> Result<Self> {
+        check_kind(event, KIND_PROFESSIONAL_PROOF)?;
+        let mut proof: ProfessionalProof = serde_json::from_str(&event.content)?;
+        proof.claim_event_id = event.tags.event_ids().next().copied();
+        proof.validate()?;
+        if proof.issued_by != event.pubkey {
+            return Err(anyhow!("Proof issuer does not match event author"));
+        }
+        Ok(proof)
+    }
+}
+
+impl SignEvent for ProfessionalProof {
+    fn unsigned_event(&self, author: PublicKey) -> Result<UnsignedEvent> {
+        self.validate()?;
+        if author != self.issued_by {
+            return Err(anyhow!("Proof must be signed by its issuer"));
+        }
+
+        let mut tags = vec![
+            Tag::identifier(self.d_tag()),
+            Tag::public_key(self.subject),
+            Tag::custom(TagKind::Custom("org".into()), vec![&self.company]),
+        ];
+        if let Some(id) = self.claim_event_id {
+            tags.push(Tag::event(id));
+        }
+
+        // Self-issued claims still carry their subject's "p" tag
+        Ok(EventBuilder::new(Kind::from(KIND_PROFESSIONAL_PROOF), serde_json::to_string(self)?)
+            .tags(tags)
+            .allow_self_tagging()
+            .build(author))
+    }
+}
+
+// ==================== 39996: Employer Verification ====================
+
+/// Organisational assertion about a claim (kind 39996, type "verification")
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EmployerVerification {
+    #[serde(rename = "type")]
+    pub verification_type: String, // "verification"
+    pub version: u32,
+
+    pub subject: PublicKey,
+    pub claim: EventId,
+    pub status: VerificationStatus,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verifier_role: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+
+    // === Extensions ===
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+
+    // === From tags (not part of content) ===
+    #[serde(skip)]
+    pub org: String, // "org" tag
+
+    #[serde(skip)]
+    pub claim_address: Option<String>, // "a" tag, the proof's 39994:<pubkey>:<d>
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum VerificationStatus {
+    Verified,
+    Disputed,
+    Pending,
+}
+
+impl EmployerVerification {
+    pub fn new(subject: PublicKey, claim: EventId, status: VerificationStatus, org: String) -> Self {
+        Self {
+            verification_type: "verification".to_string(),
+            version: 1,
+            subject,
+            claim,
+            status,
+            verifier_role: None,
+            notes: None,
+            extra: HashMap::new(),
+            org,
+            claim_address: None,
+        }
+    }
+
+    /// Verification answering a published proof. It covers that exact
+    /// version: the claim id commits to the proof's `created_at`, so an
+    /// edited proof needs a new verification.
+    pub fn for_proof(proof_event: &Event, proof: &ProfessionalProof, status: VerificationStatus) -> Self {
+        let mut verification = Self::new(proof.subject, proof_event.id, status, proof.company.clone());
+        verification.claim_address = Some(proof_address(proof_event));
+        verification
+    }
+
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        if self.verification_type != "verification" {
+            return Err(ValidationError::InvalidConfiguration(
+                "verification type must be \"verification\"",
+            ));
+        }
+        if self.org.is_empty() {
+            return Err(ValidationError::MissingField("org"));
+        }
+        Ok(())
+    }
+
+    /// Convert to a kind 39996 event signed by the organisation.
+    /// The `d` tag is the claim id, so a new status replaces the previous one.
+    pub fn to_nostr_event(&self, keys: &Keys) -> Result<Event> {
+        Ok(self.unsigned_event(keys.public_key())?.sign_with_keys(keys)?)
+    }
+
+    /// Parse a kind 39996 event
+    pub fn from_nostr_event(event: &Event) -> Result<Self> {
+        check_kind(event, KIND_EMPLOYER_VERIFICATION)?;
+        let mut verification: EmployerVerification = serde_json::from_str(&event.content)?;
+        verification.org = org_tag(event).unwrap_or_default();
+        verification.claim_address = event.tags.iter().find_map(|t| match t.as_slice() {
+            [name, value, ..] if name == "a" => Some(value.to_string()),
+            _ => None,
+        });
+        verification.validate()?;
+        Ok(verification)
+    }
+}
+
+impl SignEvent for EmployerVerification {
+    fn unsigned_event(&self, author: PublicKey) -> Result<UnsignedEvent> {
+        self.validate()?;
+        let mut tags = vec![
+            Tag::identifier(self.claim.to_hex()),
+            Tag::event(self.claim),
+            Tag::public_key(self.subject),
+            Tag::custom(TagKind::Custom("org".into()), vec![&self.org]),
+        ];
+        if let Some(address) = &self.claim_address {
+            tags.push(Tag::custom(TagKind::Custom("a".into()), vec![address]));
+        }
+        Ok(EventBuilder::new(Kind::from(KIND_EMPLOYER_VERIFICATION), serde_json::to_string(self)?)
+            .tags(tags)
+            .build(author))
+    }
+}
+
+// ==================== Countersignature Check ====================
+
+/// Status the organisation `org_pubkey` gave a proof, from its latest valid
+/// kind 39996 event about it. `None` if the org never answered (or the proof
+/// itself is invalid).
+///
+/// A verification is bound to the proof's address (`39994:<pubkey>:<d>`)
+/// and to the exact version it answered: its claim id commits to the proof's
+/// `created_at`, so editing a proof drops the countersignature until the
+/// org verifies the new version.
+pub fn countersignature<'a, I>(
+    proof_event: &Event,
+    verifications: I,
+    org_pubkey: &PublicKey,
+) -> Option<VerificationStatus>
+where
+    I: IntoIterator<Item = &'a Event>,
+{
+    if proof_event.verify().is_err() {
+        return None;
+    }
+    let proof = ProfessionalProof::from_nostr_event(proof_event).ok()?;
+    let address = proof_address(proof_event);
+
+    verifications
+        .into_iter()
+        .filter(|e| e.pubkey == *org_pubkey && e.verify().is_ok())
+        .filter_map(|e| Some((e.created_at, EmployerVerification::from_nostr_event(e).ok()?)))
+        .filter(|(_, v)| {
+            v.claim == proof_event.id
+                && v.claim_address.as_ref() == Some(&address)
+                && v.subject == proof.subject
+                && v.org.eq_ignore_ascii_case(&proof.company)
+        })
+        .max_by_key(|(created_at, _)| *created_at)
+        .map(|(_, v)| v.status)
+}
+
+/// Whether `org_pubkey` currently vouches for the proof
+pub fn is_countersigned<'a, I>(proof_event: &Event, verifications: I, org_pubkey: &PublicKey) -> bool
+where
+    I: IntoIterator<Item = &'a Event>,
+{
+    countersignature(proof_event, verifications, org_pubkey) == Some(VerificationStatus::Verified)
+}
+
+fn check_kind(event: &Event, kind: u16) -> Result<()> {
+    if event.kind != Kind::from(kind) {
+        return Err(anyhow!("Invalid event kind: expected {}, got {}", kind, event.kind));
+    }
+    Ok(())
+}
+
+/// `39994:<pubkey>:<d>` of a proof event
+fn proof_address(event: &Event) -> String {
+    format!(
+        "{}:{}:{}",
+        KIND_PROFESSIONAL_PROOF,
+        event.pubkey.to_hex(),
+        event.tags.identifier().unwrap_or_default()
+    )
+}
+
+fn org_tag(event: &Event) -> Option<String> {
+    event.tags.iter().find_map(|t| match t.as_slice() {
+        [name, value, ..] if name == "org" => Some(value.to_string()),
+        _ => None,
+    })
+}
+
+/// YYYY-MM
+fn is_year_month(s: &str) -> bool {
+    chrono::NaiveDate::parse_from_str(&format!("{}-01", s), "%Y-%m-%d").is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn proof(issuer: &Keys, subject: &Keys) -> ProfessionalProof {
+        ProfessionalProof::new(
+            issuer.public_key(),
+            subject.public_key(),
+            "Backend Engineer".to_string(),
+            "Acme Corp".to_string(),
+            EmploymentPeriod { start: "2020-01".to_string(), end: Some("2024-05".to_string()) },
+            VerificationMethod::Email,
+        )
+    }
+
+    #[test]
+    fn test_proof_round_trip() {
+        let alice = Keys::generate();
+        let resume_id = EventId::all_zeros();
+        let mut proof = proof(&alice, &alice);
+        proof.claim_event_id = Some(resume_id);
+        proof.reference_url = Some("https://acme.com/verify/abc123".to_string());
+
+        let event = proof.to_nostr_event(&alice).unwrap();
+        assert!(event.tags.iter().any(|t| t.as_slice() == ["org", "Acme Corp"]));
+        assert!(event.tags.public_keys().any(|pk| *pk == alice.public_key()));
+        assert!(event.content.contains("\"verificationMethod\":\"email\""));
+        assert!(event.content.contains("\"issued_by\""));
+
+        assert_eq!(ProfessionalProof::from_nostr_event(&event).unwrap(), proof);
+    }
+
+    #[test]
+    fn test_proof_validation() {
+        let alice = Keys::generate();
+        let mut bad = proof(&alice, &alice);
+        bad.period.end = Some("2019-12".to_string());
+        assert!(bad.validate().is_err());
+
+        bad.period = EmploymentPeriod { start: "Jan 2020".to_string(), end: None };
+        assert!(bad.validate().is_err());
+
+        assert!(proof(&alice, &alice).to_nostr_event(&Keys::generate()).is_err());
+    }
+
+    #[test]
+    fn test_verification_round_trip() {
+        let org = Keys::generate();
+        let alice = Keys::generate();
+        let proof_event = proof(&alice, &alice).to_nostr_event(&alice).unwrap();
+        let proof = ProfessionalProof::from_nostr_event(&proof_event).unwrap();
+
+        let mut verification = EmployerVerification::for_proof(&proof_event, &proof, VerificationStatus::Verified);
+        verification.verifier_role = Some("HR Manager".to_string());
+        let event = verification.to_nostr_event(&org).unwrap();
+
+        assert!(event.tags.event_ids().any(|id| *id == proof_event.id));
+        assert_eq!(EmployerVerification::from_nostr_event(&event).unwrap(), verification);
+    }
+
+    #[test]
+    fn test_countersignature() {
+        let org = Keys::generate();
+        let impostor = Keys::generate();
+        let alice = Keys::generate();
+        let proof_event = proof(&alice, &alice).to_nostr_event(&alice).unwrap();
+        let proof = ProfessionalProof::from_nostr_event(&proof_event).unwrap();
+
+        let verified = EmployerVerification::for_proof(&proof_event, &proof, VerificationStatus::Verified);
+        let from_org = verified.to_nostr_event(&org).unwrap();
+        let from_impostor = verified.to_nostr_event(&impostor).unwrap();
+
+        assert!(is_countersigned(&proof_event, [&from_org], &org.public_key()));
+        assert!(!is_countersigned(&proof_event, [&from_impostor], &org.public_key()));
+        assert!(!is_countersigned(&proof_event, [], &org.public_key()));
+
+        // A later dispute overrides the earlier verification
+        let disputed = EmployerVerification::for_proof(&proof_event, &proof, VerificationStatus::Disputed);
+        let dispute = EventBuilder::new(Kind::from(KIND_EMPLOYER_VERIFICATION), serde_json::to_string(&disputed).unwrap())
+            .tags(from_org.tags.iter().cloned())
+            .custom_created_at(from_org.created_at + 60)
+            .sign_with_keys(&org)
+            .unwrap();
+        assert_eq!(
+            countersignature(&proof_event, [&from_org, &dispute], &org.public_key()),
+            Some(VerificationStatus::Disputed)
+        );
+
+        // Verification of a different claim doesn't count
+        let mut other = verified.clone();
+        other.claim = EventId::all_zeros();
+        let other_event = other.to_nostr_event(&org).unwrap();
+        assert!(!is_countersigned(&proof_event, [&other_event], &org.public_key()));
+
+        // Nor one without the proof's address
+        let mut unbound = verified.clone();
+        unbound.claim_address = None;
+        let unbound_event = unbound.to_nostr_event(&org).unwrap();
+        assert!(!is_countersigned(&proof_event, [&unbound_event], &org.public_key()));
+    }
+
+    #[test]
+    fn test_edited_proof_loses_its_countersignature() {
+        let org = Keys::generate();
+        let alice = Keys::generate();
+        let proof_event = proof(&alice, &alice).to_nostr_event(&alice).unwrap();
+        let proof = ProfessionalProof::from_nostr_event(&proof_event).unwrap();
+        let from_org = EmployerVerification::for_proof(&proof_event, &proof, VerificationStatus::Verified)
+            .to_nostr_event(&org)
+            .unwrap();
+        assert!(from_org.tags.iter().any(|t| t.as_slice() == ["a", proof_address(&proof_event).as_str()]));
+
+        // Same address, later version with a different role
+        let mut edited = proof.clone();
+        edited.role = "Staff Engineer".to_string();
+        let edited_event = edited
+            .unsigned_event(alice.public_key())
+            .unwrap()
+            .sign_with_keys(&alice)
+            .unwrap();
+        assert_eq!(proof_address(&edited_event), proof_address(&proof_event));
+        assert!(!is_countersigned(&edited_event, [&from_org], &org.public_key()));
+    }
+}
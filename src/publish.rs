@@ -0,0 +1,328 @@
+// src/publish.rs
+// Publishing with an acceptance policy: send to every write relay, retry
+// relays that failed for transient reasons with exponential backoff, and
+// report per-relay outcomes against a required quorum of acks
+
+use crate::relays::RelayHealth;
+use anyhow::Result;
+use nostr_sdk::pool::relay::Error as RelayError;
+use nostr_sdk::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::Duration;
+use tokio::task::JoinSet;
+
+/// Machine-readable prefix of a NIP-20 `OK false` message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RejectReason {
+    Duplicate,
+    Pow,
+    Blocked,
+    RateLimited,
+    Invalid,
+    Error,
+    Unsupported,
+    AuthRequired,
+    Restricted,
+    /// No recognised prefix
+    Other,
+}
+
+impl RejectReason {
+    pub fn parse(message: &str) -> Self {
+        match MachineReadablePrefix::parse(message) {
+            Some(MachineReadablePrefix::Duplicate) => RejectReason::Duplicate,
+            Some(MachineReadablePrefix::Pow) => RejectReason::Pow,
+            Some(MachineReadablePrefix::Blocked) => RejectReason::Blocked,
+            Some(MachineReadablePrefix::RateLimited) => RejectReason::RateLimited,
+            Some(MachineReadablePrefix::Invalid) => RejectReason::Invalid,
+            Some(MachineReadablePrefix::Error) => RejectReason::Error,
+            Some(MachineReadablePrefix::Unsupported) => RejectReason::Unsupported,
+            Some(MachineReadablePrefix::AuthRequired) => RejectReason::AuthRequired,
+            Some(MachineReadablePrefix::Restricted) => RejectReason::Restricted,
+            None => RejectReason::Other,
+        }
+    }
+}
+
+/// Why a relay did not accept an event
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum PublishError {
+    /// The relay answered `OK false`
+    Rejected { reason: RejectReason, message: String },
+    /// Could not connect, or no `OK` before the timeout
+    Unreachable { message: String },
+}
+
+impl PublishError {
+    fn from_relay(error: RelayError) -> Self {
+        match error {
+            RelayError::RelayMessage(message) => PublishError::Rejected {
+                reason: RejectReason::parse(&message),
+                message,
+            },
+            other => PublishError::Unreachable { message: other.to_string() },
+        }
+    }
+
+    /// Worth trying again later: the relay was down, busy or had an internal error
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            PublishError::Unreachable { .. } => true,
+            PublishError::Rejected { reason, .. } => {
+                matches!(reason, RejectReason::RateLimited | RejectReason::Error)
+            }
+        }
+    }
+}
+
+impl fmt::Display for PublishError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PublishError::Rejected { message, .. } => write!(f, "Rejected: {}", message),
+            PublishError::Unreachable { message } => write!(f, "Unreachable: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for PublishError {}
+
+/// Final outcome for one relay
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RelayOutcome {
+    pub relay: RelayUrl,
+    pub accepted: bool,
+    pub attempts: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<PublishError>,
+}
+
+/// Machine-readable result of a publish
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PublishReport {
+    pub event_id: EventId,
+    pub quorum: usize,
+    pub accepted: usize,
+    pub quorum_met: bool,
+    pub relays: Vec<RelayOutcome>,
+}
+
+impl PublishReport {
+    /// Fail unless enough relays accepted the event
+    pub fn ensure_quorum(&self) -> Result<(), QuorumNotMet> {
+        if self.quorum_met {
+            return Ok(());
+        }
+        Err(QuorumNotMet {
+            required: self.quorum,
+            accepted: self.accepted,
+        })
+    }
+}
+
+/// Fewer relays accepted the event than the publisher required
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuorumNotMet {
+    pub required: usize,
+    pub accepted: usize,
+}
+
+impl fmt::Display for QuorumNotMet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Quorum not met: {} of the required {} relay(s) accepted the event",
+            self.accepted, self.required
+        )
+    }
+}
+
+impl std::error::Error for QuorumNotMet {}
+
+/// Sends an event to every relay in a client, retrying transient failures
+#[derive(Debug, Clone)]
+pub struct Publisher {
+    quorum: usize,
+    max_attempts: u32,
+    backoff: Duration,
+    timeout: Duration,
+}
+
+impl Publisher {
+    /// Require at least `quorum` relays to accept
+    pub fn new(quorum: usize) -> Self {
+        Self {
+            quorum,
+            max_attempts: 3,
+            backoff: Duration::from_secs(1),
+            timeout: Duration::from_secs(10),
+        }
+    }
+
+    /// Tries per relay, including the first
+    pub fn max_attempts(mut self, attempts: u32) -> Self {
+        self.max_attempts = attempts.max(1);
+        self
+    }
+
+    /// Wait before the first retry; doubles after each round
+    pub fn backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Connect timeout per attempt
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Publish to every relay added to `client` (connected or not)
+    pub async fn publish(&self, client: &Client, event: &Event) -> PublishReport {
+        let mut pending: Vec<(RelayUrl, Relay)> = client.relays().await.into_iter().collect();
+        pending.sort_by(|a, b| a.0.cmp(&b.0));
+        let mut outcomes: Vec<RelayOutcome> = Vec::new();
+        let mut delay = self.backoff;
+
+        for attempt in 1..=self.max_attempts {
+            let mut round = JoinSet::new();
+            for (url, relay) in pending.drain(..) {
+                let (event, timeout) = (event.clone(), self.timeout);
+                round.spawn(async move {
+                    let result = match relay.try_connect(timeout).await {
+                        Ok(()) => relay.send_event(&event).await.map(|_| ()),
+                        Err(e) => Err(e),
+                    };
+                    (url, relay, result.map_err(PublishError::from_relay))
+                });
+            }
+
+            while let Some(joined) = round.join_next().await {
+                let Ok((url, relay, result)) = joined else {
+                    continue;
+                };
+                match result {
+                    // Already stored counts as stored
+                    Ok(()) | Err(PublishError::Rejected { reason: RejectReason::Duplicate, .. }) => {
+                        outcomes.push(RelayOutcome { relay: url, accepted: true, attempts: attempt, error: None })
+                    }
+                    Err(e) if e.is_retryable() && attempt < self.max_attempts => pending.push((url, relay)),
+                    Err(e) => outcomes.push(RelayOutcome {
+                        relay: url,
+                        accepted: false,
+                        attempts: attempt,
+                        error: Some(e),
+                    }),
+                }
+            }
+
+            if pending.is_empty() {
+                break;
+            }
+            tokio::time::sleep(delay).await;
+            delay *= 2;
+        }
+
+        outcomes.sort_by(|a, b| a.relay.cmp(&b.relay));
+        let accepted = outcomes.iter().filter(|o| o.accepted).count();
+        PublishReport {
+            event_id: event.id,
+            quorum: self.quorum,
+            accepted,
+            quorum_met: accepted >= self.quorum,
+            relays: outcomes,
+        }
+    }
+}
+
+impl RelayHealth {
+    /// Record each relay's final publish outcome
+    pub fn record_report(&mut self, report: &PublishReport) {
+        for outcome in &report.relays {
+            match &outcome.error {
+                None => self.record_success(&outcome.relay),
+                Some(e) => self.record_failure(&outcome.relay, &e.to_string()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_relay::TestRelay;
+
+    fn publisher(quorum: usize) -> Publisher {
+        Publisher::new(quorum)
+            .backoff(Duration::from_millis(10))
+            .timeout(Duration::from_secs(2))
+    }
+
+    #[test]
+    fn test_reject_reasons() {
+        assert_eq!(RejectReason::parse("rate-limited: slow down"), RejectReason::RateLimited);
+        assert_eq!(RejectReason::parse("blocked: you are banned"), RejectReason::Blocked);
+        assert_eq!(RejectReason::parse("nope"), RejectReason::Other);
+
+        let blocked = PublishError::from_relay(RelayError::RelayMessage("blocked: spam".to_string()));
+        assert!(!blocked.is_retryable());
+        let busy = PublishError::from_relay(RelayError::RelayMessage("error: db locked".to_string()));
+        assert!(busy.is_retryable());
+        assert!(PublishError::Unreachable { message: "timeout".to_string() }.is_retryable());
+    }
+
+    #[tokio::test]
+    async fn test_retries_until_quorum() {
+        let (steady, flaky, hostile) = (TestRelay::start().await, TestRelay::start().await, TestRelay::start().await);
+        flaky.reject_next("rate-limited: slow down", 1);
+        hostile.reject_next("blocked: not on the allow list", usize::MAX);
+
+        let client = Client::default();
+        for relay in [&steady, &flaky, &hostile] {
+            client.add_relay(relay.url()).await.unwrap();
+        }
+        let event = EventBuilder::text_note("hiring").sign_with_keys(&Keys::generate()).unwrap();
+        let report = publisher(2).publish(&client, &event).await;
+
+        assert!(report.quorum_met);
+        assert_eq!(report.accepted, 2);
+        let outcome = |url: RelayUrl| report.relays.iter().find(|o| o.relay == url).unwrap();
+        assert_eq!((outcome(steady.url()).accepted, outcome(steady.url()).attempts), (true, 1));
+        assert_eq!((outcome(flaky.url()).accepted, outcome(flaky.url()).attempts), (true, 2));
+        assert!(flaky.events().iter().any(|e| e.id == event.id));
+
+        // Permanent rejections are not retried
+        let rejected = outcome(hostile.url());
+        assert_eq!(rejected.attempts, 1);
+        assert!(matches!(
+            &rejected.error,
+            Some(PublishError::Rejected { reason: RejectReason::Blocked, .. })
+        ));
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["quorum_met"], true);
+        let mut health = RelayHealth::default();
+        health.record_report(&report);
+        assert_eq!(health.stats(&hostile.url()).unwrap().failures, 1);
+    }
+
+    #[tokio::test]
+    async fn test_quorum_not_met() {
+        let relay = TestRelay::start().await;
+        let down = RelayUrl::parse("ws://127.0.0.1:1").unwrap();
+        let client = Client::default();
+        client.add_relay(relay.url()).await.unwrap();
+        client.add_relay(&down).await.unwrap();
+
+        let event = EventBuilder::text_note("hiring").sign_with_keys(&Keys::generate()).unwrap();
+        let report = publisher(2).max_attempts(2).publish(&client, &event).await;
+
+        assert_eq!(report.accepted, 1);
+        let unreachable = report.relays.iter().find(|o| o.relay == down).unwrap();
+        assert_eq!(unreachable.attempts, 2);
+        assert!(matches!(unreachable.error, Some(PublishError::Unreachable { .. })));
+        assert_eq!(report.ensure_quorum(), Err(QuorumNotMet { required: 2, accepted: 1 }));
+    }
+}
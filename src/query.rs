@@ -0,0 +1,475 @@
+// src/query.rs
+// Client-side post-filtering for fields relays cannot index
+// A JobQuery produces both the relay Filter and the local predicate
+
+use crate::events::{index_value, JobsFilter};
+use crate::payment::PaymentPolicy;
+use crate::types::*;
+use chrono::{DateTime, NaiveDate, Utc};
+use nostr_sdk::prelude::Filter;
+
+/// Combined relay + local job query.
+///
+/// Fields relays can index (company, employment type, skill) go into the
+/// relay `Filter` and are re-checked locally; everything else (salary,
+/// worker type, location, expiry, capabilities, AI thresholds, payment)
+/// is only checked by `matches` on parsed listings.
+#[derive(Debug, Clone, Default)]
+pub struct JobQuery {
+    filter: JobsFilter,
+
+    companies: Vec<String>,
+    employment_types: Vec<EmploymentType>,
+    skills: Vec<String>,
+
+    salary_min: Option<(f64, String)>,
+    salary_max: Option<(f64, String)>,
+    currency: Option<String>,
+    worker_type: Option<EligibleWorkerType>,
+    location_type: Option<JobLocationType>,
+    country: Option<String>,
+    valid_on: Option<NaiveDate>,
+    capabilities: Vec<(String, CapabilityLevel)>,
+
+    response_time: Option<(f64, String)>,
+    accuracy: Option<f64>,
+    throughput: Option<(f64, String)>,
+
+    paid_min: Option<(u64, PaymentPolicy)>,
+}
+
+impl JobQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // === Relay-indexed fields ===
+    pub fn company(mut self, name: &str) -> Self {
+        self.filter = self.filter.company(name);
+        self.companies.push(index_value(name));
+        self
+    }
+
+    pub fn employment_type(mut self, emp_type: EmploymentType) -> Self {
+        self.filter = self.filter.employment_type(emp_type.clone());
+        self.employment_types.push(emp_type);
+        self
+    }
+
+    pub fn skill(mut self, skill: &str) -> Self {
+        self.filter = self.filter.skill(skill);
+        self.skills.push(index_value(skill));
+        self
+    }
+
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.filter = self.filter.limit(limit);
+        self
+    }
+
+    // === Local-only fields ===
+    /// Listing must pay at least `amount` per `unit` at the top of its range
+    pub fn salary_min(mut self, amount: f64, unit: &str) -> Self {
+        self.salary_min = Some((amount, unit.to_string()));
+        self
+    }
+
+    /// Listing must start at or below `amount` per `unit`
+    pub fn salary_max(mut self, amount: f64, unit: &str) -> Self {
+        self.salary_max = Some((amount, unit.to_string()));
+        self
+    }
+
+    /// Restrict salary comparisons to one currency (no FX conversion)
+    pub fn currency(mut self, currency: &str) -> Self {
+        self.currency = Some(currency.to_uppercase());
+        self
+    }
+
+    pub fn worker_type(mut self, worker_type: EligibleWorkerType) -> Self {
+        self.worker_type = Some(worker_type);
+        self
+    }
+
+    pub fn location_type(mut self, location_type: JobLocationType) -> Self {
+        self.location_type = Some(location_type);
+        self
+    }
+
+    /// ISO-3166 country, matched against job locations and applicant requirements
+    pub fn country(mut self, country: &str) -> Self {
+        self.country = Some(country.to_uppercase());
+        self
+    }
+
+    /// Exclude listings whose `valid_through` is before today (UTC)
+    pub fn not_expired(self) -> Self {
+        self.valid_on(Utc::now().date_naive())
+    }
+
+    /// Exclude listings whose `valid_through` is before `date`
+    pub fn valid_on(mut self, date: NaiveDate) -> Self {
+        self.valid_on = Some(date);
+        self
+    }
+
+    /// Listing must require `name` at `level` or above
+    pub fn capability(mut self, name: &str, level: CapabilityLevel) -> Self {
+        self.capabilities.push((name.to_lowercase(), level));
+        self
+    }
+
+    /// The agent's response time: matches listings whose responseTimeMax is
+    /// at least `value` `unit` (or that set none)
+    pub fn agent_response_time(mut self, value: f64, unit: &str) -> Self {
+        self.response_time = Some((value, unit.to_string()));
+        self
+    }
+
+    /// The agent's accuracy in percent: matches listings whose accuracyMin is
+    /// at most `value` (or that set none)
+    pub fn agent_accuracy(mut self, value: f64) -> Self {
+        self.accuracy = Some(value);
+        self
+    }
+
+    /// The agent's sustained throughput: matches listings whose throughputMin
+    /// is at most `value` `unit` (or that set none)
+    pub fn agent_throughput(mut self, value: f64, unit: &str) -> Self {
+        self.throughput = Some((value, unit.to_string()));
+        self
+    }
+
+    /// Listing carries a payment proof that verifies offline under `policy`
+    /// for at least `sats`. One listing alone can't show its payment wasn't
+    /// copied from another author's; `JobRanker` credits each payment once
+    pub fn paid_min(mut self, sats: u64, policy: &PaymentPolicy) -> Self {
+        self.paid_min = Some((sats, policy.clone()));
+        self
+    }
+
+    // === Output ===
+    /// Relay-side filter for the indexed fields
+    pub fn relay_filter(&self) -> Filter {
+        self.filter.clone().build()
+    }
+
+    /// Local predicate over a parsed listing
+    pub fn matches(&self, job: &JobListing) -> bool {
+        self.matches_indexed(job)
+            && self.matches_salary(job)
+            && self.matches_worker_type(job)
+            && self.matches_location_type(job)
+            && self.matches_country(job)
+            && self.matches_expiry(job)
+            && self.matches_capabilities(job)
+            && self.matches_performance(job)
+            && self.matches_payment(job)
+    }
+
+    /// Keep only the listings that match
+    pub fn apply<'a>(&self, jobs: &'a [JobListing]) -> Vec<&'a JobListing> {
+        jobs.iter().filter(|job| self.matches(job)).collect()
+    }
+
+    // Same OR-within-field semantics as the relay filter
+    fn matches_indexed(&self, job: &JobListing) -> bool {
+        let company_ok = self.companies.is_empty()
+            || self.companies.contains(&index_value(&job.hiring_organization.name));
+        let type_ok = self.employment_types.is_empty()
+            || job.employment_type.iter().any(|t| self.employment_types.contains(t));
+        let skill_ok = self.skills.is_empty()
+            || job.skills.iter().flatten().any(|s| self.skills.contains(&index_value(s)));
+        company_ok && type_ok && skill_ok
+    }
+
+    fn matches_salary(&self, job: &JobListing) -> bool {
+        if self.salary_min.is_none() && self.salary_max.is_none() && self.currency.is_none() {
+            return true;
+        }
+        let Some(salary) = &job.base_salary else { return false };
+        if let Some(currency) = &self.currency
+            && !salary.currency.eq_ignore_ascii_case(currency)
+        {
+            return false;
+        }
+        let Some((low, high, unit)) = salary_range(salary) else { return false };
+
+        if let Some((amount, wanted_unit)) = &self.salary_min {
+            match convert_pay(high, unit, wanted_unit) {
+                Some(high) if high >= *amount => {}
+                _ => return false,
+            }
+        }
+        if let Some((amount, wanted_unit)) = &self.salary_max {
+            match convert_pay(low, unit, wanted_unit) {
+                Some(low) if low <= *amount => {}
+                _ => return false,
+            }
+        }
+        true
+    }
+
+    fn matches_worker_type(&self, job: &JobListing) -> bool {
+        self.worker_type
+            .as_ref()
+            .is_none_or(|w| job.eligible_worker_type.contains(w))
+    }
+
+    fn matches_location_type(&self, job: &JobListing) -> bool {
+        self.location_type.as_ref().is_none_or(|wanted| {
+            job.job_location_type.iter().flatten().any(|t| t == wanted)
+        })
+    }
+
+    fn matches_country(&self, job: &JobListing) -> bool {
+        let Some(country) = &self.country else { return true };
+        let in_locations = job
+            .job_location
+            .iter()
+            .filter_map(|l| l.address.as_ref()?.address_country.as_ref())
+            .any(|c| c.eq_ignore_ascii_case(country));
+        let in_requirements = job
+            .applicant_location_requirements
+            .iter()
+            .flatten()
+            .any(|r| r.name.eq_ignore_ascii_case(country));
+        in_locations || in_requirements
+    }
+
+    fn matches_expiry(&self, job: &JobListing) -> bool {
+        let Some(date) = self.valid_on else { return true };
+        match job.valid_through.as_deref().and_then(parse_date) {
+            Some(expiry) => expiry >= date,
+            None => true, // No (or unreadable) expiry never hides a listing
+        }
+    }
+
+    fn matches_capabilities(&self, job: &JobListing) -> bool {
+        self.capabilities.iter().all(|(name, level)| {
+            job.required_capabilities
+                .iter()
+                .flatten()
+                .any(|c| c.name.to_lowercase() == *name && c.level >= *level)
+        })
+    }
+
+    // Listings that leave a threshold unset place no constraint on it
+    fn matches_performance(&self, job: &JobListing) -> bool {
+        let response_ok = match (&self.response_time, &job.response_time_max) {
+            (Some((value, unit)), Some(max)) => {
+                match (seconds(*value, unit), seconds(max.value, &max.unit)) {
+                    (Some(agent), Some(limit)) => agent <= limit,
+                    _ => false,
+                }
+            }
+            _ => true,
+        };
+        let accuracy_ok = match (self.accuracy, &job.accuracy_min) {
+            (Some(agent), Some(min)) => agent >= min.value,
+            _ => true,
+        };
+        let throughput_ok = match (&self.throughput, &job.throughput_min) {
+            (Some((value, unit)), Some(min)) => {
+                unit.eq_ignore_ascii_case(&min.unit) && *value >= min.value
+            }
+            _ => true,
+        };
+        response_ok && accuracy_ok && throughput_ok
+    }
+
+    // The self-reported amount only counts once the proof verifies
+    fn matches_payment(&self, job: &JobListing) -> bool {
+        let Some((min, policy)) = &self.paid_min else { return true };
+        job.verified_payment_sats(policy).is_some_and(|sats| sats >= *min)
+    }
+}
+
+impl From<JobQuery> for Filter {
+    fn from(query: JobQuery) -> Self {
+        query.filter.build()
+    }
+}
+
+// ==================== Unit Normalisation ====================
+
+/// Periods per year for schema.org `unitText` pay periods
+fn periods_per_year(unit: &str) -> Option<f64> {
+    match unit.to_uppercase().as_str() {
+        "HOUR" => Some(2080.0),
+        "DAY" => Some(260.0),
+        "WEEK" => Some(52.0),
+        "MONTH" => Some(12.0),
+        "YEAR" => Some(1.0),
+        _ => None,
+    }
+}
+
+/// Convert a pay amount between periods.
+/// Non-calendar units (e.g. "TASK") only compare with themselves.
+pub fn convert_pay(value: f64, from_unit: &str, to_unit: &str) -> Option<f64> {
+    if from_unit.eq_ignore_ascii_case(to_unit) {
+        return Some(value);
+    }
+    let from = periods_per_year(from_unit)?;
+    let to = periods_per_year(to_unit)?;
+    Some(value * from / to)
+}
+
+fn seconds(value: f64, unit: &str) -> Option<f64> {
+    let factor = match unit.to_uppercase().as_str() {
+        "MILLISECOND" | "MS" => 0.001,
+        "SECOND" | "S" => 1.0,
+        "MINUTE" => 60.0,
+        "HOUR" => 3600.0,
+        _ => return None,
+    };
+    Some(value * factor)
+}
+
+/// (low, high, unit) of a salary, whichever shape it was published in
+pub(crate) fn salary_range(salary: &BaseSalary) -> Option<(f64, f64, &str)> {
+    match &salary.value {
+        MonetaryValue::Range(range) => {
+            let low = range.min_value.or(range.max_value)?;
+            let high = range.max_value.unwrap_or(low);
+            Some((low, high, range.unit_text.as_str()))
+        }
+        MonetaryValue::Single(dist) => {
+            let low = dist.percentile10.or(dist.percentile25).or(dist.median)?;
+            let high = dist.percentile90.or(dist.percentile75).or(dist.median).unwrap_or(low);
+            Some((low, high, dist.duration.as_str()))
+        }
+    }
+}
+
+pub(crate) fn parse_date(s: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .ok()
+        .or_else(|| DateTime::parse_from_rfc3339(s).ok().map(|d| d.date_naive()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{job_builder, task_builder};
+    use nostr_sdk::prelude::*;
+
+    fn human_job() -> JobListing {
+        let mut job = job_builder("job-001", "Senior Rust Developer")
+            .employment_type(vec![EmploymentType::FullTime])
+            .remote()
+            .salary(120000.0, 180000.0, "USD".to_string(), "YEAR".to_string())
+            .skills(vec!["Rust".to_string(), "Nostr".to_string()])
+            .valid_through("2025-12-31".to_string())
+            .build()
+            .unwrap();
+        job.job_location.push(JobLocation {
+            schema_type: "Place".to_string(),
+            address: Some(PostalAddress {
+                schema_type: "PostalAddress".to_string(),
+                street_address: None,
+                address_locality: None,
+                address_region: Some("CA".to_string()),
+                postal_code: None,
+                address_country: Some("US".to_string()),
+            }),
+        });
+        job
+    }
+
+    fn agent_job() -> JobListing {
+        task_builder()
+            .for_ai_agents()
+            .employment_type(vec![EmploymentType::TaskBased])
+            .salary(0.05, 0.10, "USD".to_string(), "TASK".to_string())
+            .response_time(5.0, "SECOND".to_string())
+            .accuracy(95.0)
+            .capabilities(vec![CapabilityRequirement {
+                name: "Image Classification".to_string(),
+                level: CapabilityLevel::Advanced,
+            }])
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_salary_unit_normalisation() {
+        let job = human_job();
+        // 180k/year top of range is ~86.5/hour
+        assert!(JobQuery::new().salary_min(80.0, "HOUR").matches(&job));
+        assert!(!JobQuery::new().salary_min(90.0, "HOUR").matches(&job));
+        assert!(JobQuery::new().salary_min(14000.0, "MONTH").matches(&job));
+        assert!(JobQuery::new().salary_max(10000.0, "MONTH").matches(&job));
+        assert!(!JobQuery::new().salary_max(100000.0, "YEAR").matches(&job));
+        assert!(!JobQuery::new().currency("EUR").matches(&job));
+
+        // Per-task pay never converts to calendar periods
+        assert!(!JobQuery::new().salary_min(1.0, "HOUR").matches(&agent_job()));
+        assert!(JobQuery::new().salary_min(0.08, "TASK").matches(&agent_job()));
+    }
+
+    #[test]
+    fn test_worker_location_and_country() {
+        let human = human_job();
+        let agent = agent_job();
+
+        let query = JobQuery::new().worker_type(EligibleWorkerType::AIAgent);
+        assert_eq!(query.apply(&[human.clone(), agent.clone()]).len(), 1);
+
+        assert!(JobQuery::new().location_type(JobLocationType::Telecommute).matches(&human));
+        assert!(!JobQuery::new().location_type(JobLocationType::OnSite).matches(&human));
+        assert!(JobQuery::new().country("us").matches(&human));
+        assert!(!JobQuery::new().country("GB").matches(&human));
+    }
+
+    #[test]
+    fn test_expiry() {
+        let job = human_job();
+        let before = NaiveDate::from_ymd_opt(2025, 6, 1).unwrap();
+        let after = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+
+        assert!(JobQuery::new().valid_on(before).matches(&job));
+        assert!(!JobQuery::new().valid_on(after).matches(&job));
+        assert!(JobQuery::new().valid_on(after).matches(&agent_job()));
+    }
+
+    #[test]
+    fn test_capability_level() {
+        let job = agent_job();
+        assert!(JobQuery::new().capability("image classification", CapabilityLevel::Intermediate).matches(&job));
+        assert!(JobQuery::new().capability("Image Classification", CapabilityLevel::Advanced).matches(&job));
+        assert!(!JobQuery::new().capability("Image Classification", CapabilityLevel::Expert).matches(&job));
+        assert!(!JobQuery::new().capability("OCR", CapabilityLevel::Basic).matches(&job));
+    }
+
+    #[test]
+    fn test_ai_performance_thresholds() {
+        let job = agent_job();
+        assert!(JobQuery::new().agent_response_time(800.0, "MILLISECOND").matches(&job));
+        assert!(!JobQuery::new().agent_response_time(1.0, "MINUTE").matches(&job));
+        assert!(JobQuery::new().agent_accuracy(97.0).matches(&job));
+        assert!(!JobQuery::new().agent_accuracy(90.0).matches(&job));
+
+        // Listings without thresholds are unconstrained
+        assert!(JobQuery::new().agent_accuracy(50.0).matches(&human_job()));
+    }
+
+    #[test]
+    fn test_relay_filter_and_local_predicate_agree() {
+        let keys = Keys::generate();
+        let job = human_job();
+        let event = job.to_nostr_event(&keys).unwrap();
+
+        let query = JobQuery::new()
+            .skill("rust")
+            .employment_type(EmploymentType::FullTime)
+            .salary_min(150000.0, "YEAR");
+        assert!(query.relay_filter().match_event(&event, MatchEventOptions::new()));
+        assert!(query.matches(&job));
+
+        let miss = JobQuery::new().company("Other Corp");
+        assert!(!miss.relay_filter().match_event(&event, MatchEventOptions::new()));
+        assert!(!miss.matches(&job));
+    }
+}
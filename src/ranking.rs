@@ -0,0 +1,425 @@
+// src/ranking.rs
+// Payment-aware feed ranking and spam scoring (see CLIENT_GUIDE.md)
+// Scorers rate a listing from 0.0 to 1.0; the ranker combines them by weight
+
+use crate::address::JobAddress;
+use crate::payment::{Bolt11Invoice, PaymentPolicy};
+use crate::pow::check_pow;
+use crate::types::JobListing;
+use nostr_sdk::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+/// Rates one aspect of a listing, from 0.0 (worst) to 1.0 (best)
+pub trait JobScorer {
+    fn name(&self) -> &'static str;
+    fn score(&self, job: &JobListing, event: &Event) -> f64;
+
+    /// Score inside `JobRanker::rank`, which also passes the payment it
+    /// credited to the listing (see `RankedJob::paid_sats`)
+    fn score_ranked(&self, job: &JobListing, event: &Event, _paid_sats: Option<u64>) -> f64 {
+        self.score(job, event)
+    }
+}
+
+/// Payment proof amount verified under `policy`, saturating at `full_score_sats`.
+/// Ranked, it scores the payment the ranker credited instead, so a payment
+/// copied onto several listings only counts for the first.
+pub struct PaymentScorer {
+    pub full_score_sats: u64,
+    pub policy: PaymentPolicy,
+}
+
+impl JobScorer for PaymentScorer {
+    fn name(&self) -> &'static str {
+        "payment"
+    }
+
+    fn score(&self, job: &JobListing, _event: &Event) -> f64 {
+        let sats = job.verified_payment_sats(&self.policy).unwrap_or(0);
+        ratio(sats as f64, self.full_score_sats as f64)
+    }
+
+    fn score_ranked(&self, _job: &JobListing, _event: &Event, paid_sats: Option<u64>) -> f64 {
+        ratio(paid_sats.unwrap_or(0) as f64, self.full_score_sats as f64)
+    }
+}
+
+/// Authors whose NIP-05 identifier has been verified (see `nip05::Nip05Verifier`)
+#[derive(Default)]
+pub struct Nip05Scorer {
+    verified: HashSet<PublicKey>,
+}
+
+impl Nip05Scorer {
+    pub fn new<I>(verified: I) -> Self
+    where
+        I: IntoIterator<Item = PublicKey>,
+    {
+        Self {
+            verified: verified.into_iter().collect(),
+        }
+    }
+}
+
+impl JobScorer for Nip05Scorer {
+    fn name(&self) -> &'static str {
+        "nip05"
+    }
+
+    fn score(&self, _job: &JobListing, event: &Event) -> f64 {
+        if self.verified.contains(&event.pubkey) { 1.0 } else { 0.0 }
+    }
+}
+
+/// NIP-13 difficulty the event commits to and meets, saturating at `target_difficulty`
+pub struct PowScorer {
+    pub target_difficulty: u8,
+}
+
+impl JobScorer for PowScorer {
+    fn name(&self) -> &'static str {
+        "pow"
+    }
+
+    fn score(&self, _job: &JobListing, event: &Event) -> f64 {
+        let bits = check_pow(event).effective_difficulty();
+        ratio(bits as f64, self.target_difficulty as f64)
+    }
+}
+
+/// How long an employer has been posting, from their earliest listing seen.
+/// Saturates at `full_score_days`; unknown employers score 0.
+pub struct EmployerHistoryScorer {
+    first_seen: HashMap<PublicKey, Timestamp>,
+    now: Timestamp,
+    pub full_score_days: u64,
+}
+
+impl EmployerHistoryScorer {
+    /// Build from past listings (any version, including withdrawn ones)
+    pub fn from_events<'a, I>(history: I, full_score_days: u64) -> Self
+    where
+        I: IntoIterator<Item = &'a Event>,
+    {
+        let mut first_seen: HashMap<PublicKey, Timestamp> = HashMap::new();
+        for event in history {
+            first_seen
+                .entry(event.pubkey)
+                .and_modify(|t| *t = (*t).min(event.created_at))
+                .or_insert(event.created_at);
+        }
+        Self {
+            first_seen,
+            now: Timestamp::now(),
+            full_score_days,
+        }
+    }
+}
+
+impl JobScorer for EmployerHistoryScorer {
+    fn name(&self) -> &'static str {
+        "employer-history"
+    }
+
+    fn score(&self, _job: &JobListing, event: &Event) -> f64 {
+        let Some(first) = self.first_seen.get(&event.pubkey) else { return 0.0 };
+        let days = self.now.as_secs().saturating_sub(first.as_secs()) / 86_400;
+        ratio(days as f64, self.full_score_days as f64)
+    }
+}
+
+/// Share of the recommended fields a listing fills in
+pub struct CompletenessScorer;
+
+impl JobScorer for CompletenessScorer {
+    fn name(&self) -> &'static str {
+        "completeness"
+    }
+
+    fn score(&self, job: &JobListing, _event: &Event) -> f64 {
+        let checks = [
+            job.base_salary.is_some(),
+            job.valid_through.is_some(),
+            job.skills.as_ref().is_some_and(|s| !s.is_empty()),
+            !job.employment_type.is_empty(),
+            job.job_location_type.is_some() || !job.job_location.is_empty(),
+            job.apply_url.as_ref().is_some_and(|u| !u.is_empty()) || job.apply_via_nostr == Some(true),
+            job.qualifications.is_some() || job.experience_requirements.is_some(),
+            job.responsibilities.is_some(),
+            job.hiring_organization.url.is_some(),
+            job.description.len() >= 200,
+        ];
+        checks.iter().filter(|c| **c).count() as f64 / checks.len() as f64
+    }
+}
+
+fn ratio(value: f64, full: f64) -> f64 {
+    if full <= 0.0 { 1.0 } else { (value / full).clamp(0.0, 1.0) }
+}
+
+/// A listing with its combined score
+#[derive(Debug, Clone)]
+pub struct RankedJob<'a> {
+    pub job: &'a JobListing,
+    pub event: &'a Event,
+    pub score: f64,
+    pub paid_sats: Option<u64>, // Verified and credited payment, for "paid" badges
+    pub breakdown: Vec<(&'static str, f64)>,
+}
+
+/// Sorts listings by the weighted average of its scorers.
+/// Payment preferences (`min_payment_sats`, `show_unpaid`) hide listings
+/// locally; nothing is ever rejected at the relay. Each payment (by payment
+/// hash) is credited to one listing address only: the earliest one seen.
+pub struct JobRanker {
+    scorers: Vec<(Box<dyn JobScorer>, f64)>,
+    payment_policy: PaymentPolicy,
+    min_payment_sats: Option<u64>,
+    show_unpaid: bool,
+}
+
+impl Default for JobRanker {
+    /// The default scorers with no accepted payees, so no payment counts
+    fn default() -> Self {
+        Self::with_payment_policy(PaymentPolicy::default())
+    }
+}
+
+impl JobRanker {
+    /// No scorers: every listing scores 0, so results come newest first
+    pub fn new() -> Self {
+        Self {
+            scorers: Vec::new(),
+            payment_policy: PaymentPolicy::default(),
+            min_payment_sats: None,
+            show_unpaid: true,
+        }
+    }
+
+    /// Payment (verified under `policy`), proof-of-work and completeness;
+    /// add NIP-05 and employer history scorers once that data has been fetched
+    pub fn with_payment_policy(policy: PaymentPolicy) -> Self {
+        Self::new()
+            .payment_policy(policy.clone())
+            .scorer(PaymentScorer { full_score_sats: 10_000, policy }, 3.0)
+            .scorer(PowScorer { target_difficulty: 20 }, 1.0)
+            .scorer(CompletenessScorer, 1.0)
+    }
+
+    /// Which payment proofs count for `min_payment_sats` and `show_unpaid`
+    pub fn payment_policy(mut self, policy: PaymentPolicy) -> Self {
+        self.payment_policy = policy;
+        self
+    }
+
+    pub fn scorer<S: JobScorer + 'static>(mut self, scorer: S, weight: f64) -> Self {
+        self.scorers.push((Box::new(scorer), weight));
+        self
+    }
+
+    /// Hide listings without a verified payment of at least `sats`
+    pub fn min_payment_sats(mut self, sats: u64) -> Self {
+        self.min_payment_sats = Some(sats);
+        self
+    }
+
+    /// Whether listings without a verified payment are shown (default: true)
+    pub fn show_unpaid(mut self, show: bool) -> Self {
+        self.show_unpaid = show;
+        self
+    }
+
+    /// Score, filter and sort best first (ties: newest first)
+    pub fn rank<'a, I>(&self, jobs: I) -> Vec<RankedJob<'a>>
+    where
+        I: IntoIterator<Item = (&'a JobListing, &'a Event)>,
+    {
+        let jobs: Vec<(&'a JobListing, &'a Event)> = jobs.into_iter().collect();
+        let claims = self.payment_claims(&jobs);
+        let total_weight: f64 = self.scorers.iter().map(|(_, w)| w).sum();
+        let mut ranked: Vec<RankedJob<'a>> = jobs
+            .into_iter()
+            .filter_map(|(job, event)| {
+                let paid_sats = self
+                    .verified_payment(job)
+                    .filter(|(hash, _)| claims.get(hash) == Some(&job.address(event.pubkey)))
+                    .map(|(_, sats)| sats);
+                let paid_enough = match (self.min_payment_sats, paid_sats) {
+                    (Some(min), Some(sats)) => sats >= min,
+                    (Some(_), None) => false,
+                    (None, paid) => paid.is_some() || self.show_unpaid,
+                };
+                if !paid_enough {
+                    return None;
+                }
+
+                let breakdown: Vec<(&'static str, f64)> = self
+                    .scorers
+                    .iter()
+                    .map(|(scorer, _)| (scorer.name(), scorer.score_ranked(job, event, paid_sats)))
+                    .collect();
+                let score = if total_weight > 0.0 {
+                    breakdown
+                        .iter()
+                        .zip(&self.scorers)
+                        .map(|((_, s), (_, w))| s * w)
+                        .sum::<f64>()
+                        / total_weight
+                } else {
+                    0.0
+                };
+                Some(RankedJob { job, event, score, paid_sats, breakdown })
+            })
+            .collect();
+
+        ranked.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| b.event.created_at.cmp(&a.event.created_at))
+        });
+        ranked
+    }
+
+    /// Payment hash and sats of a listing's proof, if it verifies
+    fn verified_payment(&self, job: &JobListing) -> Option<([u8; 32], u64)> {
+        let sats = job.verified_payment_sats(&self.payment_policy)?;
+        let invoice = Bolt11Invoice::decode(job.payment_proof.as_ref()?.invoice.as_deref()?).ok()?;
+        Some((invoice.payment_hash, sats))
+    }
+
+    /// The listing address each payment hash is credited to: that of the
+    /// earliest event carrying it (ties: lowest id), so the same payment
+    /// copied onto other listings doesn't count for them
+    fn payment_claims(&self, jobs: &[(&JobListing, &Event)]) -> HashMap<[u8; 32], JobAddress> {
+        let mut earliest: HashMap<[u8; 32], (Timestamp, EventId, JobAddress)> = HashMap::new();
+        for (job, event) in jobs {
+            let Some((hash, _)) = self.verified_payment(job) else { continue };
+            let claim = (event.created_at, event.id, job.address(event.pubkey));
+            match earliest.get(&hash) {
+                Some((created_at, id, _)) if (*created_at, *id) <= (claim.0, claim.1) => {}
+                _ => {
+                    earliest.insert(hash, claim);
+                }
+            }
+        }
+        earliest.into_iter().map(|(hash, (_, _, address))| (hash, address)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::payment::tests::{invoice, policy, to_hex};
+    use crate::payment::PaymentProof;
+    use nostr_sdk::hashes::{sha256, Hash};
+    use crate::test_fixtures::sample_job;
+
+    /// Scores a fixed value per listing id, to test the ranker in isolation
+    struct Fixed(HashMap<String, f64>);
+
+    impl JobScorer for Fixed {
+        fn name(&self) -> &'static str {
+            "fixed"
+        }
+
+        fn score(&self, job: &JobListing, _event: &Event) -> f64 {
+            self.0.get(&job.identifier).copied().unwrap_or(0.0)
+        }
+    }
+
+    #[test]
+    fn test_weighted_rank_and_payment_preferences() {
+        let keys = Keys::generate();
+        let mut paid = sample_job("paid");
+        // Unverifiable proof: self-reported amounts don't count
+        paid.payment_proof = Some(PaymentProof {
+            amount_sats: 1_000_000,
+            invoice: Some("lnbc1invalid".to_string()),
+            preimage: None,
+            txid: None,
+        });
+        let jobs = [sample_job("a"), sample_job("b"), paid];
+        let events: Vec<Event> = jobs.iter().map(|j| j.to_nostr_event(&keys).unwrap()).collect();
+        let pairs = || jobs.iter().zip(&events);
+
+        let fixed = |values: &[(&str, f64)]| Fixed(values.iter().map(|(k, v)| (k.to_string(), *v)).collect());
+        let ranker = JobRanker::new()
+            .scorer(fixed(&[("a", 1.0), ("b", 0.0)]), 1.0)
+            .scorer(PaymentScorer { full_score_sats: 1000, policy: PaymentPolicy::default() }, 3.0);
+        let ranked = ranker.rank(pairs());
+        let order: Vec<&str> = ranked.iter().map(|r| r.job.identifier.as_str()).collect();
+        assert_eq!(order[0], "a");
+        assert!((ranked[0].score - 0.25).abs() < 1e-9);
+        assert_eq!(ranked[0].breakdown, vec![("fixed", 1.0), ("payment", 0.0)]);
+        assert!(ranked.iter().all(|r| r.paid_sats.is_none()));
+
+        assert!(JobRanker::default().show_unpaid(false).rank(pairs()).is_empty());
+        assert!(JobRanker::default().min_payment_sats(1).rank(pairs()).is_empty());
+    }
+
+    #[test]
+    fn test_copied_payment_counts_once() {
+        let preimage = [5u8; 32];
+        let hash = sha256::Hash::hash(&preimage).to_byte_array();
+        // One real payment, its invoice naming an identifier both listings use
+        let proof = PaymentProof::lightning(2000, invoice("lnbc20u", &hash, "Posting fee for job-001"), to_hex(&preimage));
+        let mut job = sample_job("job-001");
+        job.payment_proof = Some(proof);
+        let signed = |keys: &Keys, created_at: u64| {
+            job.event_builder()
+                .unwrap()
+                .custom_created_at(Timestamp::from(created_at))
+                .sign_with_keys(keys)
+                .unwrap()
+        };
+        let (payer, copier) = (Keys::generate(), Keys::generate());
+        let original = signed(&payer, 1_000);
+        let copy = signed(&copier, 2_000);
+
+        let ranker = JobRanker::with_payment_policy(policy()).min_payment_sats(1000);
+        for events in [[&original, &copy], [&copy, &original]] {
+            let ranked = ranker.rank(events.into_iter().map(|e| (&job, e)));
+            assert_eq!(ranked.len(), 1);
+            assert_eq!(ranked[0].event.pubkey, payer.public_key());
+            assert_eq!(ranked[0].paid_sats, Some(2000));
+        }
+
+        // Unfiltered, the copy ranks as unpaid
+        let ranked = JobRanker::with_payment_policy(policy()).rank([(&job, &copy), (&job, &original)]);
+        let copied = ranked.iter().find(|r| r.event.id == copy.id).unwrap();
+        assert_eq!(copied.paid_sats, None);
+        assert!(copied.breakdown.contains(&("payment", 0.0)));
+
+        // A newer version of the paying listing keeps the credit
+        let updated = signed(&payer, 3_000);
+        let ranked = ranker.rank([(&job, &copy), (&job, &updated), (&job, &original)]);
+        assert_eq!(ranked.len(), 2);
+        assert!(ranked.iter().all(|r| r.event.pubkey == payer.public_key()));
+    }
+
+    #[test]
+    fn test_builtin_scorers() {
+        let keys = Keys::generate();
+        let sparse = sample_job("sparse");
+        let event = sparse.to_nostr_event(&keys).unwrap();
+
+        let mut complete = sparse.clone();
+        complete.valid_through = Some("2025-03-01".to_string());
+        complete.skills = Some(vec!["Rust".to_string()]);
+        assert!(CompletenessScorer.score(&complete, &event) > CompletenessScorer.score(&sparse, &event));
+
+        assert_eq!(Nip05Scorer::new([keys.public_key()]).score(&sparse, &event), 1.0);
+        assert_eq!(Nip05Scorer::default().score(&sparse, &event), 0.0);
+
+        let old = EventBuilder::text_note("earlier")
+            .custom_created_at(Timestamp::now() - 90 * 86_400)
+            .sign_with_keys(&keys)
+            .unwrap();
+        let history = EmployerHistoryScorer::from_events([&old, &event], 180);
+        assert!((history.score(&sparse, &event) - 0.5).abs() < 0.01);
+        assert_eq!(history.score(&sparse, &sample_job("y").to_nostr_event(&Keys::generate()).unwrap()), 0.0);
+
+        let mined = EventBuilder::text_note("pow").pow(8).sign_with_keys(&keys).unwrap();
+        assert!(PowScorer { target_difficulty: 8 }.score(&sparse, &mined) >= 1.0);
+    }
+}
@@ -0,0 +1,224 @@
+// src/reconcile.rs
+// NIP-77 negentropy reconciliation of the local store against relays:
+// compare (id, created_at) fingerprints for the job event family (kinds
+// 39993-39997) with each relay, then fetch only the ids the store lacks.
+// Deletion requests aren't reconciled; `Syncer` follows those.
+
+use crate::store::{Ingested, JobStore, MIRRORED_KINDS};
+use anyhow::Result;
+use nostr_sdk::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::time::Duration;
+
+/// What reconciling with one relay changed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RelayReconciliation {
+    pub relay: RelayUrl,
+    /// New addresses stored
+    pub added: usize,
+    /// Newer versions of stored addresses
+    pub replaced: usize,
+    /// Fetched but already superseded or withdrawn locally
+    pub stale: usize,
+    /// Current local events the relay doesn't have
+    pub missing_on_relay: Vec<EventId>,
+    /// Advertised by the relay but not delivered, or failed verification
+    pub not_received: Vec<EventId>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl RelayReconciliation {
+    fn new(relay: &RelayUrl) -> Self {
+        Self {
+            relay: relay.clone(),
+            added: 0,
+            replaced: 0,
+            stale: 0,
+            missing_on_relay: Vec::new(),
+            not_received: Vec::new(),
+            error: None,
+        }
+    }
+}
+
+/// Filter covering every kind the store mirrors
+pub fn family_filter() -> Filter {
+    Filter::new().kinds(MIRRORED_KINDS.map(Kind::from))
+}
+
+/// Reconciles a `JobStore` with the relays of a `Client`
+#[derive(Debug, Clone)]
+pub struct Reconciler {
+    batch_size: usize,
+    timeout: Duration,
+}
+
+impl Default for Reconciler {
+    fn default() -> Self {
+        Self {
+            batch_size: 250,
+            timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+impl Reconciler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Missing ids requested per fetch
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Wait for the relay's first negentropy reply, and per fetch
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Reconcile with one relay of `client` and store what it has that we don't
+    pub async fn reconcile(&self, client: &Client, relay: &RelayUrl, store: &mut JobStore) -> Result<RelayReconciliation> {
+        let items = store.negentropy_items(MIRRORED_KINDS)?;
+        let opts = SyncOptions::new().dry_run().initial_timeout(self.timeout);
+        let reconciliation = client.relay(relay).await?.sync_with_items(family_filter(), items, &opts).await?;
+
+        let mut report = RelayReconciliation::new(relay);
+        let mut wanted: Vec<EventId> = reconciliation.remote.into_iter().collect();
+        wanted.sort();
+        let mut pending: HashSet<EventId> = wanted.iter().copied().collect();
+        for chunk in wanted.chunks(self.batch_size) {
+            let filter = Filter::new().ids(chunk.iter().copied());
+            let events = client.fetch_events_from([relay.clone()], filter, self.timeout).await?;
+            for event in events.iter() {
+                if !pending.contains(&event.id) {
+                    continue;
+                }
+                match store.ingest(event) {
+                    Ok(Ingested::Inserted | Ingested::NotAJob) => report.added += 1,
+                    Ok(Ingested::Replaced) => report.replaced += 1,
+                    Ok(Ingested::Unchanged | Ingested::Deleted) => report.stale += 1,
+                    Err(_) => continue,
+                }
+                pending.remove(&event.id);
+            }
+        }
+        report.not_received = pending.into_iter().collect();
+        report.not_received.sort();
+
+        // After fetching, so versions the relay replaced aren't reported
+        for id in reconciliation.local {
+            if store.event(&id)?.is_some() {
+                report.missing_on_relay.push(id);
+            }
+        }
+        report.missing_on_relay.sort();
+        Ok(report)
+    }
+
+    /// Reconcile with every relay of `client` in turn. A relay that fails
+    /// (e.g. without NIP-77 support) is reported with its error.
+    pub async fn reconcile_all(&self, client: &Client, store: &mut JobStore) -> Vec<RelayReconciliation> {
+        let mut relays: Vec<RelayUrl> = client.relays().await.into_keys().collect();
+        relays.sort();
+        let mut reports = Vec::new();
+        for relay in &relays {
+            let report = self.reconcile(client, relay, store).await.unwrap_or_else(|e| RelayReconciliation {
+                error: Some(e.to_string()),
+                ..RelayReconciliation::new(relay)
+            });
+            reports.push(report);
+        }
+        reports
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::KIND_SKILL_EDGE;
+    use crate::test_fixtures::listing;
+    use crate::test_relay::TestRelay;
+
+    fn reconciler() -> Reconciler {
+        Reconciler::new().batch_size(2).timeout(Duration::from_secs(2))
+    }
+
+    async fn client(relays: &[RelayUrl]) -> Client {
+        let client = Client::default();
+        for relay in relays {
+            client.add_relay(relay).await.unwrap();
+        }
+        client.connect().await;
+        client.wait_for_connection(Duration::from_secs(2)).await;
+        client
+    }
+
+    #[tokio::test]
+    async fn test_fetches_only_missing_ids() {
+        let relay = TestRelay::start().await;
+        let keys = Keys::generate();
+        let shared = listing("job-1", "Rust Dev", &keys, 1_000);
+        let outdated = listing("job-2", "Go Dev", &keys, 1_000);
+        let updated = listing("job-2", "Senior Go Dev", &keys, 2_000);
+        let local_only = listing("job-3", "Zig Dev", &keys, 1_500);
+        let edge = EventBuilder::new(Kind::from(KIND_SKILL_EDGE), "{}")
+            .tag(Tag::identifier("rust-wasm"))
+            .sign_with_keys(&keys)
+            .unwrap();
+        let fresh: Vec<Event> = (4..7).map(|i| listing(&format!("job-{}", i), "Nix Dev", &keys, 3_000)).collect();
+        for event in [&shared, &updated, &edge].into_iter().chain(&fresh) {
+            relay.insert(event.clone());
+        }
+
+        let mut store = JobStore::open_in_memory().unwrap();
+        for event in [&shared, &outdated, &local_only] {
+            store.ingest(event).unwrap();
+        }
+        let client = client(&[relay.url()]).await;
+        let report = reconciler().reconcile(&client, &relay.url(), &mut store).await.unwrap();
+
+        assert_eq!((report.added, report.replaced, report.stale), (4, 1, 0));
+        assert_eq!(report.missing_on_relay, vec![local_only.id]);
+        assert!(report.not_received.is_empty());
+        assert_eq!(store.len().unwrap(), 6);
+        assert!(store.event(&edge.id).unwrap().is_some());
+        assert!(store.event(&outdated.id).unwrap().is_none());
+        // Dry run: nothing was pushed to the relay
+        assert_eq!(relay.events().len(), 6);
+
+        // Replaced versions still count as had, so nothing is fetched again
+        let again = reconciler().reconcile(&client, &relay.url(), &mut store).await.unwrap();
+        assert_eq!((again.added, again.replaced, again.stale), (0, 0, 0));
+        assert_eq!(again.missing_on_relay, vec![local_only.id]);
+    }
+
+    #[tokio::test]
+    async fn test_reports_each_relay() {
+        let (first, second) = (TestRelay::start().await, TestRelay::start().await);
+        let down = RelayUrl::parse("ws://127.0.0.1:1").unwrap();
+        let keys = Keys::generate();
+        let both = listing("job-1", "Rust Dev", &keys, 1_000);
+        first.insert(both.clone());
+        second.insert(both.clone());
+        second.insert(listing("job-2", "Go Dev", &keys, 1_000));
+
+        let mut store = JobStore::open_in_memory().unwrap();
+        let client = client(&[first.url(), second.url(), down.clone()]).await;
+        let reports = reconciler().reconcile_all(&client, &mut store).await;
+
+        let report = |url: &RelayUrl| reports.iter().find(|r| &r.relay == url).unwrap();
+        // The shared listing is only fetched from whichever relay came first
+        assert_eq!(report(&first.url()).added + report(&second.url()).added, 2);
+        assert!(report(&second.url()).added >= 1);
+        assert!(report(&down).error.is_some());
+        assert_eq!(store.len().unwrap(), 2);
+
+        let json = serde_json::to_value(report(&first.url())).unwrap();
+        assert!(json.get("error").is_none());
+    }
+}
@@ -0,0 +1,330 @@
+// src/relays.rs
+// Relay configuration: read and write sets from a JSON file, NIP-65
+// (kind 10002) import, and per-relay health stats persisted between runs
+
+use anyhow::{anyhow, Context, Result};
+use nostr_sdk::nips::nip65::{self, RelayMetadata};
+use nostr_sdk::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+/// Used when no config file exists
+pub const DEFAULT_RELAYS: [&str; 3] = [
+    "wss://relay.damus.io",
+    "wss://relay.nostr.band",
+    "wss://nostr.wine",
+];
+
+/// Relays to read listings from and publish them to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RelayConfig {
+    #[serde(default)]
+    pub read: Vec<RelayUrl>,
+    #[serde(default)]
+    pub write: Vec<RelayUrl>,
+}
+
+impl Default for RelayConfig {
+    fn default() -> Self {
+        let relays: Vec<RelayUrl> = DEFAULT_RELAYS
+            .iter()
+            .map(|url| RelayUrl::parse(url).expect("valid default relay"))
+            .collect();
+        Self {
+            read: relays.clone(),
+            write: relays,
+        }
+    }
+}
+
+impl RelayConfig {
+    /// Load a JSON config; a missing file gives the defaults
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let json = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read relay config: {}", path.display()))?;
+        serde_json::from_str(&json)
+            .with_context(|| format!("Invalid relay config: {}", path.display()))
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        fs::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write relay config: {}", path.display()))
+    }
+
+    /// Per-command overrides: a non-empty list replaces that set
+    pub fn with_overrides(mut self, read: &[RelayUrl], write: &[RelayUrl]) -> Self {
+        if !read.is_empty() {
+            self.read = read.to_vec();
+        }
+        if !write.is_empty() {
+            self.write = write.to_vec();
+        }
+        self
+    }
+
+    /// Read and write sets from a NIP-65 relay list (kind 10002).
+    /// Relays without a marker are used for both.
+    pub fn from_nip65(event: &Event) -> Result<Self> {
+        if event.kind != Kind::RelayList {
+            return Err(anyhow!("Expected kind 10002, got {}", event.kind));
+        }
+        let mut config = Self { read: Vec::new(), write: Vec::new() };
+        for (url, metadata) in nip65::extract_relay_list(event) {
+            if *metadata != Some(RelayMetadata::Write) {
+                config.read.push(url.clone());
+            }
+            if *metadata != Some(RelayMetadata::Read) {
+                config.write.push(url.clone());
+            }
+        }
+        Ok(config)
+    }
+
+    /// Add relays from `other` that aren't already configured
+    pub fn merge(&mut self, other: &RelayConfig) {
+        for (ours, theirs) in [(&mut self.read, &other.read), (&mut self.write, &other.write)] {
+            for url in theirs {
+                if !ours.contains(url) {
+                    ours.push(url.clone());
+                }
+            }
+        }
+    }
+
+    /// Fetch `pubkey`'s newest NIP-65 relay list through `client`
+    pub async fn fetch_nip65(client: &Client, pubkey: PublicKey, timeout: Duration) -> Result<Option<Self>> {
+        let filter = Filter::new().kind(Kind::RelayList).author(pubkey);
+        let events = client.fetch_events(filter, timeout).await?;
+        events
+            .iter()
+            .max_by_key(|e| e.created_at)
+            .map(Self::from_nip65)
+            .transpose()
+    }
+}
+
+/// Outcomes seen for one relay
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RelayStats {
+    pub successes: u64,
+    pub failures: u64,
+    pub consecutive_failures: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_success: Option<Timestamp>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_failure: Option<Timestamp>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+impl RelayStats {
+    pub fn success_rate(&self) -> Option<f64> {
+        let total = self.successes + self.failures;
+        (total > 0).then(|| self.successes as f64 / total as f64)
+    }
+}
+
+/// Per-relay connection and publish outcomes, kept in a JSON file
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RelayHealth {
+    relays: BTreeMap<RelayUrl, RelayStats>,
+}
+
+impl RelayHealth {
+    /// Relays that failed this many times in a row are treated as down
+    pub const MAX_CONSECUTIVE_FAILURES: u32 = 5;
+
+    /// Load saved stats; a missing file starts empty
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let json = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read relay health: {}", path.display()))?;
+        serde_json::from_str(&json)
+            .with_context(|| format!("Invalid relay health file: {}", path.display()))
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        fs::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write relay health: {}", path.display()))
+    }
+
+    pub fn record_success(&mut self, url: &RelayUrl) {
+        let stats = self.relays.entry(url.clone()).or_default();
+        stats.successes += 1;
+        stats.consecutive_failures = 0;
+        stats.last_success = Some(Timestamp::now());
+    }
+
+    pub fn record_failure(&mut self, url: &RelayUrl, error: &str) {
+        let stats = self.relays.entry(url.clone()).or_default();
+        stats.failures += 1;
+        stats.consecutive_failures += 1;
+        stats.last_failure = Some(Timestamp::now());
+        stats.last_error = Some(error.to_string());
+    }
+
+    /// Record a pool result, e.g. from `Client::try_connect` or `send_event`
+    pub fn record_output<T: Debug>(&mut self, output: &Output<T>) {
+        for url in &output.success {
+            self.record_success(url);
+        }
+        for (url, error) in &output.failed {
+            self.record_failure(url, error);
+        }
+    }
+
+    pub fn stats(&self, url: &RelayUrl) -> Option<&RelayStats> {
+        self.relays.get(url)
+    }
+
+    /// Unknown relays count as healthy
+    pub fn is_healthy(&self, url: &RelayUrl) -> bool {
+        self.stats(url)
+            .is_none_or(|s| s.consecutive_failures < Self::MAX_CONSECUTIVE_FAILURES)
+    }
+
+    /// Healthy relays first, keeping the configured order otherwise
+    pub fn prioritise(&self, relays: &[RelayUrl]) -> Vec<RelayUrl> {
+        let mut ordered = relays.to_vec();
+        ordered.sort_by_key(|url| !self.is_healthy(url));
+        ordered
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&RelayUrl, &RelayStats)> {
+        self.relays.iter()
+    }
+}
+
+/// Client connected to `relays`. Relays that fail to connect are recorded
+/// in `health` and dropped; fails if none connect.
+pub async fn connect(relays: &[RelayUrl], health: &mut RelayHealth, timeout: Duration) -> Result<Client> {
+    let client = Client::default();
+    for url in relays {
+        client.add_relay(url).await?;
+    }
+    let output = client.try_connect(timeout).await;
+    health.record_output(&output);
+    for url in output.failed.keys() {
+        client.remove_relay(url).await?;
+    }
+    if output.success.is_empty() {
+        return Err(anyhow!("Could not connect to any relay"));
+    }
+    Ok(client)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_relay::TestRelay;
+
+    fn url(s: &str) -> RelayUrl {
+        RelayUrl::parse(s).unwrap()
+    }
+
+    #[test]
+    fn test_config_file_and_overrides() {
+        let path = std::env::temp_dir().join(format!("nosjob-relays-{}.json", std::process::id()));
+        let _ = fs::remove_file(&path);
+        assert_eq!(RelayConfig::load(&path).unwrap(), RelayConfig::default());
+
+        let config = RelayConfig {
+            read: vec![url("wss://read.example.com")],
+            write: vec![url("wss://write.example.com")],
+        };
+        config.save(&path).unwrap();
+        let loaded = RelayConfig::load(&path).unwrap();
+        assert_eq!(loaded, config);
+        fs::remove_file(&path).unwrap();
+
+        let overridden = loaded.with_overrides(&[], &[url("wss://once.example.com")]);
+        assert_eq!(overridden.read, config.read);
+        assert_eq!(overridden.write, vec![url("wss://once.example.com")]);
+    }
+
+    #[test]
+    fn test_nip65_import() {
+        let keys = Keys::generate();
+        let event = EventBuilder::relay_list([
+            (url("wss://both.example.com"), None),
+            (url("wss://inbox.example.com"), Some(RelayMetadata::Read)),
+            (url("wss://outbox.example.com"), Some(RelayMetadata::Write)),
+        ])
+        .sign_with_keys(&keys)
+        .unwrap();
+
+        let imported = RelayConfig::from_nip65(&event).unwrap();
+        assert_eq!(imported.read, vec![url("wss://both.example.com"), url("wss://inbox.example.com")]);
+        assert_eq!(imported.write, vec![url("wss://both.example.com"), url("wss://outbox.example.com")]);
+
+        let mut config = RelayConfig {
+            read: vec![url("wss://both.example.com")],
+            write: vec![],
+        };
+        config.merge(&imported);
+        assert_eq!(config.read.len(), 2);
+        assert_eq!(config.write.len(), 2);
+        assert!(RelayConfig::from_nip65(&EventBuilder::text_note("hi").sign_with_keys(&keys).unwrap()).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_local_relay_health_and_import() {
+        let relay = TestRelay::start().await;
+        let down = url("ws://127.0.0.1:1");
+        let keys = Keys::generate();
+        relay.insert(
+            EventBuilder::relay_list([(url("wss://mine.example.com"), None)])
+                .sign_with_keys(&keys)
+                .unwrap(),
+        );
+
+        let mut health = RelayHealth::default();
+        let client = connect(&[relay.url(), down.clone()], &mut health, Duration::from_secs(2))
+            .await
+            .unwrap();
+        assert_eq!(client.relays().await.len(), 1);
+
+        let event = EventBuilder::text_note("hello").sign_with_keys(&keys).unwrap();
+        health.record_output(&client.send_event(&event).await.unwrap());
+        assert!(relay.events().iter().any(|e| e.id == event.id));
+
+        let up = health.stats(&relay.url()).unwrap();
+        assert_eq!((up.successes, up.failures), (2, 0));
+        assert_eq!(up.success_rate(), Some(1.0));
+        let failed = health.stats(&down).unwrap();
+        assert_eq!(failed.successes, 0);
+        assert!(failed.failures >= 1 && failed.last_error.is_some());
+
+        let fetched = RelayConfig::fetch_nip65(&client, keys.public_key(), Duration::from_secs(2))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(fetched.read, vec![url("wss://mine.example.com")]);
+
+        // Persisted and reloaded
+        let path = std::env::temp_dir().join(format!("nosjob-health-{}.json", std::process::id()));
+        for _ in 0..RelayHealth::MAX_CONSECUTIVE_FAILURES {
+            health.record_failure(&down, "connection refused");
+        }
+        health.save(&path).unwrap();
+        let reloaded = RelayHealth::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(reloaded, health);
+        assert!(!reloaded.is_healthy(&down));
+        assert_eq!(reloaded.prioritise(&[down.clone(), relay.url()]), vec![relay.url(), down.clone()]);
+        assert!(connect(&[down], &mut health, Duration::from_secs(2)).await.is_err());
+    }
+}
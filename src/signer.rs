@@ -0,0 +1,60 @@
+// src/signer.rs
+// Signing through any NostrSigner: local keys, a key unlocked from an
+// encrypted keystore (keystore.rs) or a NIP-46 remote signer (bunker.rs)
+
+use anyhow::Result;
+use nostr_sdk::prelude::*;
+use std::future::Future;
+
+/// An event type that can be built unsigned and signed by any signer
+pub trait SignEvent {
+    /// Unsigned event authored by `author`. Checks that depend on the
+    /// author (e.g. a proof must be signed by its issuer) happen here.
+    fn unsigned_event(&self, author: PublicKey) -> Result<UnsignedEvent>;
+
+    /// Build and sign with any `NostrSigner`
+    fn sign_with<S>(&self, signer: &S) -> impl Future<Output = Result<Event>> + Send
+    where
+        S: NostrSigner + ?Sized,
+        Self: Sync,
+    {
+        async move {
+            let author = signer.get_public_key().await?;
+            let unsigned = self.unsigned_event(author)?;
+            Ok(signer.sign_event(unsigned).await?)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proof::{EmploymentPeriod, ProfessionalProof, VerificationMethod};
+    use crate::test_fixtures::sample_job;
+    use crate::types::JobListing;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_sign_with_any_signer() {
+        let keys = Keys::generate();
+        let job = sample_job("job-001");
+
+        // Same event as the `&Keys` constructor, through a trait object
+        let signer: Arc<dyn NostrSigner> = Arc::new(keys.clone());
+        let event = job.sign_with(&signer).await.unwrap();
+        event.verify().unwrap();
+        assert_eq!(event.pubkey, keys.public_key());
+        assert_eq!(JobListing::from_nostr_event(&event).unwrap(), job);
+
+        // Author checks still apply
+        let proof = ProfessionalProof::new(
+            Keys::generate().public_key(),
+            keys.public_key(),
+            "Engineer".to_string(),
+            "Acme Corp".to_string(),
+            EmploymentPeriod { start: "2020-01".to_string(), end: None },
+            VerificationMethod::Email,
+        );
+        assert!(proof.sign_with(&keys).await.is_err());
+    }
+}
@@ -0,0 +1,336 @@
+// src/skills.rs
+// Kind 39997: Skill graph edges and an in-memory skill graph
+// Used to widen skill searches to closely related skills
+
+use crate::signer::SignEvent;
+use crate::events::{JobsFilter, KIND_SKILL_EDGE};
+use crate::query::JobQuery;
+use crate::types::ValidationError;
+use anyhow::{anyhow, Result};
+use nostr_sdk::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+/// Relationship between two skills
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum SkillRelation {
+    Requires,
+    Enables,
+    SimilarTo,
+    PrerequisiteFor,
+}
+
+impl SkillRelation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SkillRelation::Requires => "requires",
+            SkillRelation::Enables => "enables",
+            SkillRelation::SimilarTo => "similar-to",
+            SkillRelation::PrerequisiteFor => "prerequisite-for",
+        }
+    }
+}
+
+/// Skill graph edge (kind 39997, type "skill-edge")
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SkillEdge {
+    #[serde(rename = "type")]
+    pub edge_type: String, // "skill-edge"
+    pub version: u32,
+
+    pub from: String,
+    pub to: String,
+    pub relation: SkillRelation,
+    pub weight: f64, // 0.0 - 1.0
+
+    // === Extensions ===
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+impl SkillEdge {
+    pub fn new(from: &str, to: &str, relation: SkillRelation, weight: f64) -> Self {
+        Self {
+            edge_type: "skill-edge".to_string(),
+            version: 1,
+            from: skill_key(from),
+            to: skill_key(to),
+            relation,
+            weight,
+            extra: HashMap::new(),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        if self.edge_type != "skill-edge" {
+            return Err(ValidationError::InvalidConfiguration(
+                "skill edge type must be \"skill-edge\"",
+            ));
+        }
+        if self.from.is_empty() {
+            return Err(ValidationError::MissingField("from"));
+        }
+        if self.to.is_empty() {
+            return Err(ValidationError::MissingField("to"));
+        }
+        if self.from == self.to {
+            return Err(ValidationError::InvalidConfiguration("skill edge links a skill to itself"));
+        }
+        if !(0.0..=1.0).contains(&self.weight) {
+            return Err(ValidationError::InvalidConfiguration("skill edge weight must be between 0 and 1"));
+        }
+        Ok(())
+    }
+
+    /// `d` tag: one addressable edge per author, skill pair and relation
+    pub fn d_tag(&self) -> String {
+        format!("{}:{}:{}", self.from, self.relation.as_str(), self.to)
+    }
+
+    /// Convert to a kind 39997 event
+    pub fn to_nostr_event(&self, keys: &Keys) -> Result<Event> {
+        Ok(self.unsigned_event(keys.public_key())?.sign_with_keys(keys)?)
+    }
+
+    /// Parse a kind 39997 event
+    pub fn from_nostr_event(event: &Event) -> Result<Self> {
+        if event.kind != Kind::from(KIND_SKILL_EDGE) {
+            return Err(anyhow!(
+                "Invalid event kind: expected {}, got {}",
+                KIND_SKILL_EDGE,
+                event.kind
+            ));
+        }
+        let mut edge: SkillEdge = serde_json::from_str(&event.content)?;
+        edge.from = skill_key(&edge.from);
+        edge.to = skill_key(&edge.to);
+        edge.validate()?;
+        Ok(edge)
+    }
+}
+
+impl SignEvent for SkillEdge {
+    fn unsigned_event(&self, author: PublicKey) -> Result<UnsignedEvent> {
+        self.validate()?;
+        Ok(EventBuilder::new(Kind::from(KIND_SKILL_EDGE), serde_json::to_string(self)?)
+            .tags([
+                Tag::identifier(self.d_tag()),
+                Tag::custom(TagKind::Custom("skill".into()), vec![&self.from]),
+                Tag::custom(TagKind::Custom("skill".into()), vec![&self.to]),
+                Tag::custom(TagKind::Custom("relation".into()), vec![self.relation.as_str()]),
+            ])
+            .build(author))
+    }
+}
+
+/// Skill names are compared trimmed and lowercase
+fn skill_key(skill: &str) -> String {
+    skill.trim().to_lowercase()
+}
+
+/// Undirected, weighted skill graph.
+/// Expansion follows edges in either direction: a job asking for a skill
+/// is relevant to the skills it requires, enables or resembles.
+#[derive(Debug, Clone, Default)]
+pub struct SkillGraph {
+    // skill -> (neighbour -> strongest edge weight)
+    neighbours: HashMap<String, HashMap<String, f64>>,
+}
+
+impl SkillGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build from kind 39997 events, skipping any that don't parse.
+    /// Edges are addressable, so only each author's newest version of an
+    /// edge (per `d` tag) counts: republishing with a lower weight lowers it.
+    pub fn from_events<'a, I>(events: I) -> Self
+    where
+        I: IntoIterator<Item = &'a Event>,
+    {
+        // NIP-01: newest created_at wins, ties go to the lowest id
+        let mut newest: HashMap<(PublicKey, String), &Event> = HashMap::new();
+        // Other addressable kinds can share a d tag; they must not shadow an edge
+        for event in events.into_iter().filter(|e| e.kind == Kind::from(KIND_SKILL_EDGE)) {
+            let key = (event.pubkey, event.tags.identifier().unwrap_or_default().to_string());
+            let current = newest.entry(key).or_insert(event);
+            if (event.created_at, Reverse(event.id)) > (current.created_at, Reverse(current.id)) {
+                *current = event;
+            }
+        }
+
+        let mut graph = Self::new();
+        for edge in newest.values().filter_map(|e| SkillEdge::from_nostr_event(e).ok()) {
+            graph.add_edge(&edge);
+        }
+        graph
+    }
+
+    /// Add an edge; when several authors link the same pair the strongest weight wins
+    pub fn add_edge(&mut self, edge: &SkillEdge) {
+        let (from, to) = (skill_key(&edge.from), skill_key(&edge.to));
+        for (a, b) in [(&from, &to), (&to, &from)] {
+            let weight = self.neighbours.entry(a.clone()).or_default().entry(b.clone()).or_insert(0.0);
+            *weight = weight.max(edge.weight);
+        }
+    }
+
+    /// Skills reachable from `skill` within `max_hops`, with path weight
+    /// (product of edge weights along the strongest path) of at least
+    /// `min_weight`. Includes `skill` itself at weight 1.0; strongest first.
+    pub fn expand(&self, skill: &str, max_hops: usize, min_weight: f64) -> Vec<(String, f64)> {
+        let start = skill_key(skill);
+        let mut best: HashMap<String, f64> = HashMap::from([(start.clone(), 1.0)]);
+        let mut frontier = vec![(start, 1.0)];
+
+        for _ in 0..max_hops {
+            let mut next = Vec::new();
+            for (node, weight) in &frontier {
+                for (neighbour, edge_weight) in self.neighbours.get(node).into_iter().flatten() {
+                    let path_weight = weight * edge_weight;
+                    if path_weight < min_weight {
+                        continue;
+                    }
+                    if best.get(neighbour).is_none_or(|w| path_weight > *w) {
+                        best.insert(neighbour.clone(), path_weight);
+                        next.push((neighbour.clone(), path_weight));
+                    }
+                }
+            }
+            frontier = next;
+        }
+
+        let mut related: Vec<(String, f64)> = best.into_iter().collect();
+        related.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        related
+    }
+}
+
+impl JobsFilter {
+    /// Match `skill` or any related skill from `graph` (see `SkillGraph::expand`)
+    pub fn related_skills(mut self, skill: &str, graph: &SkillGraph, max_hops: usize, min_weight: f64) -> Self {
+        for (related, _) in graph.expand(skill, max_hops, min_weight) {
+            self = self.skill(&related);
+        }
+        self
+    }
+}
+
+impl JobQuery {
+    /// Match `skill` or any related skill from `graph`, on relays and locally
+    pub fn related_skills(mut self, skill: &str, graph: &SkillGraph, max_hops: usize, min_weight: f64) -> Self {
+        for (related, _) in graph.expand(skill, max_hops, min_weight) {
+            self = self.skill(&related);
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::KIND_JOB_LISTING;
+    use crate::test_fixtures::job_builder;
+
+    fn graph(keys: &Keys) -> SkillGraph {
+        let edges = [
+            SkillEdge::new("Rust", "async-programming", SkillRelation::Requires, 0.8),
+            SkillEdge::new("rust", "C++", SkillRelation::SimilarTo, 0.6),
+            SkillEdge::new("async-programming", "tokio", SkillRelation::Enables, 0.9),
+            SkillEdge::new("tokio", "networking", SkillRelation::Enables, 0.9),
+            SkillEdge::new("c++", "unreal-engine", SkillRelation::PrerequisiteFor, 0.5),
+        ];
+        let events: Vec<Event> = edges.iter().map(|e| e.to_nostr_event(keys).unwrap()).collect();
+        SkillGraph::from_events(&events)
+    }
+
+    #[test]
+    fn test_edge_round_trip() {
+        let keys = Keys::generate();
+        let edge = SkillEdge::new("Rust", "async-programming", SkillRelation::PrerequisiteFor, 0.8);
+        let event = edge.to_nostr_event(&keys).unwrap();
+
+        assert!(event.content.contains("\"relation\":\"prerequisite-for\""));
+        assert!(event.tags.iter().any(|t| t.as_slice() == ["skill", "rust"]));
+        assert!(event.tags.iter().any(|t| t.as_slice() == ["relation", "prerequisite-for"]));
+        assert_eq!(SkillEdge::from_nostr_event(&event).unwrap(), edge);
+
+        assert!(SkillEdge::new("rust", "go", SkillRelation::SimilarTo, 1.5).validate().is_err());
+        assert!(SkillEdge::new("rust", "Rust", SkillRelation::SimilarTo, 0.5).validate().is_err());
+    }
+
+    #[test]
+    fn test_expand_within_hops_and_weight() {
+        let graph = graph(&Keys::generate());
+
+        let one_hop: Vec<String> = graph.expand("Rust", 1, 0.5).into_iter().map(|(s, _)| s).collect();
+        assert_eq!(one_hop, vec!["rust", "async-programming", "c++"]);
+
+        let two_hops = graph.expand("rust", 2, 0.5);
+        let tokio = two_hops.iter().find(|(s, _)| s == "tokio").unwrap();
+        assert!((tokio.1 - 0.72).abs() < 1e-9);
+        // 0.6 * 0.5 = 0.3 falls below the threshold
+        assert!(!two_hops.iter().any(|(s, _)| s == "unreal-engine"));
+
+        let three_hops = graph.expand("rust", 3, 0.6);
+        assert!(three_hops.iter().any(|(s, _)| s == "networking"));
+
+        // Edges are followed in both directions
+        assert!(graph.expand("tokio", 1, 0.5).iter().any(|(s, _)| s == "async-programming"));
+    }
+
+    #[test]
+    fn test_republished_edge_replaces_weight() {
+        let (keys, other) = (Keys::generate(), Keys::generate());
+        let edge = |weight: f64, created_at: u64, keys: &Keys| {
+            let edge = SkillEdge::new("rust", "go", SkillRelation::SimilarTo, weight);
+            EventBuilder::new(Kind::from(KIND_SKILL_EDGE), serde_json::to_string(&edge).unwrap())
+                .tag(Tag::identifier(edge.d_tag()))
+                .custom_created_at(Timestamp::from(created_at))
+                .sign_with_keys(keys)
+                .unwrap()
+        };
+        let strong = edge(0.9, 1_000, &keys);
+        let weakened = edge(0.2, 2_000, &keys);
+        let weight = |graph: &SkillGraph| {
+            graph.expand("rust", 1, 0.0).into_iter().find(|(s, _)| s == "go").map(|(_, w)| w)
+        };
+
+        // Newest version wins regardless of order
+        assert_eq!(weight(&SkillGraph::from_events([&weakened, &strong])), Some(0.2));
+        assert_eq!(weight(&SkillGraph::from_events([&strong, &weakened])), Some(0.2));
+
+        // Another author's edge still counts on its own
+        let theirs = edge(0.5, 1_000, &other);
+        assert_eq!(weight(&SkillGraph::from_events([&strong, &weakened, &theirs])), Some(0.5));
+        // A newer event of another kind with the same d tag doesn't hide the edge
+        let unrelated = EventBuilder::new(Kind::from(KIND_JOB_LISTING), "{}")
+            .tag(Tag::identifier(SkillEdge::new("rust", "go", SkillRelation::SimilarTo, 0.9).d_tag()))
+            .custom_created_at(Timestamp::from(3_000))
+            .sign_with_keys(&keys)
+            .unwrap();
+        assert_eq!(weight(&SkillGraph::from_events([&weakened, &unrelated])), Some(0.2));
+    }
+
+    #[test]
+    fn test_widened_search_matches_related_skill() {
+        let keys = Keys::generate();
+        let graph = graph(&keys);
+        let job = job_builder("job-001", "Async Backend Developer")
+            .skills(vec!["Tokio".to_string()])
+            .build()
+            .unwrap();
+        let event = job.to_nostr_event(&keys).unwrap();
+
+        assert!(!JobQuery::new().skill("Rust").matches(&job));
+        let query = JobQuery::new().related_skills("Rust", &graph, 2, 0.5);
+        assert!(query.matches(&job));
+        assert!(query.relay_filter().match_event(&event, MatchEventOptions::new()));
+
+        let filter = JobsFilter::new().related_skills("Rust", &graph, 2, 0.5).build();
+        assert!(filter.match_event(&event, MatchEventOptions::new()));
+    }
+}
@@ -0,0 +1,809 @@
+// src/store.rs
+// Local SQLite job index for offline search
+// Keeps the newest version of each address for the job event family
+// (kinds 39993-39997 are addressable), remembers NIP-09 withdrawals, and
+// indexes listings' searchable fields plus an FTS5 table over title,
+// company, skills and description
+
+use crate::address::JobAddress;
+use crate::events::{index_value, KIND_JOB_LISTING, KIND_SKILL_EDGE};
+use crate::profile::ProfessionalEvent;
+use crate::query::{convert_pay, parse_date, salary_range, JobQuery};
+use crate::sync::SyncCursor;
+use crate::types::*;
+use anyhow::{anyhow, Result};
+use chrono::{NaiveDate, Utc};
+use nostr_sdk::prelude::*;
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, Transaction};
+use std::cmp::Reverse;
+use std::ops::RangeInclusive;
+use std::path::Path;
+
+/// Kinds kept in the store: listings through skill edges
+pub const MIRRORED_KINDS: RangeInclusive<u16> = KIND_JOB_LISTING..=KIND_SKILL_EDGE;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS jobs (
+    address          TEXT PRIMARY KEY,
+    event_id         TEXT NOT NULL UNIQUE,
+    pubkey           TEXT NOT NULL,
+    created_at       INTEGER NOT NULL,
+    title            TEXT NOT NULL,
+    company          TEXT NOT NULL,
+    currency         TEXT,
+    salary_low       REAL,
+    salary_high      REAL,
+    salary_unit      TEXT,
+    salary_low_year  REAL,
+    salary_high_year REAL,
+    valid_through    TEXT,
+    event            TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS jobs_company ON jobs (company);
+CREATE INDEX IF NOT EXISTS jobs_created_at ON jobs (created_at);
+CREATE INDEX IF NOT EXISTS jobs_valid_through ON jobs (valid_through);
+
+-- Every event of the job family seen (kinds 39993-39997). Replaced and
+-- withdrawn versions keep their id with no content, so set
+-- reconciliation doesn't fetch them again.
+CREATE TABLE IF NOT EXISTS events (
+    id         TEXT PRIMARY KEY,
+    address    TEXT NOT NULL,
+    pubkey     TEXT NOT NULL,
+    kind       INTEGER NOT NULL,
+    created_at INTEGER NOT NULL,
+    event      TEXT
+);
+CREATE INDEX IF NOT EXISTS events_address ON events (address);
+CREATE INDEX IF NOT EXISTS events_kind ON events (kind, created_at);
+
+-- Multi-valued fields: skill, employment-type, worker-type, location-type,
+-- country (job locations and applicant requirements), locality
+CREATE TABLE IF NOT EXISTS job_terms (
+    address TEXT NOT NULL REFERENCES jobs (address) ON DELETE CASCADE,
+    field   TEXT NOT NULL,
+    value   TEXT NOT NULL,
+    PRIMARY KEY (address, field, value)
+);
+CREATE INDEX IF NOT EXISTS job_terms_value ON job_terms (field, value);
+
+-- Withdrawn event ids and addresses (NIP-09), so old copies stay out.
+-- Keyed per deleter: a deletion only counts for its own author's events,
+-- so someone else's request for the same id must not shadow the real one.
+CREATE TABLE IF NOT EXISTS tombstones (
+    target     TEXT NOT NULL,
+    pubkey     TEXT NOT NULL,
+    deleted_at INTEGER NOT NULL,
+    PRIMARY KEY (target, pubkey)
+);
+
+-- Sync progress per relay and stream (see sync.rs)
+CREATE TABLE IF NOT EXISTS sync_cursors (
+    relay      TEXT NOT NULL,
+    stream     TEXT NOT NULL,
+    newest     INTEGER,
+    oldest     INTEGER,
+    backfilled INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (relay, stream)
+);
+
+CREATE VIRTUAL TABLE IF NOT EXISTS jobs_fts USING fts5(
+    address UNINDEXED, title, company, skills, description
+);
+";
+
+/// What `JobStore::ingest` did with an event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ingested {
+    /// First version of this address
+    Inserted,
+    /// Newer version of a stored address
+    Replaced,
+    /// The stored version is the same or newer
+    Unchanged,
+    /// Withdrawn by a deletion request already seen
+    Deleted,
+    /// Stored, but kind 39993 content that isn't a readable job listing
+    /// (a resume, skill assertion or unparseable content), so not searchable
+    NotAJob,
+}
+
+/// A stored listing with the event it came from
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredJob {
+    pub job: JobListing,
+    pub event: Event,
+}
+
+/// SQLite-backed job index
+#[derive(Debug)]
+pub struct JobStore {
+    conn: Connection,
+}
+
+impl JobStore {
+    /// Open (or create) the index at `path`
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::init(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> Result<Self> {
+        conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self { conn })
+    }
+
+    /// Store an event of the job family (kinds 39993–39997) unless a newer
+    /// version of its address (or a deletion) is known. Listings are also
+    /// indexed for search.
+    pub fn ingest(&mut self, event: &Event) -> Result<Ingested> {
+        let tx = self.conn.transaction()?;
+        let ingested = ingest(&tx, event)?;
+        tx.commit()?;
+        Ok(ingested)
+    }
+
+    /// Ingest many events in one transaction, skipping ones that fail to parse
+    /// or verify. A database error aborts the batch and rolls it back.
+    pub fn ingest_all<'a, I>(&mut self, events: I) -> Result<Vec<Ingested>>
+    where
+        I: IntoIterator<Item = &'a Event>,
+    {
+        let tx = self.conn.transaction()?;
+        let mut results = Vec::new();
+        for event in events {
+            match ingest(&tx, event) {
+                Ok(ingested) => results.push(ingested),
+                Err(e) if e.is::<rusqlite::Error>() => return Err(e),
+                Err(_) => {}
+            }
+        }
+        tx.commit()?;
+        Ok(results)
+    }
+
+    /// Apply a NIP-09 deletion request: drops the events it withdraws
+    /// and remembers it for later copies. Returns how many were removed.
+    pub fn apply_deletion(&mut self, deletion: &Event) -> Result<usize> {
+        if deletion.kind != Kind::EventDeletion {
+            return Err(anyhow!("Expected a deletion request (kind 5), got {}", deletion.kind));
+        }
+        deletion.verify()?;
+        let tx = self.conn.transaction()?;
+        let pubkey = deletion.pubkey.to_hex();
+        let deleted_at = deletion.created_at.as_secs() as i64;
+        let mut removed = 0;
+
+        for id in deletion.tags.event_ids() {
+            tombstone(&tx, &id.to_hex(), &pubkey, deleted_at)?;
+            delete_where(&tx, "event_id = ?1 AND pubkey = ?2", params![id.to_hex(), pubkey])?;
+            removed += tx.execute(
+                "UPDATE events SET event = NULL WHERE id = ?1 AND pubkey = ?2 AND event IS NOT NULL",
+                params![id.to_hex(), pubkey],
+            )?;
+        }
+        for coordinate in deletion.tags.coordinates() {
+            let address = JobAddress::from(coordinate.clone());
+            if !MIRRORED_KINDS.contains(&address.kind.as_u16()) || address.pubkey != deletion.pubkey {
+                continue;
+            }
+            tombstone(&tx, &address.to_string(), &pubkey, deleted_at)?;
+            delete_where(
+                &tx,
+                "address = ?1 AND created_at <= ?2",
+                params![address.to_string(), deleted_at],
+            )?;
+            removed += tx.execute(
+                "UPDATE events SET event = NULL WHERE address = ?1 AND created_at <= ?2 AND event IS NOT NULL",
+                params![address.to_string(), deleted_at],
+            )?;
+        }
+        tx.commit()?;
+        Ok(removed)
+    }
+
+    /// Current version of a listing
+    pub fn get(&self, address: &JobAddress) -> Result<Option<StoredJob>> {
+        let json: Option<String> = self
+            .conn
+            .query_row("SELECT event FROM jobs WHERE address = ?1", [address.to_string()], |row| row.get(0))
+            .optional()?;
+        json.map(|json| stored_job(&json)).transpose()
+    }
+
+    /// Number of stored listings
+    pub fn len(&self) -> Result<usize> {
+        let count: i64 = self.conn.query_row("SELECT COUNT(*) FROM jobs", [], |row| row.get(0))?;
+        Ok(count as usize)
+    }
+
+    pub fn is_empty(&self) -> Result<bool> {
+        Ok(self.len()? == 0)
+    }
+
+    /// Everyone with a stored listing (including ones that aren't job
+    /// postings), i.e. the keys whose deletion requests matter
+    pub fn listing_authors(&self) -> Result<Vec<PublicKey>> {
+        let mut statement = self
+            .conn
+            .prepare("SELECT DISTINCT pubkey FROM events WHERE kind = ?1 AND event IS NOT NULL ORDER BY pubkey")?;
+        let rows = statement.query_map([KIND_JOB_LISTING], |row| row.get::<_, String>(0))?;
+        let mut authors = Vec::new();
+        for hex in rows {
+            authors.push(PublicKey::from_hex(&hex?)?);
+        }
+        Ok(authors)
+    }
+
+    /// Current version of any stored event (None if unknown, replaced or withdrawn)
+    pub fn event(&self, id: &EventId) -> Result<Option<Event>> {
+        let json: Option<Option<String>> = self
+            .conn
+            .query_row("SELECT event FROM events WHERE id = ?1", [id.to_hex()], |row| row.get(0))
+            .optional()?;
+        Ok(json.flatten().map(Event::from_json).transpose()?)
+    }
+
+    /// (id, created_at) of every event seen of `kinds`, including replaced
+    /// and withdrawn versions, for NIP-77 set reconciliation
+    pub fn negentropy_items(&self, kinds: RangeInclusive<u16>) -> Result<Vec<(EventId, Timestamp)>> {
+        let mut statement = self
+            .conn
+            .prepare("SELECT id, created_at FROM events WHERE kind BETWEEN ?1 AND ?2")?;
+        let rows = statement.query_map(params![kinds.start(), kinds.end()], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+        })?;
+        let mut items = Vec::new();
+        for row in rows {
+            let (id, created_at) = row?;
+            items.push((EventId::from_hex(&id)?, Timestamp::from(created_at as u64)));
+        }
+        Ok(items)
+    }
+
+    /// Saved sync progress for `stream` on `relay` (empty if never synced)
+    pub fn sync_cursor(&self, relay: &RelayUrl, stream: &str) -> Result<SyncCursor> {
+        let cursor = self
+            .conn
+            .query_row(
+                "SELECT newest, oldest, backfilled FROM sync_cursors WHERE relay = ?1 AND stream = ?2",
+                params![relay.as_str(), stream],
+                |row| {
+                    Ok(SyncCursor {
+                        newest: row.get::<_, Option<i64>>(0)?.map(|t| Timestamp::from(t as u64)),
+                        oldest: row.get::<_, Option<i64>>(1)?.map(|t| Timestamp::from(t as u64)),
+                        backfilled: row.get(2)?,
+                    })
+                },
+            )
+            .optional()?;
+        Ok(cursor.unwrap_or_default())
+    }
+
+    pub fn save_sync_cursor(&self, relay: &RelayUrl, stream: &str, cursor: &SyncCursor) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO sync_cursors (relay, stream, newest, oldest, backfilled)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                relay.as_str(),
+                stream,
+                cursor.newest.map(|t| t.as_secs() as i64),
+                cursor.oldest.map(|t| t.as_secs() as i64),
+                cursor.backfilled,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Listings matching `search`: best text match first, otherwise newest first
+    pub fn search(&self, search: &JobSearch) -> Result<Vec<StoredJob>> {
+        let (sql, values) = search.to_sql();
+        let mut statement = self.conn.prepare(&sql)?;
+        let rows = statement.query_map(params_from_iter(values), |row| row.get::<_, String>(0))?;
+
+        let mut jobs = Vec::new();
+        for json in rows {
+            let stored = stored_job(&json?)?;
+            if search.query.as_ref().is_none_or(|q| q.matches(&stored.job)) {
+                jobs.push(stored);
+            }
+            if search.limit.is_some_and(|limit| jobs.len() >= limit) {
+                break;
+            }
+        }
+        Ok(jobs)
+    }
+}
+
+fn ingest(tx: &Transaction, event: &Event) -> Result<Ingested> {
+    if !MIRRORED_KINDS.contains(&event.kind.as_u16()) {
+        return Err(anyhow!(
+            "Expected kind {}–{}, got {}",
+            MIRRORED_KINDS.start(),
+            MIRRORED_KINDS.end(),
+            event.kind
+        ));
+    }
+    event.verify()?;
+    let address = JobAddress::from_event(event)?.to_string();
+    let id = event.id.to_hex();
+    let created_at = event.created_at.as_secs() as i64;
+    // Remembered without content, so sync doesn't fetch it again
+    let seen_only = || -> Result<()> {
+        tx.execute(
+            "INSERT OR IGNORE INTO events (id, address, pubkey, kind, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![id, address, event.pubkey.to_hex(), event.kind.as_u16(), created_at],
+        )?;
+        Ok(())
+    };
+
+    // Withdrawn by id, or by address at or after this version
+    let withdrawn: bool = tx.query_row(
+        "SELECT EXISTS (SELECT 1 FROM tombstones WHERE pubkey = ?3
+            AND (target = ?1 OR (target = ?2 AND deleted_at >= ?4)))",
+        params![id, address, event.pubkey.to_hex(), created_at],
+        |row| row.get(0),
+    )?;
+    if withdrawn {
+        seen_only()?;
+        return Ok(Ingested::Deleted);
+    }
+    let seen: bool = tx.query_row("SELECT EXISTS (SELECT 1 FROM events WHERE id = ?1)", [&id], |row| row.get(0))?;
+    if seen {
+        return Ok(Ingested::Unchanged);
+    }
+
+    // NIP-01: newest created_at wins, ties go to the lowest id
+    let stored: Option<(i64, String)> = tx
+        .query_row(
+            "SELECT created_at, id FROM events WHERE address = ?1 AND event IS NOT NULL",
+            [&address],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?;
+    if let Some((stored_at, stored_id)) = &stored
+        && (*stored_at, Reverse(stored_id.as_str())) >= (created_at, Reverse(id.as_str()))
+    {
+        seen_only()?;
+        return Ok(Ingested::Unchanged);
+    }
+    tx.execute("UPDATE events SET event = NULL WHERE address = ?1", [&address])?;
+    tx.execute(
+        "INSERT INTO events (id, address, pubkey, kind, created_at, event) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![id, address, event.pubkey.to_hex(), event.kind.as_u16(), created_at, event.as_json()],
+    )?;
+    delete_where(tx, "address = ?1", params![address])?;
+
+    // Listings also go into the search index
+    if event.kind == Kind::from(KIND_JOB_LISTING) {
+        match ProfessionalEvent::from_nostr_event(event) {
+            Ok(ProfessionalEvent::Job(job)) => index_job(tx, &address, event, &job)?,
+            _ => return Ok(Ingested::NotAJob),
+        }
+    }
+    Ok(if stored.is_some() { Ingested::Replaced } else { Ingested::Inserted })
+}
+
+fn index_job(tx: &Transaction, address: &str, event: &Event, job: &JobListing) -> Result<()> {
+    let salary = job.base_salary.as_ref();
+    let range = salary.and_then(salary_range);
+    let yearly = |amount: f64| range.and_then(|(_, _, unit)| convert_pay(amount, unit, "YEAR"));
+    let valid_through = job
+        .valid_through
+        .as_deref()
+        .and_then(parse_date)
+        .map(|date| date.format("%Y-%m-%d").to_string());
+    tx.execute(
+        "INSERT INTO jobs (address, event_id, pubkey, created_at, title, company, currency,
+            salary_low, salary_high, salary_unit, salary_low_year, salary_high_year, valid_through, event)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+        params![
+            address,
+            event.id.to_hex(),
+            event.pubkey.to_hex(),
+            event.created_at.as_secs() as i64,
+            job.title,
+            index_value(&job.hiring_organization.name),
+            salary.map(|s| s.currency.to_uppercase()),
+            range.map(|(low, _, _)| low),
+            range.map(|(_, high, _)| high),
+            range.map(|(_, _, unit)| unit.to_uppercase()),
+            range.and_then(|(low, _, _)| yearly(low)),
+            range.and_then(|(_, high, _)| yearly(high)),
+            valid_through,
+            event.as_json(),
+        ],
+    )?;
+
+    let addresses = job.job_location.iter().filter_map(|l| l.address.as_ref());
+    let terms = job
+        .skills
+        .iter()
+        .flatten()
+        .map(|s| ("skill", index_value(s)))
+        .chain(job.employment_type.iter().map(|t| ("employment-type", t.as_str().to_string())))
+        .chain(job.eligible_worker_type.iter().map(|t| ("worker-type", t.as_str().to_string())))
+        .chain(job.job_location_type.iter().flatten().map(|t| ("location-type", t.as_str().to_string())))
+        .chain(addresses.clone().filter_map(|a| a.address_country.as_deref()).map(|c| ("country", index_value(c))))
+        .chain(
+            job.applicant_location_requirements
+                .iter()
+                .flatten()
+                .map(|r| ("country", index_value(&r.name))),
+        )
+        .chain(addresses.filter_map(|a| a.address_locality.as_deref()).map(|l| ("locality", index_value(l))));
+    for (field, value) in terms {
+        tx.execute(
+            "INSERT OR IGNORE INTO job_terms (address, field, value) VALUES (?1, ?2, ?3)",
+            params![address, field, value],
+        )?;
+    }
+    tx.execute(
+        "INSERT INTO jobs_fts (address, title, company, skills, description) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            address,
+            job.title,
+            job.hiring_organization.name,
+            job.skills.iter().flatten().cloned().collect::<Vec<_>>().join(" "),
+            job.description,
+        ],
+    )?;
+    Ok(())
+}
+
+fn tombstone(tx: &Transaction, target: &str, pubkey: &str, deleted_at: i64) -> Result<()> {
+    tx.execute(
+        "INSERT INTO tombstones (target, pubkey, deleted_at) VALUES (?1, ?2, ?3)
+         ON CONFLICT (target, pubkey) DO UPDATE SET deleted_at = MAX(deleted_at, excluded.deleted_at)",
+        params![target, pubkey, deleted_at],
+    )?;
+    Ok(())
+}
+
+/// Remove listings (and their terms and text) matching `condition`
+fn delete_where<P: rusqlite::Params + Clone>(tx: &Transaction, condition: &str, params: P) -> Result<usize> {
+    tx.execute(
+        &format!("DELETE FROM jobs_fts WHERE address IN (SELECT address FROM jobs WHERE {})", condition),
+        params.clone(),
+    )?;
+    Ok(tx.execute(&format!("DELETE FROM jobs WHERE {}", condition), params)?)
+}
+
+fn stored_job(json: &str) -> Result<StoredJob> {
+    let event = Event::from_json(json)?;
+    let job = JobListing::from_nostr_event(&event)?;
+    Ok(StoredJob { job, event })
+}
+
+/// Query over the indexed columns and the full-text index.
+///
+/// Repeated calls for the same field match any of the values, as in
+/// `JobQuery`; `matching` adds a `JobQuery` for everything else, checked
+/// on the parsed listings.
+#[derive(Debug, Clone, Default)]
+pub struct JobSearch {
+    text: Option<String>,
+    companies: Vec<String>,
+    terms: Vec<(&'static str, String)>,
+    salary_min: Option<(f64, String)>,
+    currency: Option<String>,
+    valid_on: Option<NaiveDate>,
+    limit: Option<usize>,
+    query: Option<JobQuery>,
+}
+
+impl JobSearch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Full-text match on title, company, skills and description (all words must appear)
+    pub fn text(mut self, text: &str) -> Self {
+        self.text = Some(text.to_string());
+        self
+    }
+
+    pub fn company(mut self, name: &str) -> Self {
+        self.companies.push(index_value(name));
+        self
+    }
+
+    pub fn skill(mut self, skill: &str) -> Self {
+        self.terms.push(("skill", index_value(skill)));
+        self
+    }
+
+    pub fn employment_type(mut self, emp_type: EmploymentType) -> Self {
+        self.terms.push(("employment-type", emp_type.as_str().to_string()));
+        self
+    }
+
+    pub fn worker_type(mut self, worker_type: EligibleWorkerType) -> Self {
+        self.terms.push(("worker-type", worker_type.as_str().to_string()));
+        self
+    }
+
+    pub fn location_type(mut self, location_type: JobLocationType) -> Self {
+        self.terms.push(("location-type", location_type.as_str().to_string()));
+        self
+    }
+
+    /// Country of a job location or an applicant location requirement,
+    /// as the listing spells it (e.g. ISO-3166 "US"); case-insensitive
+    pub fn country(mut self, country: &str) -> Self {
+        self.terms.push(("country", index_value(country)));
+        self
+    }
+
+    /// City or town of a job location; case-insensitive
+    pub fn locality(mut self, locality: &str) -> Self {
+        self.terms.push(("locality", index_value(locality)));
+        self
+    }
+
+    /// Listing must pay at least `amount` per `unit` at the top of its range
+    pub fn salary_min(mut self, amount: f64, unit: &str) -> Self {
+        self.salary_min = Some((amount, unit.to_uppercase()));
+        self
+    }
+
+    pub fn currency(mut self, currency: &str) -> Self {
+        self.currency = Some(currency.to_uppercase());
+        self
+    }
+
+    /// Exclude listings whose `valid_through` is before today (UTC)
+    pub fn not_expired(self) -> Self {
+        self.valid_on(Utc::now().date_naive())
+    }
+
+    pub fn valid_on(mut self, date: NaiveDate) -> Self {
+        self.valid_on = Some(date);
+        self
+    }
+
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Also require `query` to match each listing
+    pub fn matching(mut self, query: JobQuery) -> Self {
+        self.query = Some(query);
+        self
+    }
+
+    fn to_sql(&self) -> (String, Vec<Value>) {
+        let mut sql = String::from("SELECT jobs.event FROM jobs");
+        let mut conditions = Vec::new();
+        let mut values = Vec::new();
+
+        if let Some(text) = &self.text {
+            sql.push_str(" JOIN jobs_fts ON jobs_fts.address = jobs.address");
+            conditions.push("jobs_fts MATCH ?".to_string());
+            values.push(Value::Text(fts_phrase(text)));
+        }
+        if !self.companies.is_empty() {
+            conditions.push(format!("jobs.company IN ({})", placeholders(self.companies.len())));
+            values.extend(self.companies.iter().cloned().map(Value::Text));
+        }
+        // OR within a field, AND across fields
+        let mut fields: Vec<&str> = self.terms.iter().map(|(field, _)| *field).collect();
+        fields.sort_unstable();
+        fields.dedup();
+        for field in fields {
+            let wanted: Vec<&String> = self.terms.iter().filter(|(f, _)| *f == field).map(|(_, v)| v).collect();
+            conditions.push(format!(
+                "EXISTS (SELECT 1 FROM job_terms t WHERE t.address = jobs.address AND t.field = ? AND t.value IN ({}))",
+                placeholders(wanted.len())
+            ));
+            values.push(Value::Text(field.to_string()));
+            values.extend(wanted.into_iter().cloned().map(Value::Text));
+        }
+        if let Some((amount, unit)) = &self.salary_min {
+            // Same unit compares directly; calendar periods compare per year
+            conditions.push("((jobs.salary_unit = ? AND jobs.salary_high >= ?) OR jobs.salary_high_year >= ?)".to_string());
+            values.push(Value::Text(unit.clone()));
+            values.push(Value::Real(*amount));
+            values.push(convert_pay(*amount, unit, "YEAR").map_or(Value::Null, Value::Real));
+        }
+        if let Some(currency) = &self.currency {
+            conditions.push("jobs.currency = ?".to_string());
+            values.push(Value::Text(currency.clone()));
+        }
+        if let Some(date) = self.valid_on {
+            // No expiry never hides a listing
+            conditions.push("(jobs.valid_through IS NULL OR jobs.valid_through >= ?)".to_string());
+            values.push(Value::Text(date.format("%Y-%m-%d").to_string()));
+        }
+
+        if !conditions.is_empty() {
+            sql.push_str(" WHERE ");
+            sql.push_str(&conditions.join(" AND "));
+        }
+        sql.push_str(if self.text.is_some() { " ORDER BY jobs_fts.rank" } else { " ORDER BY jobs.created_at DESC" });
+        // With a JobQuery the limit applies after it, in `search`
+        if let (Some(limit), None) = (self.limit, &self.query) {
+            sql.push_str(&format!(" LIMIT {}", limit));
+        }
+        (sql, values)
+    }
+}
+
+/// Every word as a quoted FTS5 string, so user input can't inject query syntax
+fn fts_phrase(text: &str) -> String {
+    text.split_whitespace()
+        .map(|word| format!("\"{}\"", word.replace('"', "\"\"")))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn placeholders(n: usize) -> String {
+    vec!["?"; n].join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lifecycle;
+    use crate::test_fixtures::job_builder;
+
+    fn job(id: &str, title: &str, description: &str, skills: &[&str], salary: f64) -> JobListing {
+        let mut job = job_builder(id, title)
+            .employment_type(vec![EmploymentType::FullTime])
+            .location_type(vec![JobLocationType::Telecommute])
+            .salary(salary, salary + 20000.0, "USD".to_string(), "YEAR".to_string())
+            .skills(skills.iter().map(|s| s.to_string()).collect())
+            .build()
+            .unwrap();
+        job.description = description.to_string();
+        job
+    }
+
+    fn signed(job: &JobListing, keys: &Keys, created_at: u64) -> Event {
+        job.event_builder()
+            .unwrap()
+            .custom_created_at(Timestamp::from(created_at))
+            .sign_with_keys(keys)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_replaceable_semantics_and_deletions() {
+        let keys = Keys::generate();
+        let mut store = JobStore::open_in_memory().unwrap();
+        let first = signed(&job("job-1", "Rust Dev", "Old text", &["Rust"], 100000.0), &keys, 1_000);
+        let second = signed(&job("job-1", "Senior Rust Dev", "New text", &["Rust"], 100000.0), &keys, 2_000);
+
+        assert_eq!(store.ingest(&first).unwrap(), Ingested::Inserted);
+        assert_eq!(store.ingest(&second).unwrap(), Ingested::Replaced);
+        assert_eq!(store.ingest(&first).unwrap(), Ingested::Unchanged);
+        assert_eq!(store.len().unwrap(), 1);
+        let address = JobAddress::from_event(&second).unwrap();
+        assert_eq!(store.get(&address).unwrap().unwrap().job.title, "Senior Rust Dev");
+        // The replaced version's text is gone from the index
+        assert!(store.search(&JobSearch::new().text("Old")).unwrap().is_empty());
+
+        let deletion = lifecycle::deletion_request(&second, None, &keys).unwrap();
+        assert_eq!(store.apply_deletion(&deletion).unwrap(), 1);
+        assert!(store.is_empty().unwrap());
+        // A relay still serving an old copy doesn't bring it back
+        assert_eq!(store.ingest(&second).unwrap(), Ingested::Deleted);
+        assert_eq!(store.ingest(&first).unwrap(), Ingested::Deleted);
+
+        // Someone else's deletion has no effect
+        let other = signed(&job("job-2", "Go Dev", "Gophers", &["Go"], 90000.0), &keys, 3_000);
+        store.ingest(&other).unwrap();
+        let forged = EventBuilder::delete(EventDeletionRequest::new().id(other.id))
+            .sign_with_keys(&Keys::generate())
+            .unwrap();
+        assert_eq!(store.apply_deletion(&forged).unwrap(), 0);
+        assert_eq!(store.len().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_squatted_deletion_does_not_block_the_author() {
+        let keys = Keys::generate();
+        let mut store = JobStore::open_in_memory().unwrap();
+        let event = signed(&job("job-1", "Rust Dev", "Text", &["Rust"], 100000.0), &keys, 1_000);
+
+        // Someone else tombstones the id before the listing arrives
+        let squat = EventBuilder::delete(EventDeletionRequest::new().id(event.id))
+            .sign_with_keys(&Keys::generate())
+            .unwrap();
+        assert_eq!(store.apply_deletion(&squat).unwrap(), 0);
+
+        // The author's own deletion still sticks to late copies
+        let deletion = EventBuilder::delete(EventDeletionRequest::new().id(event.id))
+            .sign_with_keys(&keys)
+            .unwrap();
+        assert_eq!(store.apply_deletion(&deletion).unwrap(), 0);
+        assert_eq!(store.ingest(&event).unwrap(), Ingested::Deleted);
+        assert!(store.is_empty().unwrap());
+    }
+
+    #[test]
+    fn test_ingest_all_skips_bad_events_but_not_database_errors() {
+        let keys = Keys::generate();
+        let mut store = JobStore::open_in_memory().unwrap();
+        let good = signed(&job("a", "Rust Developer", "Build relays", &["Rust"], 100000.0), &keys, 1);
+        let note = EventBuilder::text_note("not a listing").sign_with_keys(&keys).unwrap();
+        assert_eq!(store.ingest_all([&note, &good]).unwrap(), vec![Ingested::Inserted]);
+
+        // The whole batch rolls back
+        store.conn.execute_batch("DROP TABLE job_terms").unwrap();
+        let other = signed(&job("b", "Go Developer", "Build gateways", &["Go"], 90000.0), &keys, 2);
+        let error = store.ingest_all([&other]).unwrap_err();
+        assert!(error.is::<rusqlite::Error>(), "{}", error);
+        assert_eq!(store.len().unwrap(), 1);
+        assert!(store.event(&other.id).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_search() {
+        let path = std::env::temp_dir().join(format!("nosjob-store-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let keys = Keys::generate();
+        {
+            let mut store = JobStore::open(&path).unwrap();
+            let mut rust = job("rust", "Rust Developer", "Build decentralized relays", &["Rust", "Nostr"], 120000.0);
+            rust.applicant_location_requirements = Some(vec![ApplicantLocationRequirement {
+                schema_type: "Country".to_string(),
+                name: "GB".to_string(),
+            }]);
+            let mut go = job("go", "Go Developer", "Maintain payment \"gateways\"", &["Go"], 80000.0);
+            go.job_location.push(JobLocation {
+                schema_type: "Place".to_string(),
+                address: Some(PostalAddress {
+                    schema_type: "PostalAddress".to_string(),
+                    street_address: None,
+                    address_locality: Some("San Francisco".to_string()),
+                    address_region: Some("CA".to_string()),
+                    postal_code: None,
+                    address_country: Some("US".to_string()),
+                }),
+            });
+            let events = [signed(&rust, &keys, 1), signed(&go, &keys, 2)];
+            let ingested = store.ingest_all(events.iter()).unwrap();
+            assert_eq!(ingested, vec![Ingested::Inserted, Ingested::Inserted]);
+        }
+
+        // Persisted across opens
+        let store = JobStore::open(&path).unwrap();
+        let titles = |search: JobSearch| -> Vec<String> {
+            store.search(&search).unwrap().into_iter().map(|s| s.job.title).collect()
+        };
+        assert_eq!(titles(JobSearch::new()), vec!["Go Developer", "Rust Developer"]);
+        assert_eq!(titles(JobSearch::new().text("decentralized relays")), vec!["Rust Developer"]);
+        assert_eq!(titles(JobSearch::new().text("\"gateways\" OR")), Vec::<String>::new());
+        assert_eq!(titles(JobSearch::new().text("gateways")), vec!["Go Developer"]);
+        assert_eq!(titles(JobSearch::new().skill("rust").skill("go")).len(), 2);
+        // Interleaved fields still get one condition each
+        let interleaved = JobSearch::new()
+            .skill("rust")
+            .employment_type(EmploymentType::FullTime)
+            .skill("go");
+        assert_eq!(interleaved.to_sql().0.matches("EXISTS").count(), 2);
+        assert_eq!(titles(interleaved).len(), 2);
+        assert_eq!(titles(JobSearch::new().skill("NOSTR").company("acme corp")), vec!["Rust Developer"]);
+        assert_eq!(titles(JobSearch::new().salary_min(10000.0, "MONTH")), vec!["Rust Developer"]);
+        assert_eq!(titles(JobSearch::new().worker_type(EligibleWorkerType::AIAgent)).len(), 0);
+        assert_eq!(titles(JobSearch::new().location_type(JobLocationType::Telecommute).limit(1)).len(), 1);
+        assert_eq!(titles(JobSearch::new().country("us")), vec!["Go Developer"]);
+        assert_eq!(titles(JobSearch::new().country("GB")), vec!["Rust Developer"]);
+        assert_eq!(titles(JobSearch::new().country("GB").country("US")).len(), 2);
+        assert_eq!(titles(JobSearch::new().locality("san francisco")), vec!["Go Developer"]);
+        assert_eq!(titles(JobSearch::new().locality("London")).len(), 0);
+        assert_eq!(
+            titles(JobSearch::new().matching(JobQuery::new().skill("go")).limit(5)),
+            vec!["Go Developer"]
+        );
+        drop(store);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
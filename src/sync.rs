@@ -0,0 +1,542 @@
+// src/sync.rs
+// Relay → local store sync: backfill history with paginated `until`
+// queries, then follow live subscriptions. Progress is kept per relay in
+// the store so a restart resumes from the last seen `created_at`.
+
+use crate::events::{JobsFilter, KIND_JOB_LISTING};
+use crate::store::{Ingested, JobStore};
+use anyhow::Result;
+use nostr_sdk::prelude::*;
+use std::collections::HashSet;
+use std::future::Future;
+use std::time::Duration;
+
+/// Cursor stream for kind 39993 listings
+pub const STREAM_JOBS: &str = "jobs";
+/// Cursor stream for NIP-09 deletions of listings
+pub const STREAM_DELETIONS: &str = "deletions";
+
+/// Resumed syncs re-ask for this much before the cursor, for events that
+/// reach a relay after newer ones (clock skew, slow propagation)
+pub const RESUME_MARGIN: Duration = Duration::from_secs(300);
+
+/// How far one stream on one relay has been synced
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SyncCursor {
+    /// Everything from here up to the time of the last sync is stored
+    pub newest: Option<Timestamp>,
+    /// Backfill has reached back to here
+    pub oldest: Option<Timestamp>,
+    /// Backfill reached the relay's oldest event
+    pub backfilled: bool,
+}
+
+/// What syncing did with one event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Applied {
+    Listing(Ingested),
+    /// Deletion request and the number of stored listings it removed
+    Deletion(usize),
+}
+
+/// Running totals for a sync
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SyncStats {
+    pub inserted: usize,
+    pub replaced: usize,
+    pub unchanged: usize,
+    pub deleted: usize,
+    pub skipped: usize,
+}
+
+impl SyncStats {
+    fn record(&mut self, applied: Option<Applied>) {
+        match applied {
+            Some(Applied::Listing(Ingested::Inserted)) => self.inserted += 1,
+            Some(Applied::Listing(Ingested::Replaced)) => self.replaced += 1,
+            Some(Applied::Listing(Ingested::Unchanged)) => self.unchanged += 1,
+            Some(Applied::Deletion(removed)) => self.deleted += removed,
+            Some(Applied::Listing(Ingested::Deleted | Ingested::NotAJob)) | None => self.skipped += 1,
+        }
+    }
+
+    fn add(&mut self, other: SyncStats) {
+        self.inserted += other.inserted;
+        self.replaced += other.replaced;
+        self.unchanged += other.unchanged;
+        self.deleted += other.deleted;
+        self.skipped += other.skipped;
+    }
+}
+
+// Authors per filter when asking for their deletions
+const AUTHORS_PER_FILTER: usize = 250;
+
+/// Filter for one cursor stream
+pub fn stream_filter(stream: &str) -> Filter {
+    match stream {
+        STREAM_DELETIONS => Filter::new()
+            .kind(Kind::EventDeletion)
+            .custom_tag(SingleLetterTag::lowercase(Alphabet::K), KIND_JOB_LISTING.to_string()),
+        _ => JobsFilter::new().build(),
+    }
+}
+
+/// Every filter for one cursor stream. NIP-09 doesn't require the `k` tag,
+/// so deletions are also asked for by the authors of stored listings.
+pub fn stream_filters(store: &JobStore, stream: &str) -> Result<Vec<Filter>> {
+    let mut filters = vec![stream_filter(stream)];
+    if stream == STREAM_DELETIONS {
+        let authors = store.listing_authors()?;
+        filters.extend(
+            authors
+                .chunks(AUTHORS_PER_FILTER)
+                .map(|chunk| Filter::new().kind(Kind::EventDeletion).authors(chunk.iter().copied())),
+        );
+    }
+    Ok(filters)
+}
+
+/// Syncs a `JobStore` from the relays of a `Client`
+#[derive(Debug, Clone)]
+pub struct Syncer {
+    page_size: usize,
+    timeout: Duration,
+    backfill: bool,
+}
+
+impl Default for Syncer {
+    fn default() -> Self {
+        Self {
+            page_size: 500,
+            timeout: Duration::from_secs(10),
+            backfill: true,
+        }
+    }
+}
+
+impl Syncer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Events requested per page
+    pub fn page_size(mut self, page_size: usize) -> Self {
+        self.page_size = page_size.max(1);
+        self
+    }
+
+    /// Wait per page for the relay to finish (EOSE)
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Whether to page back through history older than the first sync
+    pub fn backfill(mut self, backfill: bool) -> Self {
+        self.backfill = backfill;
+        self
+    }
+
+    /// Catch up on everything newer than the saved cursor, then (unless
+    /// disabled) continue the backfill of older history
+    pub async fn catch_up(&self, client: &Client, relay: &RelayUrl, store: &mut JobStore) -> Result<SyncStats> {
+        let mut stats = SyncStats::default();
+        // Listings first, so their authors' deletions are asked for too
+        for stream in [STREAM_JOBS, STREAM_DELETIONS] {
+            let filters = stream_filters(store, stream)?;
+            let mut cursor = store.sync_cursor(relay, stream)?;
+
+            // Newer than the last sync; the cursor only moves once the gap is closed
+            if let Some(since) = cursor.newest {
+                let mut newest = cursor.newest;
+                for filter in &filters {
+                    let (page_stats, filter_newest, _) = self
+                        .paginate(client, relay, store, filter, Some(since - RESUME_MARGIN), None, |_, _| Ok(()))
+                        .await?;
+                    stats.add(page_stats);
+                    newest = newest.max(filter_newest);
+                }
+                cursor.newest = newest;
+                store.save_sync_cursor(relay, stream, &cursor)?;
+            }
+
+            if !self.backfill || cursor.backfilled {
+                continue;
+            }
+            // Each page's progress is only saved with a single filter; with
+            // several, an interrupted backfill restarts from the saved cursor
+            let resumable = filters.len() == 1;
+            let (mut newest, mut oldest) = (None, None);
+            for filter in &filters {
+                let (page_stats, filter_newest, filter_oldest) = self
+                    .paginate(client, relay, store, filter, None, cursor.oldest, |store, page_cursor: SyncCursor| {
+                        if !resumable {
+                            return Ok(());
+                        }
+                        // Saved per page so an interrupted backfill resumes where it stopped
+                        let saved = SyncCursor {
+                            newest: cursor.newest.or(page_cursor.newest),
+                            oldest: page_cursor.oldest.or(cursor.oldest),
+                            backfilled: false,
+                        };
+                        store.save_sync_cursor(relay, stream, &saved)
+                    })
+                    .await?;
+                stats.add(page_stats);
+                newest = newest.max(filter_newest);
+                oldest = match (oldest, filter_oldest) {
+                    (Some(a), Some(b)) => Some(std::cmp::min(a, b)),
+                    (a, b) => a.or(b),
+                };
+            }
+            cursor = SyncCursor {
+                newest: cursor.newest.or(newest),
+                oldest: oldest.or(cursor.oldest),
+                backfilled: true,
+            };
+            store.save_sync_cursor(relay, stream, &cursor)?;
+        }
+        Ok(stats)
+    }
+
+    /// Catch up on every relay, then apply live events until `shutdown`
+    /// completes. `on_event` sees each applied event as it arrives.
+    pub async fn run<F, E>(&self, client: &Client, store: &mut JobStore, shutdown: F, mut on_event: E) -> Result<SyncStats>
+    where
+        F: Future<Output = ()>,
+        E: FnMut(&RelayUrl, &Event, Applied),
+    {
+        let relays: Vec<RelayUrl> = client.relays().await.into_keys().collect();
+        let mut stats = SyncStats::default();
+        for relay in &relays {
+            stats.add(self.catch_up(client, relay, store).await?);
+        }
+
+        // Listen before subscribing so nothing falls between the two
+        let mut notifications = client.notifications();
+        let mut subscriptions = HashSet::new();
+        for relay in &relays {
+            // A deletion can reach the relay after newer events, so with none
+            // synced yet deletions are followed from the first synced listing
+            let jobs = store.sync_cursor(relay, STREAM_JOBS)?;
+            let jobs_start = jobs.oldest.or(jobs.newest);
+            for stream in [STREAM_JOBS, STREAM_DELETIONS] {
+                // Nothing synced yet (empty relay, or no backfill): only new events
+                let since = match store.sync_cursor(relay, stream)?.newest {
+                    Some(newest) => newest - RESUME_MARGIN,
+                    None if stream == STREAM_DELETIONS => jobs_start.unwrap_or_else(Timestamp::now),
+                    None => Timestamp::now(),
+                };
+                for filter in stream_filters(store, stream)? {
+                    if let Ok(output) = client.subscribe_to([relay.clone()], filter.since(since), None).await {
+                        subscriptions.insert(output.val);
+                    }
+                }
+            }
+        }
+
+        // Authors whose deletions are already followed
+        let mut authors: HashSet<PublicKey> = store.listing_authors()?.into_iter().collect();
+        tokio::pin!(shutdown);
+        loop {
+            let notification = tokio::select! {
+                _ = &mut shutdown => break,
+                notification = notifications.recv() => notification,
+            };
+            let (relay_url, subscription_id, event) = match notification {
+                // Every copy, so each relay's cursor advances
+                Ok(RelayPoolNotification::Message {
+                    relay_url,
+                    message: RelayMessage::Event { subscription_id, event },
+                }) => (relay_url, subscription_id.into_owned(), event.into_owned()),
+                Ok(RelayPoolNotification::Shutdown) => break,
+                Ok(_) | Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(_) => break,
+            };
+            if !subscriptions.contains(&subscription_id) {
+                continue;
+            }
+            let applied = apply(store, &event);
+            stats.record(applied);
+            if let Some(applied) = applied {
+                let stream = if event.kind == Kind::EventDeletion { STREAM_DELETIONS } else { STREAM_JOBS };
+                let mut cursor = store.sync_cursor(&relay_url, stream)?;
+                let created_at = not_after_now(event.created_at);
+                if cursor.newest.is_none_or(|newest| created_at > newest) {
+                    cursor.newest = Some(created_at);
+                    store.save_sync_cursor(&relay_url, stream, &cursor)?;
+                }
+                // A new author's deletions may lack the `k` tag too
+                if applied == Applied::Listing(Ingested::Inserted) && authors.insert(event.pubkey) {
+                    let filter = Filter::new()
+                        .kind(Kind::EventDeletion)
+                        .author(event.pubkey)
+                        .since(event.created_at);
+                    for relay in &relays {
+                        if let Ok(output) = client.subscribe_to([relay.clone()], filter.clone(), None).await {
+                            subscriptions.insert(output.val);
+                        }
+                    }
+                }
+                on_event(&relay_url, &event, applied);
+            }
+        }
+
+        client.unsubscribe_all().await;
+        Ok(stats)
+    }
+
+    /// Page backwards from `until` (or now) down to `since` (or the
+    /// relay's oldest event), applying every event. Returns the stats and
+    /// the newest and oldest `created_at` seen.
+    #[allow(clippy::too_many_arguments)]
+    async fn paginate<P>(
+        &self,
+        client: &Client,
+        relay: &RelayUrl,
+        store: &mut JobStore,
+        filter: &Filter,
+        since: Option<Timestamp>,
+        mut until: Option<Timestamp>,
+        mut on_page: P,
+    ) -> Result<(SyncStats, Option<Timestamp>, Option<Timestamp>)>
+    where
+        P: FnMut(&mut JobStore, SyncCursor) -> Result<()>,
+    {
+        let mut stats = SyncStats::default();
+        let (mut newest, mut oldest): (Option<Timestamp>, Option<Timestamp>) = (None, None);
+        loop {
+            let mut filter = filter.clone().limit(self.page_size);
+            if let Some(since) = since {
+                filter = filter.since(since);
+            }
+            if let Some(until) = until {
+                filter = filter.until(until);
+            }
+            let events = client.fetch_events_from([relay.clone()], filter, self.timeout).await?;
+            let Some(page_oldest) = events.iter().map(|e| e.created_at).min() else {
+                break;
+            };
+            for event in events.iter() {
+                stats.record(apply(store, event));
+            }
+            newest = newest.max(events.iter().map(|e| not_after_now(e.created_at)).max());
+            oldest = Some(oldest.map_or(page_oldest, |o| o.min(page_oldest)));
+            on_page(store, SyncCursor { newest, oldest, backfilled: false })?;
+
+            // `until` is inclusive: re-ask for the oldest second unless the
+            // whole page was that second (or the relay ignored `until`), then
+            // step past it so `until` always decreases
+            let next = match until {
+                Some(until) if page_oldest >= until => until - 1,
+                _ => page_oldest,
+            };
+            if since.is_some_and(|since| next < since) || next.as_secs() == 0 {
+                break;
+            }
+            until = Some(next);
+        }
+        Ok((stats, newest, oldest))
+    }
+}
+
+/// Cursors never move past the present: a future-dated event would
+/// otherwise make every resume skip the events published until then
+fn not_after_now(created_at: Timestamp) -> Timestamp {
+    created_at.min(Timestamp::now())
+}
+
+/// Apply a listing or deletion to the store; None if it was rejected
+fn apply(store: &mut JobStore, event: &Event) -> Option<Applied> {
+    if event.kind == Kind::EventDeletion {
+        store.apply_deletion(event).ok().map(Applied::Deletion)
+    } else {
+        store.ingest(event).ok().map(Applied::Listing)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::address::JobAddress;
+    use crate::lifecycle;
+    use crate::test_fixtures::listing;
+    use crate::test_relay::TestRelay;
+
+    async fn client(relay: &TestRelay) -> Client {
+        let client = Client::default();
+        client.add_relay(relay.url()).await.unwrap();
+        client.connect().await;
+        client
+    }
+
+    #[tokio::test]
+    async fn test_backfill_pages_and_resume() {
+        let relay = TestRelay::start().await;
+        let keys = Keys::generate();
+        // Two listings share a second across a page boundary
+        for (i, created_at) in [1_000, 2_000, 2_000, 3_000, 4_000].into_iter().enumerate() {
+            relay.insert(listing(&format!("job-{}", i), "Rust Dev", &keys, created_at));
+        }
+        let withdrawn = listing("job-gone", "Withdrawn", &keys, 500);
+        relay.insert(withdrawn.clone());
+        relay.insert(lifecycle::deletion_request(&withdrawn, None, &keys).unwrap());
+
+        let mut store = JobStore::open_in_memory().unwrap();
+        let syncer = Syncer::new().page_size(2).timeout(Duration::from_secs(2));
+        let stats = syncer.catch_up(&client(&relay).await, &relay.url(), &mut store).await.unwrap();
+        assert_eq!(store.len().unwrap(), 5);
+        assert_eq!(stats.deleted, 1);
+        let cursor = store.sync_cursor(&relay.url(), STREAM_JOBS).unwrap();
+        assert_eq!(cursor.newest, Some(Timestamp::from(4_000)));
+        assert!(cursor.backfilled);
+
+        // After a restart only the gap is fetched
+        relay.insert(listing("job-new", "Go Dev", &keys, 5_000));
+        let stats = syncer.catch_up(&client(&relay).await, &relay.url(), &mut store).await.unwrap();
+        assert_eq!((stats.inserted, stats.replaced), (1, 0));
+        assert_eq!(store.len().unwrap(), 6);
+        assert_eq!(store.sync_cursor(&relay.url(), STREAM_JOBS).unwrap().newest, Some(Timestamp::from(5_000)));
+    }
+
+    #[tokio::test]
+    async fn test_live_follows_deletions_by_new_authors() {
+        let relay = TestRelay::start().await;
+        let keys = Keys::generate();
+        let mut store = JobStore::open_in_memory().unwrap();
+        let sync_client = client(&relay).await;
+        let publisher = client(&relay).await;
+        let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+
+        let now = Timestamp::now().as_secs();
+        let posted = listing("job-1", "Rust Dev", &keys, now);
+        // No `k` tag, so only a stream by author sees it
+        let deletion = EventBuilder::delete(EventDeletionRequest::new().id(posted.id))
+            .custom_created_at(Timestamp::from(now + 1))
+            .sign_with_keys(&keys)
+            .unwrap();
+        let live = async {
+            // Nothing stored yet: listings and deletions by `k` only
+            relay.wait_for_streams(2).await;
+            publisher.send_event(&posted).await.unwrap();
+            relay.wait_for_streams(3).await;
+            publisher.send_event(&deletion).await.unwrap();
+        };
+        let syncer = Syncer::new().timeout(Duration::from_secs(2));
+        let shutdown = async {
+            let _ = tokio::time::timeout(Duration::from_secs(10), stopped).await;
+        };
+        let mut stop = Some(stop);
+        let run = syncer.run(&sync_client, &mut store, shutdown, |_, _, applied| {
+            if matches!(applied, Applied::Deletion(_))
+                && let Some(stop) = stop.take()
+            {
+                let _ = stop.send(());
+            }
+        });
+        let (stats, _) = tokio::join!(run, live);
+        let stats = stats.unwrap();
+
+        assert_eq!((stats.inserted, stats.deleted), (1, 1));
+        assert_eq!(store.len().unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_backfill_ends_when_the_relay_ignores_until() {
+        let relay = TestRelay::start().await;
+        relay.ignore_until();
+        let keys = Keys::generate();
+        for created_at in 1..=4 {
+            relay.insert(listing(&format!("job-{}", created_at), "Rust Dev", &keys, created_at));
+        }
+
+        // Every page is the newest two listings again
+        let mut store = JobStore::open_in_memory().unwrap();
+        let syncer = Syncer::new().page_size(2).timeout(Duration::from_secs(2));
+        let sync_client = client(&relay).await;
+        tokio::time::timeout(Duration::from_secs(20), syncer.catch_up(&sync_client, &relay.url(), &mut store))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(store.len().unwrap(), 2);
+        assert!(store.sync_cursor(&relay.url(), STREAM_JOBS).unwrap().backfilled);
+    }
+
+    #[tokio::test]
+    async fn test_future_dated_events_do_not_move_the_cursor_ahead() {
+        let relay = TestRelay::start().await;
+        let keys = Keys::generate();
+        let now = Timestamp::now().as_secs();
+        relay.insert(listing("job-future", "Time Traveller", &keys, now + 86_400));
+
+        let mut store = JobStore::open_in_memory().unwrap();
+        let syncer = Syncer::new().timeout(Duration::from_secs(2));
+        syncer.catch_up(&client(&relay).await, &relay.url(), &mut store).await.unwrap();
+        let newest = store.sync_cursor(&relay.url(), STREAM_JOBS).unwrap().newest.unwrap();
+        assert!(newest <= Timestamp::now());
+
+        // Published after the first sync, but dated just before it
+        relay.insert(listing("job-late", "Rust Dev", &keys, now - 60));
+        let stats = syncer.catch_up(&client(&relay).await, &relay.url(), &mut store).await.unwrap();
+        assert_eq!(stats.inserted, 1);
+        assert_eq!(store.len().unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_live_updates_replacements_and_deletions() {
+        let relay = TestRelay::start().await;
+        let keys = Keys::generate();
+        let original = listing("job-1", "Rust Dev", &keys, 1_000);
+        relay.insert(original.clone());
+
+        let mut store = JobStore::open_in_memory().unwrap();
+        let sync_client = client(&relay).await;
+        let publisher = client(&relay).await;
+        let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+        let mut seen = Vec::new();
+
+        let updated = listing("job-1", "Senior Rust Dev", &keys, 2_000);
+        let other = listing("job-2", "Go Dev", &keys, 2_500);
+        // Dated long before the sync starts and without a `k` tag, so only
+        // the stream by listing author (from the first listing on) sees it
+        let deletion = EventBuilder::delete(EventDeletionRequest::new().id(other.id))
+            .custom_created_at(Timestamp::from(3_000))
+            .sign_with_keys(&keys)
+            .unwrap();
+        let live = async {
+            // Catch-up done; listings, deletions by `k` and by author followed
+            relay.wait_for_streams(3).await;
+            for event in [&updated, &other, &deletion] {
+                publisher.send_event(event).await.unwrap();
+            }
+        };
+        let syncer = Syncer::new().timeout(Duration::from_secs(2));
+        let shutdown = async {
+            let _ = tokio::time::timeout(Duration::from_secs(10), stopped).await;
+        };
+        let mut stop = Some(stop);
+        let run = syncer.run(&sync_client, &mut store, shutdown, |_, event, applied| {
+            seen.push((event.id, applied));
+            // The deletion is the last event published
+            if matches!(applied, Applied::Deletion(_))
+                && let Some(stop) = stop.take()
+            {
+                let _ = stop.send(());
+            }
+        });
+        let (stats, _) = tokio::join!(run, live);
+        let stats = stats.unwrap();
+
+        assert_eq!((stats.inserted, stats.replaced, stats.deleted), (2, 1, 1));
+        assert!(seen.contains(&(updated.id, Applied::Listing(Ingested::Replaced))));
+        assert!(seen.contains(&(deletion.id, Applied::Deletion(1))));
+        let address = JobAddress::from_event(&original).unwrap();
+        assert_eq!(store.get(&address).unwrap().unwrap().job.title, "Senior Rust Dev");
+        assert_eq!(store.len().unwrap(), 1);
+        assert_eq!(
+            store.sync_cursor(&relay.url(), STREAM_DELETIONS).unwrap().newest,
+            Some(deletion.created_at)
+        );
+    }
+}
@@ -0,0 +1,50 @@
+// src/test_fixtures.rs
+// Sample listings shared by the unit tests, so modules don't each keep a
+// copy of the same builder call
+
+use crate::types::{JobListing, JobListingBuilder};
+use nostr_sdk::prelude::*;
+
+/// The sample listing's builder with `id` as its `d` tag, for tests that
+/// add fields
+pub(crate) fn job_builder(id: &str, title: &str) -> JobListingBuilder {
+    JobListing::builder(
+        id.to_string(),
+        title.to_string(),
+        "Build decentralized systems".to_string(),
+        "Acme Corp".to_string(),
+        "2025-01-15".to_string(),
+        "https://acme.com/apply".to_string(),
+    )
+}
+
+/// "Senior Rust Developer" at Acme Corp, nothing optional set
+pub(crate) fn sample_job(id: &str) -> JobListing {
+    job_builder(id, "Senior Rust Developer").build().unwrap()
+}
+
+/// Builder for an image classification task; each test picks the worker
+/// type and agent requirements
+pub(crate) fn task_builder() -> JobListingBuilder {
+    JobListing::builder(
+        "task-001".to_string(),
+        "Image Classification Task".to_string(),
+        "Classify product images".to_string(),
+        "TaskPlatform".to_string(),
+        "2025-01-15".to_string(),
+        "https://api.taskplatform.com/apply".to_string(),
+    )
+}
+
+/// Signed sample listing dated `created_at`, so tests control which
+/// version of an address is newest
+pub(crate) fn listing(id: &str, title: &str, keys: &Keys, created_at: u64) -> Event {
+    job_builder(id, title)
+        .build()
+        .unwrap()
+        .event_builder()
+        .unwrap()
+        .custom_created_at(Timestamp::from(created_at))
+        .sign_with_keys(keys)
+        .unwrap()
+}
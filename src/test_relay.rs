@@ -0,0 +1,252 @@
+// src/test_relay.rs
+// Minimal in-process NIP-01 relay for tests: stores events (replaceable and
+// addressable kinds keep the newest), answers REQ with stored events then
+// EOSE, pushes new events to open subscriptions, and answers NIP-77
+// negentropy reconciliation
+
+use futures_util::{SinkExt, StreamExt};
+use negentropy::{Id, Negentropy, NegentropyStorageVector};
+use nostr_sdk::prelude::*;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+use tokio::net::TcpListener;
+use tokio::sync::broadcast;
+use tokio_tungstenite::tungstenite::Message;
+
+#[derive(Default)]
+struct State {
+    events: Vec<Event>,
+    /// `OK false` messages for the next incoming events
+    rejections: VecDeque<String>,
+    /// Open subscriptions without a `limit` (live follows, not page fetches)
+    streaming: HashSet<SubscriptionId>,
+    /// Answer REQs as if their filters had no `until`
+    ignore_until: bool,
+}
+
+pub(crate) struct TestRelay {
+    url: RelayUrl,
+    state: Arc<Mutex<State>>,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl TestRelay {
+    pub(crate) async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = RelayUrl::parse(&format!("ws://{}", listener.local_addr().unwrap())).unwrap();
+        let state = Arc::new(Mutex::new(State::default()));
+        let (live, _) = broadcast::channel::<Event>(1024);
+
+        let shared = state.clone();
+        let task = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let (state, live) = (shared.clone(), live.clone());
+                tokio::spawn(async move {
+                    if let Ok(ws) = tokio_tungstenite::accept_async(stream).await {
+                        serve(ws, state, live).await;
+                    }
+                });
+            }
+        });
+        Self { url, state, task }
+    }
+
+    pub(crate) fn url(&self) -> RelayUrl {
+        self.url.clone()
+    }
+
+    pub(crate) fn events(&self) -> Vec<Event> {
+        self.state.lock().unwrap().events.clone()
+    }
+
+    /// Answer the next `times` events with `OK false` and `message`
+    pub(crate) fn reject_next(&self, message: &str, times: usize) {
+        let rejections = &mut self.state.lock().unwrap().rejections;
+        rejections.extend(std::iter::repeat_n(message.to_string(), times.min(1024)));
+    }
+
+    /// Wait until `count` subscriptions without a `limit` are open
+    pub(crate) async fn wait_for_streams(&self, count: usize) {
+        while self.state.lock().unwrap().streaming.len() < count {
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+    }
+
+    /// Misbehave like relays that don't support `until`
+    pub(crate) fn ignore_until(&self) {
+        self.state.lock().unwrap().ignore_until = true;
+    }
+
+    /// Preload events without going through a client
+    pub(crate) fn insert(&self, event: Event) {
+        store(&mut self.state.lock().unwrap(), event);
+    }
+}
+
+impl Drop for TestRelay {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+fn store(state: &mut State, event: Event) -> bool {
+    let replaces = |old: &Event| {
+        old.pubkey == event.pubkey
+            && old.kind == event.kind
+            && (event.kind.is_replaceable()
+                || (event.kind.is_addressable() && old.tags.identifier() == event.tags.identifier()))
+    };
+    if state.events.iter().any(|old| old.id == event.id || (replaces(old) && old.created_at > event.created_at)) {
+        return false;
+    }
+    state.events.retain(|old| !replaces(old));
+    state.events.push(event);
+    true
+}
+
+fn matching(state: &State, filters: &[Filter]) -> Vec<Event> {
+    let mut out = Vec::new();
+    for filter in filters {
+        let mut filter = filter.clone();
+        if state.ignore_until {
+            filter.until = None;
+        }
+        let mut hits: Vec<&Event> = state
+            .events
+            .iter()
+            .filter(|e| filter.match_event(e, MatchEventOptions::new()))
+            .collect();
+        hits.sort_by_key(|e| std::cmp::Reverse(e.created_at));
+        if let Some(limit) = filter.limit {
+            hits.truncate(limit);
+        }
+        out.extend(hits.into_iter().cloned());
+    }
+    out.sort_by_key(|e| e.id);
+    out.dedup_by_key(|e| e.id);
+    out
+}
+
+async fn serve<S>(ws: tokio_tungstenite::WebSocketStream<S>, state: Arc<Mutex<State>>, live: broadcast::Sender<Event>)
+where
+    S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin,
+{
+    let (mut sink, mut stream) = ws.split();
+    let mut subscriptions: HashMap<SubscriptionId, Vec<Filter>> = HashMap::new();
+    let mut reconciliations: HashMap<SubscriptionId, Negentropy<NegentropyStorageVector>> = HashMap::new();
+    let mut feed = live.subscribe();
+
+    loop {
+        let reply: Vec<RelayMessage> = tokio::select! {
+            incoming = stream.next() => {
+                let Some(Ok(Message::Text(text))) = incoming else {
+                    match incoming {
+                        Some(Ok(_)) => continue,
+                        _ => return,
+                    }
+                };
+                let Ok(message) = ClientMessage::from_json(text.as_str()) else {
+                    continue;
+                };
+                match message {
+                    ClientMessage::Event(event) => {
+                        let event = event.into_owned();
+                        let verdict = {
+                            let mut state = state.lock().unwrap();
+                            match (state.rejections.pop_front(), event.verify()) {
+                                (Some(message), _) => Err(message),
+                                (None, Ok(())) => Ok(store(&mut state, event.clone())),
+                                (None, Err(_)) => Err("invalid: bad signature".to_string()),
+                            }
+                        };
+                        match verdict {
+                            Ok(stored) => {
+                                if stored {
+                                    let _ = live.send(event.clone());
+                                }
+                                vec![RelayMessage::ok(event.id, true, if stored { "" } else { "duplicate:" })]
+                            }
+                            Err(reason) => vec![RelayMessage::ok(event.id, false, reason)],
+                        }
+                    }
+                    ClientMessage::Req { subscription_id, filters } => {
+                        let id = subscription_id.into_owned();
+                        let filters: Vec<Filter> = filters.into_iter().map(|f| f.into_owned()).collect();
+                        let mut out: Vec<RelayMessage> = matching(&state.lock().unwrap(), &filters)
+                            .into_iter()
+                            .map(|e| RelayMessage::event(id.clone(), e))
+                            .collect();
+                        out.push(RelayMessage::eose(id.clone()));
+                        if filters.iter().all(|f| f.limit.is_none()) {
+                            state.lock().unwrap().streaming.insert(id.clone());
+                        }
+                        subscriptions.insert(id, filters);
+                        out
+                    }
+                    ClientMessage::Close(id) => {
+                        state.lock().unwrap().streaming.remove(id.as_ref());
+                        subscriptions.remove(&id);
+                        vec![]
+                    }
+                    ClientMessage::NegOpen { subscription_id, filter, initial_message, .. } => {
+                        let id = subscription_id.into_owned();
+                        let events = matching(&state.lock().unwrap(), &[filter.into_owned()]);
+                        let mut negentropy = negentropy_for(&events);
+                        let reply = reconcile(&mut negentropy, &id, &initial_message);
+                        reconciliations.insert(id, negentropy);
+                        vec![reply]
+                    }
+                    ClientMessage::NegMsg { subscription_id, message } => {
+                        match reconciliations.get_mut(subscription_id.as_ref()) {
+                            Some(negentropy) => vec![reconcile(negentropy, &subscription_id, &message)],
+                            None => vec![RelayMessage::NegErr {
+                                subscription_id,
+                                message: "closed: unknown subscription".into(),
+                            }],
+                        }
+                    }
+                    ClientMessage::NegClose { subscription_id } => {
+                        reconciliations.remove(subscription_id.as_ref());
+                        vec![]
+                    }
+                    _ => vec![RelayMessage::notice("unsupported")],
+                }
+            }
+            Ok(event) = feed.recv() => subscriptions
+                .iter()
+                .filter(|(_, filters)| filters.iter().any(|f| f.match_event(&event, MatchEventOptions::new())))
+                .map(|(id, _)| RelayMessage::event(id.clone(), event.clone()))
+                .collect(),
+        };
+
+        for message in reply {
+            if sink.send(Message::text(message.as_json())).await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+fn negentropy_for(events: &[Event]) -> Negentropy<'static, NegentropyStorageVector> {
+    let mut storage = NegentropyStorageVector::with_capacity(events.len());
+    for event in events {
+        let id = Id::from_slice(event.id.as_bytes()).unwrap();
+        storage.insert(event.created_at.as_secs(), id).unwrap();
+    }
+    storage.seal().unwrap();
+    Negentropy::owned(storage, 0).unwrap()
+}
+
+fn reconcile(
+    negentropy: &mut Negentropy<NegentropyStorageVector>,
+    id: &SubscriptionId,
+    message: &str,
+) -> RelayMessage<'static> {
+    let subscription_id = std::borrow::Cow::Owned(id.clone());
+    match hex::decode(message).map_err(|e| e.to_string()).and_then(|query| {
+        negentropy.reconcile(&query).map_err(|e| e.to_string())
+    }) {
+        Ok(reply) => RelayMessage::NegMsg { subscription_id, message: hex::encode(reply).into() },
+        Err(e) => RelayMessage::NegErr { subscription_id, message: format!("error: {}", e).into() },
+    }
+}
@@ -0,0 +1,214 @@
+// src/trust.rs
+// Employer impersonation checks
+// A listing's declared employer (`nostrEmployerPubkey` / `employer-pubkey`)
+// is self-asserted; compare it with the key that actually signed the event
+
+use crate::types::JobListing;
+use anyhow::{anyhow, Result};
+use nostr_sdk::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+/// Who signed a listing relative to the employer it names
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrustVerdict {
+    SignerIsEmployer,                   // Signed by the declared employer
+    Delegated { employer: PublicKey },  // Signed by a key the employer authorised
+    Mismatch { claimed: PublicKey },    // Names an employer that didn't sign it
+    Undeclared,                         // Names no employer or org key: nothing to check
+}
+
+impl TrustVerdict {
+    /// Signed by the employer it names, or on its behalf
+    pub fn is_trusted(&self) -> bool {
+        matches!(self, TrustVerdict::SignerIsEmployer | TrustVerdict::Delegated { .. })
+    }
+}
+
+/// Keys allowed to post on an employer's behalf
+#[derive(Debug, Clone, Default)]
+pub struct Delegations {
+    signers: HashMap<PublicKey, HashSet<PublicKey>>,
+}
+
+impl Delegations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn allow(&mut self, employer: PublicKey, signer: PublicKey) -> &mut Self {
+        self.signers.entry(employer).or_default().insert(signer);
+        self
+    }
+
+    pub fn is_delegate(&self, employer: &PublicKey, signer: &PublicKey) -> bool {
+        self.signers.get(employer).is_some_and(|s| s.contains(signer))
+    }
+}
+
+/// Parse an npub, `nostr:npub` URI or hex pubkey
+pub fn normalize_pubkey(value: &str) -> Result<PublicKey> {
+    let value = value.trim();
+    let value = value.strip_prefix("nostr:").unwrap_or(value);
+    PublicKey::parse(value).map_err(|e| anyhow!("Invalid employer pubkey {:?}: {}", value, e))
+}
+
+impl JobListing {
+    /// Parse a listing, rejecting it unless the event id and signature
+    /// verify and the declared employer (content and tag) is well formed
+    /// and consistent. The declared employer is normalised to hex.
+    pub fn from_nostr_event_strict(event: &Event, delegations: &Delegations) -> Result<(Self, TrustVerdict)> {
+        event.verify().map_err(|e| anyhow!("Invalid event: {}", e))?;
+        let mut job = Self::from_nostr_event(event)?;
+        let verdict = job.trust_verdict(event, delegations)?;
+        if let Some(employer) = declared_employer(&job, event)? {
+            job.nostr_employer_pubkey = Some(employer.to_hex());
+        }
+        if let Some(org) = job.hiring_organization.nostr_pubkey.as_deref() {
+            job.hiring_organization.nostr_pubkey = Some(normalize_pubkey(org)?.to_hex());
+        }
+        Ok((job, verdict))
+    }
+
+    /// Compare the declared employer and org (`hiringOrganization.nostrPubkey`)
+    /// with the event's signer. Each must be the signer or have authorised it;
+    /// a recruiter's listing for an org is credited to the org.
+    pub fn trust_verdict(&self, event: &Event, delegations: &Delegations) -> Result<TrustVerdict> {
+        let org = self.hiring_organization.nostr_pubkey.as_deref().map(normalize_pubkey).transpose()?;
+        let declared = [declared_employer(self, event)?, org];
+        if declared.iter().all(Option::is_none) {
+            return Ok(TrustVerdict::Undeclared);
+        }
+        let mut verdict = TrustVerdict::SignerIsEmployer;
+        for claimed in declared.into_iter().flatten() {
+            if claimed == event.pubkey {
+                continue;
+            }
+            if !delegations.is_delegate(&claimed, &event.pubkey) {
+                return Ok(TrustVerdict::Mismatch { claimed });
+            }
+            verdict = TrustVerdict::Delegated { employer: claimed };
+        }
+        Ok(verdict)
+    }
+}
+
+/// Employer named in the content and/or `employer-pubkey` tag; both must agree
+fn declared_employer(job: &JobListing, event: &Event) -> Result<Option<PublicKey>> {
+    let from_content = job.nostr_employer_pubkey.as_deref().map(normalize_pubkey).transpose()?;
+    let from_tag = event
+        .tags
+        .iter()
+        .find_map(|t| match t.as_slice() {
+            [name, value, ..] if name == "employer-pubkey" => Some(value.clone()),
+            _ => None,
+        })
+        .as_deref()
+        .map(normalize_pubkey)
+        .transpose()?;
+
+    match (from_content, from_tag) {
+        (Some(content), Some(tag)) if content != tag => {
+            Err(anyhow!("Content and tag declare different employers"))
+        }
+        (content, tag) => Ok(content.or(tag)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::sample_job;
+
+    fn job(employer: Option<String>) -> JobListing {
+        let mut job = sample_job("job-001");
+        job.nostr_employer_pubkey = employer;
+        job
+    }
+
+    #[test]
+    fn test_verdicts() {
+        let employer = Keys::generate();
+        let recruiter = Keys::generate();
+        let npub = employer.public_key().to_bech32().unwrap();
+        let mut delegations = Delegations::new();
+        delegations.allow(employer.public_key(), recruiter.public_key());
+
+        let own = job(Some(npub.clone())).to_nostr_event(&employer).unwrap();
+        let (decoded, verdict) = JobListing::from_nostr_event_strict(&own, &delegations).unwrap();
+        assert_eq!(verdict, TrustVerdict::SignerIsEmployer);
+        assert_eq!(decoded.nostr_employer_pubkey, Some(employer.public_key().to_hex()));
+
+        let undeclared = job(None).to_nostr_event(&recruiter).unwrap();
+        let verdict = JobListing::from_nostr_event_strict(&undeclared, &delegations).unwrap().1;
+        assert_eq!(verdict, TrustVerdict::Undeclared);
+        assert!(!verdict.is_trusted());
+
+        let delegated = job(Some(format!("nostr:{}", npub))).to_nostr_event(&recruiter).unwrap();
+        assert_eq!(
+            JobListing::from_nostr_event_strict(&delegated, &delegations).unwrap().1,
+            TrustVerdict::Delegated { employer: employer.public_key() }
+        );
+
+        let impostor = job(Some(employer.public_key().to_hex())).to_nostr_event(&Keys::generate()).unwrap();
+        let verdict = JobListing::from_nostr_event_strict(&impostor, &delegations).unwrap().1;
+        assert_eq!(verdict, TrustVerdict::Mismatch { claimed: employer.public_key() });
+        assert!(!verdict.is_trusted());
+    }
+
+    #[test]
+    fn test_strict_rejections() {
+        let keys = Keys::generate();
+        let delegations = Delegations::new();
+
+        let bad_key = job(Some("npub1notakey".to_string())).to_nostr_event(&keys).unwrap();
+        assert!(JobListing::from_nostr_event(&bad_key).is_ok());
+        assert!(JobListing::from_nostr_event_strict(&bad_key, &delegations).is_err());
+
+        // Tag and content disagree
+        let event = job(Some(keys.public_key().to_hex())).to_nostr_event(&keys).unwrap();
+        let other = Keys::generate().public_key().to_hex();
+        let tags = event.tags.iter().map(|t| match t.as_slice() {
+            [name, ..] if name == "employer-pubkey" => {
+                Tag::custom(TagKind::Custom("employer-pubkey".into()), vec![other.clone()])
+            }
+            _ => t.clone(),
+        });
+        let conflicting = EventBuilder::new(event.kind, event.content.clone())
+            .tags(tags)
+            .sign_with_keys(&keys)
+            .unwrap();
+        assert!(JobListing::from_nostr_event_strict(&conflicting, &delegations).is_err());
+
+        // Tampered content no longer matches the id and signature
+        let mut tampered = event.clone();
+        tampered.content = tampered.content.replace("Acme Corp", "Evil Corp");
+        assert!(JobListing::from_nostr_event_strict(&tampered, &delegations).is_err());
+    }
+
+    #[test]
+    fn test_org_recruiters() {
+        use crate::org::OrgMembership;
+
+        let org = Keys::generate();
+        let recruiter = Keys::generate();
+        let list = OrgMembership::new(org.public_key(), "Acme Corp".to_string()).recruiter(recruiter.public_key());
+        let delegations = Delegations::from_membership_events([&list.to_nostr_event(&org).unwrap()]);
+
+        // Recruiter names itself as employer and Acme as the org
+        let mut posting = job(Some(recruiter.public_key().to_bech32().unwrap()));
+        posting.hiring_organization.nostr_pubkey = Some(org.public_key().to_bech32().unwrap());
+        let event = posting.to_nostr_event(&recruiter).unwrap();
+        let (decoded, verdict) = JobListing::from_nostr_event_strict(&event, &delegations).unwrap();
+        assert_eq!(verdict, TrustVerdict::Delegated { employer: org.public_key() });
+        assert_eq!(decoded.hiring_organization.nostr_pubkey, Some(org.public_key().to_hex()));
+
+        // Unlisted keys can't post as Acme
+        let outsider = Keys::generate();
+        posting.nostr_employer_pubkey = None;
+        let event = posting.to_nostr_event(&outsider).unwrap();
+        assert_eq!(
+            JobListing::from_nostr_event_strict(&event, &delegations).unwrap().1,
+            TrustVerdict::Mismatch { claimed: org.public_key() }
+        );
+    }
+}
@@ -5,12 +5,13 @@
 // Schema.org-aligned job listing types for Nostr
 // Combines Schema.org JobPosting with Nostr-specific extensions
 
+use crate::payment::PaymentProof;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 /// Main job listing structure
 /// Follows Schema.org JobPosting with Nostr extensions for NIP-104
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct JobListing {
     // === Schema.org Core Fields ===
@@ -20,6 +21,13 @@ pub struct JobListing {
     #[serde(rename = "@type")]
     pub schema_type: String, // "JobPosting"
 
+    // === NIP-39993 content header ===
+    #[serde(rename = "type", default = "JobListing::default_content_type")]
+    pub content_type: String, // "job"
+
+    #[serde(default = "JobListing::default_version")]
+    pub version: u32,
+
     pub identifier: String, // Unique ID (maps to Nostr "d" tag)
     pub title: String,
     pub description: String, // Full markdown description
@@ -83,6 +91,12 @@ pub struct JobListing {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub nip05_verified: Option<String>, // Employer verification
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<JobStatus>, // Lifecycle status (absent = open)
+
+    #[serde(rename = "payment_proof", skip_serializing_if = "Option::is_none")]
+    pub payment_proof: Option<PaymentProof>, // Optional anti-spam payment
+
     // === Extensions ===
     #[serde(flatten)]
     pub extra: HashMap<String, serde_json::Value>,
@@ -99,14 +113,33 @@ pub enum EligibleWorkerType {
     AIAgent,
 }
 
+impl EligibleWorkerType {
+    /// NIP-39993 controlled vocabulary value (e.g. "AIAgent")
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EligibleWorkerType::Human => "Human",
+            EligibleWorkerType::AIAgent => "AIAgent",
+        }
+    }
+
+    /// Parse a tag value, ignoring case and separators
+    pub fn parse(s: &str) -> Option<Self> {
+        match vocab_key(s).as_str() {
+            "HUMAN" => Some(EligibleWorkerType::Human),
+            "AIAGENT" | "AI" => Some(EligibleWorkerType::AIAgent),
+            _ => None,
+        }
+    }
+}
+
 /// NIP-104: Structured capability requirements
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct CapabilityRequirement {
     pub name: String,
     pub level: CapabilityLevel,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 #[serde(rename_all = "lowercase")]
 pub enum CapabilityLevel {
     Basic,
@@ -115,8 +148,31 @@ pub enum CapabilityLevel {
     Expert,
 }
 
+impl CapabilityLevel {
+    /// NIP-39993 controlled vocabulary value (e.g. "Advanced")
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CapabilityLevel::Basic => "Basic",
+            CapabilityLevel::Intermediate => "Intermediate",
+            CapabilityLevel::Advanced => "Advanced",
+            CapabilityLevel::Expert => "Expert",
+        }
+    }
+
+    /// Parse a tag value, ignoring case
+    pub fn parse(s: &str) -> Option<Self> {
+        match vocab_key(s).as_str() {
+            "BASIC" => Some(CapabilityLevel::Basic),
+            "INTERMEDIATE" => Some(CapabilityLevel::Intermediate),
+            "ADVANCED" => Some(CapabilityLevel::Advanced),
+            "EXPERT" => Some(CapabilityLevel::Expert),
+            _ => None,
+        }
+    }
+}
+
 /// NIP-104: Performance requirements for AI agents
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct PerformanceRequirement {
     pub value: f64,
     pub unit: String,
@@ -132,6 +188,29 @@ pub enum InterfaceType {
     WebPortal,
 }
 
+impl InterfaceType {
+    /// NIP-39993 controlled vocabulary value (e.g. "WEB_PORTAL")
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            InterfaceType::Api => "API",
+            InterfaceType::Rpc => "RPC",
+            InterfaceType::Webhook => "WEBHOOK",
+            InterfaceType::WebPortal => "WEB_PORTAL",
+        }
+    }
+
+    /// Parse a tag value, ignoring case and separators
+    pub fn parse(s: &str) -> Option<Self> {
+        match vocab_key(s).as_str() {
+            "API" => Some(InterfaceType::Api),
+            "RPC" => Some(InterfaceType::Rpc),
+            "WEBHOOK" => Some(InterfaceType::Webhook),
+            "WEBPORTAL" => Some(InterfaceType::WebPortal),
+            _ => None,
+        }
+    }
+}
+
 /// NIP-104: Human oversight requirements
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
@@ -141,14 +220,67 @@ pub enum OversightRequirement {
     None,
 }
 
+impl OversightRequirement {
+    /// NIP-39993 controlled vocabulary value (e.g. "Required")
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OversightRequirement::Required => "Required",
+            OversightRequirement::Optional => "Optional",
+            OversightRequirement::None => "None",
+        }
+    }
+
+    /// Parse a tag value, ignoring case
+    pub fn parse(s: &str) -> Option<Self> {
+        match vocab_key(s).as_str() {
+            "REQUIRED" => Some(OversightRequirement::Required),
+            "OPTIONAL" => Some(OversightRequirement::Optional),
+            "NONE" => Some(OversightRequirement::None),
+            _ => None,
+        }
+    }
+}
+
+/// Listing lifecycle status
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    Open,
+    Paused,
+    Filled,
+    Closed,
+}
+
+impl JobStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobStatus::Open => "open",
+            JobStatus::Paused => "paused",
+            JobStatus::Filled => "filled",
+            JobStatus::Closed => "closed",
+        }
+    }
+
+    /// Parse a tag value, ignoring case
+    pub fn parse(s: &str) -> Option<Self> {
+        match vocab_key(s).as_str() {
+            "OPEN" => Some(JobStatus::Open),
+            "PAUSED" => Some(JobStatus::Paused),
+            "FILLED" => Some(JobStatus::Filled),
+            "CLOSED" => Some(JobStatus::Closed),
+            _ => None,
+        }
+    }
+}
+
 /// Translated job content
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct TranslatedJob {
     pub title: String,
     pub description: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct HiringOrganization {
     #[serde(rename = "@type")]
     pub schema_type: String, // "Organization"
@@ -163,9 +295,13 @@ pub struct HiringOrganization {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub same_as: Option<Vec<String>>,
+
+    /// Organisation key whose membership list authorises recruiters (npub or hex)
+    #[serde(rename = "nostrPubkey", skip_serializing_if = "Option::is_none")]
+    pub nostr_pubkey: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct JobLocation {
     #[serde(rename = "@type")]
     pub schema_type: String, // "Place"
@@ -174,7 +310,7 @@ pub struct JobLocation {
     pub address: Option<PostalAddress>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct PostalAddress {
     #[serde(rename = "@type")]
     pub schema_type: String, // "PostalAddress"
@@ -210,6 +346,42 @@ pub enum EmploymentType {
     Other,
 }
 
+impl EmploymentType {
+    /// NIP-39993 controlled vocabulary value (e.g. "FULL_TIME")
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EmploymentType::FullTime => "FULL_TIME",
+            EmploymentType::PartTime => "PART_TIME",
+            EmploymentType::Contractor => "CONTRACTOR",
+            EmploymentType::Temporary => "TEMPORARY",
+            EmploymentType::Intern => "INTERN",
+            EmploymentType::Volunteer => "VOLUNTEER",
+            EmploymentType::PerDiem => "PER_DIEM",
+            EmploymentType::TaskBased => "TASK_BASED",
+            EmploymentType::MicroTask => "MICRO_TASK",
+            EmploymentType::Other => "OTHER",
+        }
+    }
+
+    /// Parse a tag value, ignoring case and separators
+    /// (accepts both "FULL_TIME" and legacy "FullTime")
+    pub fn parse(s: &str) -> Option<Self> {
+        match vocab_key(s).as_str() {
+            "FULLTIME" => Some(EmploymentType::FullTime),
+            "PARTTIME" => Some(EmploymentType::PartTime),
+            "CONTRACTOR" => Some(EmploymentType::Contractor),
+            "TEMPORARY" => Some(EmploymentType::Temporary),
+            "INTERN" => Some(EmploymentType::Intern),
+            "VOLUNTEER" => Some(EmploymentType::Volunteer),
+            "PERDIEM" => Some(EmploymentType::PerDiem),
+            "TASKBASED" => Some(EmploymentType::TaskBased),
+            "MICROTASK" => Some(EmploymentType::MicroTask),
+            "OTHER" => Some(EmploymentType::Other),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum JobLocationType {
@@ -218,7 +390,36 @@ pub enum JobLocationType {
     Hybrid,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl JobLocationType {
+    /// NIP-39993 controlled vocabulary value (e.g. "ON_SITE")
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobLocationType::Telecommute => "TELECOMMUTE",
+            JobLocationType::OnSite => "ON_SITE",
+            JobLocationType::Hybrid => "HYBRID",
+        }
+    }
+
+    /// Parse a tag value, ignoring case and separators
+    pub fn parse(s: &str) -> Option<Self> {
+        match vocab_key(s).as_str() {
+            "TELECOMMUTE" | "REMOTE" => Some(JobLocationType::Telecommute),
+            "ONSITE" => Some(JobLocationType::OnSite),
+            "HYBRID" => Some(JobLocationType::Hybrid),
+            _ => None,
+        }
+    }
+}
+
+/// Uppercase with '_', '-' and spaces removed, for lenient vocabulary matching
+fn vocab_key(s: &str) -> String {
+    s.chars()
+        .filter(|c| !matches!(c, '_' | '-' | ' '))
+        .collect::<String>()
+        .to_uppercase()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct BaseSalary {
     #[serde(rename = "@type")]
     pub schema_type: String,
@@ -227,14 +428,14 @@ pub struct BaseSalary {
     pub value: MonetaryValue,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(untagged)]
 pub enum MonetaryValue {
     Single(MonetaryAmountDistribution),
     Range(QuantitativeValue),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct MonetaryAmountDistribution {
     #[serde(rename = "@type")]
     pub schema_type: String,
@@ -247,7 +448,7 @@ pub struct MonetaryAmountDistribution {
     pub percentile90: Option<f64>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct QuantitativeValue {
     #[serde(rename = "@type")]
     pub schema_type: String,
@@ -257,14 +458,14 @@ pub struct QuantitativeValue {
     pub unit_text: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ApplicantLocationRequirement {
     #[serde(rename = "@type")]
     pub schema_type: String,
     pub name: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ExperienceRequirement {
     #[serde(rename = "@type")]
     pub schema_type: String,
@@ -287,6 +488,8 @@ impl JobListing {
         Self {
             context: "https://schema.org".to_string(),
             schema_type: "JobPosting".to_string(),
+            content_type: Self::default_content_type(),
+            version: Self::default_version(),
             identifier,
             title,
             description,
@@ -299,6 +502,7 @@ impl JobListing {
                 url: None,
                 logo: None,
                 same_as: None,
+                nostr_pubkey: None,
             },
             job_location: vec![],
             employment_type: vec![],
@@ -323,12 +527,29 @@ impl JobListing {
             apply_via_nostr: None,
             lightning_address: None,
             nip05_verified: None,
+            status: None,
+            payment_proof: None,
             extra: HashMap::new(),
             translations: None,
         }
     }
 
+    // Legacy content without a header is a version 1 job
+    fn default_content_type() -> String {
+        "job".to_string()
+    }
+
+    fn default_version() -> u32 {
+        1
+    }
+
     pub fn validate(&self) -> Result<(), ValidationError> {
+        if self.content_type != "job" {
+            return Err(ValidationError::InvalidConfiguration("job content type must be \"job\""));
+        }
+        if Some(self.version) != crate::versioning::supported_version("job") {
+            return Err(ValidationError::InvalidConfiguration("unsupported job content version"));
+        }
         if self.identifier.is_empty() {
             return Err(ValidationError::MissingField("identifier"));
         }
@@ -353,17 +574,16 @@ impl JobListing {
         }
 
         // Validate AI agent fields are only used with AIAgent worker type
-        if !self.eligible_worker_type.contains(&EligibleWorkerType::AIAgent) {
-            if self.response_time_max.is_some()
+        if !self.eligible_worker_type.contains(&EligibleWorkerType::AIAgent)
+            && (self.response_time_max.is_some()
                 || self.accuracy_min.is_some()
                 || self.throughput_min.is_some()
                 || self.interface_type.is_some()
-                || self.protocol.is_some()
-            {
-                return Err(ValidationError::InvalidConfiguration(
-                    "AI agent fields require EligibleWorkerType::AIAgent",
-                ));
-            }
+                || self.protocol.is_some())
+        {
+            return Err(ValidationError::InvalidConfiguration(
+                "AI agent fields require EligibleWorkerType::AIAgent",
+            ));
         }
 
         Ok(())
@@ -513,11 +733,21 @@ impl JobListingBuilder {
         self
     }
 
+    pub fn org_pubkey(mut self, pubkey: String) -> Self {
+        self.job.hiring_organization.nostr_pubkey = Some(pubkey);
+        self
+    }
+
     pub fn lightning_address(mut self, address: String) -> Self {
         self.job.lightning_address = Some(address);
         self
     }
 
+    pub fn payment_proof(mut self, proof: PaymentProof) -> Self {
+        self.job.payment_proof = Some(proof);
+        self
+    }
+
     pub fn translations(mut self, translations: HashMap<String, TranslatedJob>) -> Self {
         self.job.translations = Some(translations);
         self
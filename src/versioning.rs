@@ -0,0 +1,176 @@
+// src/versioning.rs
+// Content `type`/`version` handling for the NIP-39993 event family
+// Newer payloads are either migrated down to the version this crate reads,
+// or rejected with a typed `SchemaError`
+
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Content version this crate reads and writes, per content `type`
+pub fn supported_version(content_type: &str) -> Option<u32> {
+    match content_type {
+        "job" | "resume" | "skill" => Some(1),
+        _ => None,
+    }
+}
+
+/// Why a content payload can't be read
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaError {
+    UnsupportedType(String),
+    UnsupportedVersion {
+        content_type: String,
+        version: u32,
+        supported: u32,
+    },
+    Migration {
+        content_type: String,
+        from_version: u32,
+        reason: String,
+    },
+}
+
+impl std::fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            SchemaError::UnsupportedType(content_type) => {
+                write!(f, "Unsupported content type: {}", content_type)
+            }
+            SchemaError::UnsupportedVersion { content_type, version, supported } => write!(
+                f,
+                "Unsupported {} version {} (supported: {})",
+                content_type, version, supported
+            ),
+            SchemaError::Migration { content_type, from_version, reason } => write!(
+                f,
+                "Failed to migrate {} from version {}: {}",
+                content_type, from_version, reason
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SchemaError {}
+
+type Migration = Box<dyn Fn(Value) -> Result<Value, String> + Send + Sync>;
+
+/// Migrations that rewrite a payload of one version into the previous one.
+/// Steps chain, so a v3 payload goes v3 → v2 → v1 when both are registered.
+#[derive(Default)]
+pub struct MigrationRegistry {
+    migrations: HashMap<(String, u32), Migration>,
+}
+
+impl MigrationRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the step from `from_version` to `from_version - 1`.
+    /// The registry updates the `version` field after each step.
+    pub fn register<F>(&mut self, content_type: &str, from_version: u32, migration: F) -> &mut Self
+    where
+        F: Fn(Value) -> Result<Value, String> + Send + Sync + 'static,
+    {
+        self.migrations
+            .insert((content_type.to_string(), from_version), Box::new(migration));
+        self
+    }
+
+    /// Bring `value` to the supported version of `content_type`.
+    /// A missing `type` is taken as `content_type`, a missing `version` as 1.
+    pub fn upgrade(&self, content_type: &str, mut value: Value) -> Result<Value, SchemaError> {
+        let found = value
+            .get("type")
+            .and_then(Value::as_str)
+            .unwrap_or(content_type)
+            .to_string();
+        if found != content_type {
+            return Err(SchemaError::UnsupportedType(found));
+        }
+        let supported = supported_version(content_type)
+            .ok_or_else(|| SchemaError::UnsupportedType(content_type.to_string()))?;
+        let mut version = value
+            .get("version")
+            .and_then(Value::as_u64)
+            .map_or(1, |v| v.min(u32::MAX as u64) as u32);
+
+        if version == 0 {
+            return Err(SchemaError::UnsupportedVersion {
+                content_type: found,
+                version,
+                supported,
+            });
+        }
+        while version > supported {
+            let migration = self
+                .migrations
+                .get(&(found.clone(), version))
+                .ok_or_else(|| SchemaError::UnsupportedVersion {
+                    content_type: found.clone(),
+                    version,
+                    supported,
+                })?;
+            value = migration(value).map_err(|reason| SchemaError::Migration {
+                content_type: found.clone(),
+                from_version: version,
+                reason,
+            })?;
+            version -= 1;
+            if let Some(object) = value.as_object_mut() {
+                object.insert("version".to_string(), Value::from(version));
+            }
+        }
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_rejects_unknown_version_and_type() {
+        let registry = MigrationRegistry::new();
+        assert_eq!(
+            registry.upgrade("job", json!({"type": "job", "version": 2})),
+            Err(SchemaError::UnsupportedVersion {
+                content_type: "job".to_string(),
+                version: 2,
+                supported: 1,
+            })
+        );
+        assert_eq!(
+            registry.upgrade("job", json!({"type": "resume", "version": 1})),
+            Err(SchemaError::UnsupportedType("resume".to_string()))
+        );
+        // Headerless payloads are read as the expected type, version 1
+        assert!(registry.upgrade("job", json!({"title": "Dev"})).is_ok());
+    }
+
+    #[test]
+    fn test_chained_migrations() {
+        let mut registry = MigrationRegistry::new();
+        registry
+            .register("job", 3, |mut v| {
+                v["title"] = v["headline"].take();
+                Ok(v)
+            })
+            .register("job", 2, |mut v| {
+                v.as_object_mut().unwrap().remove("headline");
+                Ok(v)
+            });
+
+        let upgraded = registry
+            .upgrade("job", json!({"type": "job", "version": 3, "headline": "Dev"}))
+            .unwrap();
+        assert_eq!(upgraded, json!({"type": "job", "version": 1, "title": "Dev"}));
+
+        registry.register("job", 4, |_| Err("missing title".to_string()));
+        assert!(matches!(
+            registry.upgrade("job", json!({"type": "job", "version": 4})),
+            Err(SchemaError::Migration { from_version: 4, .. })
+        ));
+    }
+}